@@ -0,0 +1,14 @@
+-- Task state for the PostgreSQL StateManager backend.
+-- The full serialized TaskState lives in `state`; status and owner are
+-- duplicated into their own columns so they can be indexed and filtered.
+CREATE TABLE IF NOT EXISTS tasks (
+    id TEXT PRIMARY KEY,
+    status TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    state JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS tasks_status_idx ON tasks (status);
+CREATE INDEX IF NOT EXISTS tasks_owner_idx ON tasks (owner);
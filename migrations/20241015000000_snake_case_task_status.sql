@@ -0,0 +1,4 @@
+-- TaskStatus now serializes as snake_case; rewrite the indexed status
+-- column written with the old variant names. The `state` JSON keeps its
+-- old spelling, which still deserializes.
+UPDATE tasks SET status = lower(status) WHERE status <> lower(status);
@@ -1,4 +1,5 @@
 use thiserror::Error;
+use crate::build::CommandOutput;
 use crate::state::error::StateError;
 
 #[derive(Debug, Error)]
@@ -6,12 +7,46 @@ pub enum BuildError {
     #[error("Invalid command: {0}")]
     InvalidCommand(String),
 
-    #[error("Command failed: {0}")]
-    CommandFailed(String),
+    #[error("Command failed: {}", .0.stderr)]
+    CommandFailed(Box<CommandOutput>),
+
+    #[error("Command {stream} is not valid UTF-8")]
+    InvalidUtf8 { stream: &'static str, bytes: Vec<u8> },
 
     #[error("State error: {0}")]
     StateError(#[from] StateError),
 
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
+
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] serde_json::Error),
+
+    /// Creating a project's files from its config failed; shows the full context chain
+    #[error("{0:#}")]
+    Scaffold(anyhow::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    fn load_task() -> Result<(), StateError> {
+        Err(StateError::TaskNotFound("missing-task".to_string()))
+    }
+
+    fn execute() -> Result<(), BuildError> {
+        load_task()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_state_error_propagates_through_question_mark() {
+        let err = execute().unwrap_err();
+
+        assert!(matches!(err, BuildError::StateError(StateError::TaskNotFound(ref id)) if id == "missing-task"));
+        assert_eq!(err.to_string(), "State error: Task not found: missing-task");
+        assert_eq!(err.source().unwrap().to_string(), "Task not found: missing-task");
+    }
 }
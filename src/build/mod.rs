@@ -1,123 +1,480 @@
-use std::path::PathBuf;
-use std::fs::{self, File};
-use std::io::Write;
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::fs;
 use tokio::process::Command;
 use serde_json::Value;
 use anyhow::{Context, Result};
-use jsonschema::JSONSchema;
 
+use crate::prompt::storage::{SchemaValidator, Storage};
+use crate::prompt::ProjectConfig;
+use crate::prompt::project_generation::GenerationProjectType;
 use crate::state::types::{TaskId, TaskState, TaskStatus};
 use crate::state::StateManager;
+use crate::state::error::StateError;
 
 pub mod error;
+pub mod types;
+pub mod verify;
+pub mod versions;
 pub use error::BuildError;
+pub use verify::verification_commands;
+pub use versions::CrateVersionResolver;
+
+/// How `BuildManager::execute_all` reacts to a failing task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FailureMode {
+    /// Stop at the first failure; tasks not yet started are skipped
+    #[default]
+    FailFast,
+    /// Run every task whose dependencies succeeded and report all failures
+    KeepGoing,
+}
+
+/// Outcome of `BuildManager::execute_all`
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    pub succeeded: Vec<TaskId>,
+    pub failures: Vec<(TaskId, BuildError)>,
+    /// Tasks not run because a dependency failed or execution stopped early
+    pub skipped: Vec<TaskId>,
+}
+
+impl BatchReport {
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty() && self.skipped.is_empty()
+    }
+}
+
+/// What `BuildManager::execute_task_dry_run` found when resolving a task's command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DryRunReport {
+    pub task_id: TaskId,
+    pub program: String,
+    pub args: Vec<String>,
+    pub working_dir: PathBuf,
+    pub working_dir_exists: bool,
+    /// Where the program was found, if it was
+    pub resolved_program: Option<PathBuf>,
+}
+
+impl DryRunReport {
+    pub fn program_found(&self) -> bool {
+        self.resolved_program.is_some()
+    }
+
+    /// Whether `execute_task` could at least start the command
+    pub fn is_runnable(&self) -> bool {
+        self.program_found() && self.working_dir_exists
+    }
+}
+
+/// How task command output is turned into text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputDecoding {
+    /// Replace invalid UTF-8 with U+FFFD
+    #[default]
+    Lossy,
+    /// Decode lossily, but also keep the undecoded bytes
+    Raw,
+    /// Fail with `BuildError::InvalidUtf8` when output is not valid UTF-8. A failed
+    /// command is still reported as `CommandFailed`, with its output decoded as `Raw`.
+    Strict,
+}
+
+/// Captured output of a task command
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandOutput {
+    /// Exit code, if the command was not killed by a signal
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    /// Undecoded stdout, kept with `OutputDecoding::Raw`
+    pub raw_stdout: Option<Vec<u8>>,
+    /// Undecoded stderr, kept with `OutputDecoding::Raw`
+    pub raw_stderr: Option<Vec<u8>>,
+}
+
+impl CommandOutput {
+    pub fn decode(output: &std::process::Output, decoding: OutputDecoding) -> Result<Self, BuildError> {
+        let decode = |stream: &'static str, bytes: &[u8]| match decoding {
+            OutputDecoding::Strict => String::from_utf8(bytes.to_vec())
+                .map_err(|e| BuildError::InvalidUtf8 { stream, bytes: e.into_bytes() }),
+            OutputDecoding::Lossy | OutputDecoding::Raw => Ok(String::from_utf8_lossy(bytes).into_owned()),
+        };
+        let keep_raw = decoding == OutputDecoding::Raw;
+
+        Ok(Self {
+            exit_code: output.status.code(),
+            stdout: decode("stdout", &output.stdout)?,
+            stderr: decode("stderr", &output.stderr)?,
+            raw_stdout: keep_raw.then(|| output.stdout.clone()),
+            raw_stderr: keep_raw.then(|| output.stderr.clone()),
+        })
+    }
+}
+
+/// Config files that hold credentials and should only be readable by their owner
+const SECRET_FILES: &[&str] = &["database.toml", "jwt.toml", ".env"];
+
+/// Permissions for `SECRET_FILES`: readable and writable by the owner only
+const SECRET_FILE_MODE: u32 = 0o600;
+
+fn is_secret_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| SECRET_FILES.contains(&name))
+}
+
+/// Permissions a generated file gets unless the config overrides them
+fn default_file_mode(path: &Path) -> Option<u32> {
+    if is_secret_file(path) {
+        Some(SECRET_FILE_MODE)
+    } else if path.extension().is_some_and(|ext| ext == "sh") {
+        Some(0o755)
+    } else {
+        None
+    }
+}
+
+/// Parse an octal permission string: `"600"`, `"0600"` or `"0o600"`
+fn parse_file_mode(mode: &str) -> Option<u32> {
+    let digits = mode.trim().trim_start_matches("0o");
+    u32::from_str_radix(digits, 8).ok().filter(|mode| *mode <= 0o7777)
+}
+
+#[cfg(unix)]
+fn set_file_mode(path: &Path, mode: u32) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn set_file_mode(_path: &Path, _mode: u32) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Write a credential file that is never readable by others, even for a moment: new
+/// files are created with `SECRET_FILE_MODE` and existing ones restricted before writing
+#[cfg(unix)]
+fn write_secret_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(SECRET_FILE_MODE)
+        .open(path)?;
+    file.set_permissions(fs::Permissions::from_mode(SECRET_FILE_MODE))?;
+    file.write_all(contents)
+}
+
+#[cfg(not(unix))]
+fn write_secret_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    fs::write(path, contents)
+}
+
+/// Resolve a `file_modes` key against the project, refusing absolute paths and `..`
+/// so a generated config cannot change permissions outside the project
+fn project_relative_path(project_dir: &Path, file: &str) -> Result<PathBuf> {
+    let relative = Path::new(file);
+    let escapes = relative.components().any(|component| {
+        !matches!(component, std::path::Component::Normal(_) | std::path::Component::CurDir)
+    });
+    if escapes {
+        anyhow::bail!("file_modes path must be relative and stay inside the project: {}", file);
+    }
+    Ok(project_dir.join(relative))
+}
+
+/// What `BuildManager::scaffold_project` created under `root`. Paths are joined onto
+/// `root`; a file written more than once is listed once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScaffoldReport {
+    pub root: PathBuf,
+    pub created_dirs: Vec<PathBuf>,
+    pub created_files: Vec<PathBuf>,
+    /// Paths left alone, with the reason
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+impl ScaffoldReport {
+    fn new(root: PathBuf) -> Self {
+        Self { root, ..Default::default() }
+    }
+
+    /// Create `dir` and any missing parents, recording each one that didn't exist
+    fn create_dir(&mut self, dir: &Path) -> std::io::Result<()> {
+        let missing: Vec<PathBuf> = dir.ancestors()
+            .take_while(|ancestor| !ancestor.exists())
+            .map(Path::to_path_buf)
+            .collect();
+        fs::create_dir_all(dir)?;
+        self.created_dirs.extend(missing.into_iter().rev());
+        Ok(())
+    }
+
+    fn write_file(&mut self, path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            self.create_dir(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        if is_secret_file(path) {
+            write_secret_file(path, contents.as_ref())?;
+        } else {
+            fs::write(path, contents)?;
+        }
+        if !self.created_files.iter().any(|created| created == path) {
+            self.created_files.push(path.to_path_buf());
+        }
+        Ok(())
+    }
+
+    fn skip(&mut self, path: PathBuf, reason: &str) {
+        self.skipped.push((path, reason.to_string()));
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct BuildManager {
     pub state_manager: StateManager,
     working_dir: PathBuf,
+    strict: bool,
+    recommend_dependencies: bool,
+    output_decoding: OutputDecoding,
 }
 
 impl BuildManager {
     pub fn new(state_manager: StateManager, working_dir: PathBuf) -> Self {
         Self { 
             state_manager, 
-            working_dir 
+            working_dir,
+            strict: false,
+            recommend_dependencies: false,
+            output_decoding: OutputDecoding::default(),
         }
     }
 
-    /// Validate JSON against a given schema
-    pub fn validate_json(schema: &Value, data: &Value) -> Result<()> {
-        // Create a 'static reference by leaking the schema
-        let schema_static = Box::leak(Box::new(schema.clone()));
-        let compiled_schema = JSONSchema::compile(schema_static)
-            .context("Failed to compile JSON schema")?;
+    /// Choose how command output is decoded, e.g. `OutputDecoding::Raw` to inspect the
+    /// exact bytes a failing tool wrote
+    pub fn with_output_decoding(mut self, decoding: OutputDecoding) -> Self {
+        self.output_decoding = decoding;
+        self
+    }
+
+    /// Fill in dependencies recommended for the project type when the config declares none
+    pub fn with_recommended_dependencies(mut self, enabled: bool) -> Self {
+        self.recommend_dependencies = enabled;
+        self
+    }
 
-        if let Err(errors) = compiled_schema.validate(data) {
-            let error_messages: Vec<String> = errors
-                .map(|error| error.to_string())
-                .collect();
-            anyhow::bail!("JSON validation failed: {}", error_messages.join(", "))
+    /// Merge `GenerationProjectType::recommended_dependencies` into the production
+    /// dependencies of `config` when it specifies none
+    fn apply_recommended_dependencies(config: &mut Value) {
+        let has_production = config["dependencies"]["production"]
+            .as_object()
+            .is_some_and(|deps| !deps.is_empty());
+        if has_production {
+            return;
         }
 
-        Ok(())
+        let project_type = match serde_json::from_value::<GenerationProjectType>(config["project_type"].clone()) {
+            Ok(project_type) => project_type,
+            Err(_) => return,
+        };
+        let recommended = project_type.recommended_dependencies(config["language"].as_str().unwrap_or(""));
+        if recommended.is_empty() {
+            return;
+        }
+
+        tracing::info!("Using recommended dependencies for {}: {:?}", project_type, recommended.keys());
+        if !config["dependencies"].is_object() {
+            config["dependencies"] = serde_json::json!({});
+        }
+        config["dependencies"]["production"] = serde_json::json!(recommended);
+    }
+
+    /// In strict mode, scaffolding fails instead of substituting defaults for missing fields
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// List the configuration fields scaffolding would have to fill with a default value
+    pub fn defaulted_fields(config: &Value) -> Vec<String> {
+        let mut fields = Vec::new();
+        for field in ["project_name", "language", "description"] {
+            if config[field].as_str().is_none() {
+                fields.push(field.to_string());
+            }
+        }
+
+        for env in ["production", "development"] {
+            if let Some(deps) = config["dependencies"][env].as_object() {
+                for (name, version) in deps {
+                    if version.as_str().is_none() {
+                        fields.push(format!("dependencies.{}.{}", env, name));
+                    }
+                }
+            }
+        }
+
+        fields
+    }
+
+    /// Validate JSON against a given schema
+    pub fn validate_json(schema: &Value, data: &Value) -> Result<()> {
+        SchemaValidator::new(schema)?.validate(data)
     }
 
     // New method to scaffold a project from JSON configuration
-    pub fn scaffold_project(&self, project_config: &str) -> Result<PathBuf> {
+    pub fn scaffold_project(&self, project_config: &str) -> Result<ScaffoldReport> {
+        let span = tracing::info_span!("scaffold_project", project_name = tracing::field::Empty);
+        let _enter = span.enter();
+
         // Parse the JSON configuration
-        let config: Value = serde_json::from_str(project_config)
+        let mut config: Value = serde_json::from_str(project_config)
             .context("Failed to parse project configuration")?;
 
+        if self.recommend_dependencies {
+            Self::apply_recommended_dependencies(&mut config);
+        }
+
+        if self.strict {
+            let defaulted = Self::defaulted_fields(&config);
+            if !defaulted.is_empty() {
+                anyhow::bail!(
+                    "Strict mode: project configuration is missing fields: {}",
+                    defaulted.join(", ")
+                );
+            }
+        }
+
         // Extract project name
         let project_name = config["project_name"].as_str()
             .unwrap_or("unnamed_project")
             .to_string();
+        span.record("project_name", project_name.as_str());
 
         // Create unique project directory
         let project_dir = self.working_dir.join(format!("{}_{}",
             project_name, 
             std::process::id()  // Add process ID to ensure uniqueness
         ));
-        fs::create_dir_all(&project_dir)?;
+        let mut report = ScaffoldReport::new(project_dir.clone());
+        report.create_dir(&project_dir)?;
 
         // Create directory structure
-        self.create_directory_structure(&project_dir, &config)?;
+        self.create_directory_structure(&project_dir, &config, &mut report)?;
 
         // Create initialization files
-        self.create_initialization_files(&project_dir, &config)?;
+        self.create_initialization_files(&project_dir, &config, &mut report)?;
 
         // Create configuration files
-        self.create_config_files(&project_dir, &config)?;
+        self.create_config_files(&project_dir, &config, &mut report)?;
 
         // Create documentation
-        self.create_documentation(&project_dir, &config)?;
+        self.create_documentation(&project_dir, &config, &mut report)?;
+
+        // Restrict secrets and make scripts executable
+        Self::apply_file_modes(&project_dir, &config, &report)?;
+
+        tracing::info!("Scaffolded project at {}", project_dir.display());
+        Ok(report)
+    }
+
+    /// Scaffold a project from a configuration file, parsed as YAML for `.yaml`/`.yml`
+    /// extensions and as JSON otherwise
+    pub fn scaffold_project_from_path(&self, path: &Path) -> Result<ScaffoldReport> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read project configuration: {}", path.display()))?;
+
+        let config: ProjectConfig = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&contents)
+                .with_context(|| format!("Failed to parse YAML configuration: {}", path.display()))?,
+            _ => serde_json::from_str(&contents)
+                .with_context(|| format!("Failed to parse JSON configuration: {}", path.display()))?,
+        };
+
+        self.scaffold_project(&serde_json::to_string(&config)?)
+    }
+
+    /// Scaffold a project, skipping generation when `idempotency_key` was already used.
+    ///
+    /// Completed generations are recorded in `storage` under `idem-<key>`; replaying a key
+    /// returns the recorded path as long as it still exists on disk.
+    pub fn scaffold_project_idempotent(
+        &self,
+        project_config: &str,
+        storage: &Storage,
+        idempotency_key: Option<&str>,
+    ) -> Result<PathBuf> {
+        let key = match idempotency_key {
+            Some(key) => format!("idem-{}", key),
+            None => return Ok(self.scaffold_project(project_config)?.root),
+        };
+
+        if let Some(existing) = storage.load::<PathBuf>(&key)? {
+            if existing.exists() {
+                tracing::info!("Skipping generation for {}: already scaffolded at {}", key, existing.display());
+                return Ok(existing);
+            }
+        }
 
+        let project_dir = self.scaffold_project(project_config)?.root;
+        storage.store(&key, &project_dir)?;
         Ok(project_dir)
     }
 
-    fn create_directory_structure(&self, project_dir: &PathBuf, config: &Value) -> Result<()> {
-        // Ensure the base directories are created
-        let base_dirs = vec!["src", "tests", "migrations", "config"];
-        for dir in base_dirs {
-            fs::create_dir_all(project_dir.join(dir))?;
+    fn create_directory_structure(&self, project_dir: &Path, config: &Value, report: &mut ScaffoldReport) -> Result<()> {
+        let (dirs, files) = Self::directory_plan(config);
+        for dir in &dirs {
+            report.create_dir(&project_dir.join(dir))?;
+        }
+        for file in &files {
+            let file_path = project_dir.join(file);
+            if file_path.exists() {
+                report.skip(file_path, "already exists");
+            } else {
+                report.write_file(&file_path, "")?;
+            }
         }
 
-        // Create subdirectories and files based on the directory_structure
-        if let Some(dir_structure) = config["directory_structure"].as_object() {
-            for (base_dir, entries) in dir_structure {
-                let base_path = project_dir.join(base_dir);
-                
-                // Create base directory if it doesn't exist
-                fs::create_dir_all(&base_path)?;
-
-                // Create subdirectories and files
-                if let Some(dir_list) = entries.as_array() {
-                    for entry in dir_list {
-                        if let Some(entry_str) = entry.as_str() {
-                            let entry_path = base_path.join(entry_str);
-                            
-                            // Check if it's a directory or a file
-                            if entry_str.contains('/') {
-                                // It's a subdirectory
-                                fs::create_dir_all(&entry_path)?;
-                            } else {
-                                // It's a file
-                                if let Some(parent) = entry_path.parent() {
-                                    fs::create_dir_all(parent)?;
-                                }
-                                File::create(&entry_path)?;
-                            }
-                        }
-                    }
+        Ok(())
+    }
+
+    /// Directories and empty files to create for a project: the base directories unioned
+    /// with every `directory_structure` entry, so keys that name the same directory
+    /// (`src` and `src/`) merge instead of shadowing each other. Entries containing a
+    /// `/` are directories, the rest files; a path that is both is kept as a directory.
+    fn directory_plan(config: &Value) -> (BTreeSet<PathBuf>, BTreeSet<PathBuf>) {
+        let mut dirs: BTreeSet<PathBuf> = ["src", "tests", "migrations", "config"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        let mut files = BTreeSet::new();
+
+        for (base_dir, entries) in config["directory_structure"].as_object().into_iter().flatten() {
+            let base_path = PathBuf::from(base_dir.trim_matches('/'));
+            if !base_path.as_os_str().is_empty() {
+                dirs.insert(base_path.clone());
+            }
+
+            for entry in entries.as_array().into_iter().flatten().filter_map(Value::as_str) {
+                let entry_path = base_path.join(entry.trim_end_matches('/'));
+                if entry.contains('/') {
+                    dirs.insert(entry_path);
+                } else {
+                    files.insert(entry_path);
                 }
             }
         }
 
-        Ok(())
+        files.retain(|file| !dirs.contains(file));
+        (dirs, files)
     }
 
-    fn create_initialization_files(&self, project_dir: &PathBuf, config: &Value) -> Result<()> {
+    fn create_initialization_files(&self, project_dir: &Path, config: &Value, report: &mut ScaffoldReport) -> Result<()> {
         // Determine main file based on language
         let main_file_path = match config["language"].as_str() {
             Some("Rust") => project_dir.join("src/main.rs"),
@@ -126,39 +483,14 @@ impl BuildManager {
             _ => project_dir.join("src/main"),
         };
 
-        // Ensure parent directory exists
-        if let Some(parent) = main_file_path.parent() {
-            std::fs::create_dir_all(parent)
-                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
-        }
-
-        // Create main file with boilerplate content
-        let main_content = match config["language"].as_str() {
-            Some("Rust") => {
-                if config["framework"].as_str() == Some("Rocket") {
-                    r#"#[macro_use] extern crate rocket;
-
-#[get("/")]
-fn index() -> &'static str {
-    "Welcome to TaskMaster!"
-}
-
-#[launch]
-fn rocket() -> _ {
-    rocket::build().mount("/", routes![index])
-}"#
-                } else {
-                    "fn main() {\n    println!(\"Hello, TaskMaster!\");\n}"
-                }
-            },
-            Some("JavaScript") => 
-                "console.log('TaskMaster application started');",
-            Some("Python") => 
-                "def main():\n    print('TaskMaster application started')\n\nif __name__ == '__main__':\n    main()",
-            _ => "// Main application entry point",
+        // Create main file from the configured template or the language's boilerplate
+        let template = match config["main_template"].as_str() {
+            Some(template) => template.to_string(),
+            None => Self::default_main_template(config["language"].as_str(), config["framework"].as_str()).to_string(),
         };
+        let main_content = Self::render_main_template(&template, config);
 
-        std::fs::write(&main_file_path, main_content)
+        report.write_file(&main_file_path, main_content)
             .with_context(|| format!("Failed to write main file: {}", main_file_path.display()))?;
 
         // Create configuration files
@@ -167,23 +499,28 @@ fn rocket() -> _ {
                 if let Some(filename) = config_file.as_str() {
                     let config_path = project_dir.join("config").join(filename);
                     
-                    // Create default content based on filename
-                    let config_content = match filename {
-                        "database.toml" => r#"[database]
+                    // Use the configured template for this file, or default content based on filename
+                    let config_content = match config["config_templates"][filename].as_str() {
+                        Some(template) => template,
+                        None => match filename {
+                            "database.toml" => r#"[database]
 host = "localhost"
 port = 5432
 name = "taskmaster"
 username = "taskmaster_user"
 password = "changeme"
 "#,
-                        "jwt.toml" => r#"[jwt]
+                            "jwt.toml" => r#"[jwt]
 secret_key = "your_secret_key_here"
 expiration_hours = 24
 "#,
-                        _ => "# Configuration file",
+                            _ => "# Configuration file",
+                        },
                     };
+                    let config_content = resolve_env_placeholders(config_content)
+                        .with_context(|| format!("Failed to resolve placeholders in {}", filename))?;
 
-                    std::fs::write(&config_path, config_content)
+                    report.write_file(&config_path, config_content)
                         .with_context(|| format!("Failed to write config file: {}", config_path.display()))?;
                 }
             }
@@ -192,7 +529,75 @@ expiration_hours = 24
         Ok(())
     }
 
-    fn create_config_files(&self, project_dir: &PathBuf, config: &Value) -> Result<()> {
+    /// Set Unix permissions on the generated files: `0600` for files holding secrets,
+    /// `0755` for shell scripts, unless the config's `file_modes` map (project-relative
+    /// path to an octal string such as `"0640"`) says otherwise. A no-op off Unix.
+    fn apply_file_modes(project_dir: &Path, config: &Value, report: &ScaffoldReport) -> Result<()> {
+        let mut modes: Vec<(PathBuf, u32)> = report.created_files.iter()
+            .filter_map(|file| default_file_mode(file).map(|mode| (file.clone(), mode)))
+            .collect();
+
+        for (file, mode) in config["file_modes"].as_object().into_iter().flatten() {
+            let mode = mode.as_str()
+                .and_then(parse_file_mode)
+                .with_context(|| format!("Invalid file mode for {}: {} (expected an octal string like \"0644\")", file, mode))?;
+            let path = project_relative_path(project_dir, file)?;
+            modes.retain(|(existing, _)| existing != &path);
+            modes.push((path, mode));
+        }
+
+        for (path, mode) in modes {
+            set_file_mode(&path, mode)
+                .with_context(|| format!("Failed to set permissions {:o} on {}", mode, path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Boilerplate for the main file; `{{project_name}}` and `{{description}}` are interpolated
+    fn default_main_template(language: Option<&str>, framework: Option<&str>) -> &'static str {
+        match language {
+            Some("Rust") => {
+                if framework == Some("Rocket") {
+                    r#"//! {{description}}
+
+#[macro_use] extern crate rocket;
+
+#[get("/")]
+fn index() -> &'static str {
+    "Welcome to {{project_name}}!"
+}
+
+#[launch]
+fn rocket() -> _ {
+    rocket::build().mount("/", routes![index])
+}"#
+                } else {
+                    "//! {{description}}\n\nfn main() {\n    println!(\"Hello, {{project_name}}!\");\n}"
+                }
+            },
+            Some("JavaScript") => 
+                "// {{description}}\nconsole.log('{{project_name}} application started');",
+            Some("Python") => 
+                "\"\"\"{{description}}\"\"\"\n\n\ndef main():\n    print('{{project_name}} application started')\n\nif __name__ == '__main__':\n    main()",
+            _ => "// {{project_name}}: main application entry point",
+        }
+    }
+
+    fn render_main_template(template: &str, config: &Value) -> String {
+        let project_name = config["project_name"].as_str().unwrap_or("unnamed_project");
+        // Keep the description on one line so it stays inside the comment it is placed in
+        let description = config["description"].as_str()
+            .unwrap_or("")
+            .lines()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        template
+            .replace("{{project_name}}", project_name)
+            .replace("{{description}}", &description)
+    }
+
+    fn create_config_files(&self, project_dir: &Path, config: &Value, report: &mut ScaffoldReport) -> Result<()> {
         match config["language"].as_str() {
             Some("Rust") => {
                 let cargo_toml_path = project_dir.join("Cargo.toml");
@@ -201,18 +606,11 @@ expiration_hours = 24
                 let mut prod_deps = String::new();
                 let mut dev_deps = String::new();
 
-                if let Some(dependencies) = config["dependencies"].as_object() {
-                    if let Some(production) = dependencies.get("production").and_then(|d| d.as_object()) {
-                        for (name, version) in production {
-                            prod_deps.push_str(&format!("{} = \"{}\"\n", name, version.as_str().unwrap_or("latest")));
-                        }
-                    }
-
-                    if let Some(development) = dependencies.get("development").and_then(|d| d.as_object()) {
-                        for (name, version) in development {
-                            dev_deps.push_str(&format!("{} = \"{}\"\n", name, version.as_str().unwrap_or("latest")));
-                        }
-                    }
+                for (name, version) in sorted_entries(&config["dependencies"]["production"]) {
+                    prod_deps.push_str(&format!("{} = \"{}\"\n", name, version.as_str().unwrap_or("latest")));
+                }
+                for (name, version) in sorted_entries(&config["dependencies"]["development"]) {
+                    dev_deps.push_str(&format!("{} = \"{}\"\n", name, version.as_str().unwrap_or("latest")));
                 }
 
                 let cargo_toml_content = format!(
@@ -231,7 +629,7 @@ edition = "2021"
                     dev_deps
                 );
 
-                std::fs::write(&cargo_toml_path, cargo_toml_content)
+                report.write_file(&cargo_toml_path, cargo_toml_content)
                     .with_context(|| format!("Failed to write Cargo.toml: {}", cargo_toml_path.display()))?;
             },
             Some("Python") => {
@@ -245,7 +643,7 @@ edition = "2021"
                                 .join("\n"))
                             .unwrap_or_default();
                         
-                        std::fs::write(project_dir.join("requirements.txt"), requirements)
+                        report.write_file(&project_dir.join("requirements.txt"), requirements)
                             .with_context(|| "Failed to write requirements.txt")?;
                     }
 
@@ -257,21 +655,42 @@ edition = "2021"
                                 .join("\n"))
                             .unwrap_or_default();
                         
-                        std::fs::write(project_dir.join("dev-requirements.txt"), dev_requirements)
+                        report.write_file(&project_dir.join("dev-requirements.txt"), dev_requirements)
                             .with_context(|| "Failed to write dev-requirements.txt")?;
                     }
                 }
             },
+            Some("JavaScript") | Some("TypeScript") => {
+                let package_json_path = project_dir.join("package.json");
+
+                // Scripts keep the order they were declared in
+                let scripts = config["build_system"]["scripts"].as_object()
+                    .or_else(|| config["build_config"]["scripts"].as_object())
+                    .cloned()
+                    .unwrap_or_default();
+
+                let package_json = serde_json::json!({
+                    "name": config["project_name"].as_str().unwrap_or("unnamed_project"),
+                    "version": "0.1.0",
+                    "description": config["description"].as_str().unwrap_or(""),
+                    "main": "src/app.js",
+                    "scripts": scripts,
+                    "dependencies": sorted_object(&config["dependencies"]["production"]),
+                    "devDependencies": sorted_object(&config["dependencies"]["development"]),
+                });
+
+                report.write_file(&package_json_path, serde_json::to_string_pretty(&package_json)?)
+                    .with_context(|| format!("Failed to write package.json: {}", package_json_path.display()))?;
+            },
             _ => {}
         }
 
         Ok(())
     }
 
-    fn create_documentation(&self, project_dir: &PathBuf, config: &Value) -> Result<()> {
+    fn create_documentation(&self, project_dir: &Path, config: &Value, report: &mut ScaffoldReport) -> Result<()> {
         // Create README.md
         let readme_path = project_dir.join("README.md");
-        let mut readme = File::create(&readme_path)?;
 
         // Write project overview
         let readme_content = format!(
@@ -282,27 +701,112 @@ edition = "2021"
             config["recommendations"].as_array().unwrap_or(&vec![])
         );
 
-        readme.write_all(readme_content.as_bytes())?;
+        report.write_file(&readme_path, readme_content)?;
 
         Ok(())
     }
 
-    pub async fn execute_task(&self, task_id: &TaskId) -> Result<(), BuildError> {
+    /// Base directory task commands run in
+    pub fn working_dir(&self) -> &Path {
+        &self.working_dir
+    }
+
+    pub fn set_working_dir(&mut self, working_dir: PathBuf) {
+        self.working_dir = working_dir;
+    }
+
+    /// Run a task's command and mark the task completed, returning what the command printed
+    #[tracing::instrument(skip(self), fields(task_id = %task_id))]
+    pub async fn execute_task(&self, task_id: &TaskId) -> Result<CommandOutput, BuildError> {
+        self.execute_task_in(task_id, &self.working_dir).await
+    }
+
+    /// Execute a task with `dir` standing in for the manager's working directory, e.g.
+    /// to run the same task in several scaffolded projects
+    pub async fn execute_task_in(&self, task_id: &TaskId, dir: &Path) -> Result<CommandOutput, BuildError> {
         // Get task from state manager
-        let task = self.state_manager.get_task(task_id).await
-            .map_err(BuildError::StateError)?;
+        let task = self.state_manager.get_task(task_id).await?;
+        tracing::info!("Executing task command: {} in {}", task.metadata.name, dir.display());
 
         // Execute task command
-        self.execute_command(&task).await?;
+        let output = self.execute_command(&task, dir).await?;
 
         // Update task status to completed
-        self.state_manager.update_task_status(task_id, TaskStatus::Completed).await
-            .map_err(BuildError::StateError)?;
+        self.state_manager.update_task_status(task_id, TaskStatus::Completed).await?;
 
-        Ok(())
+        Ok(output)
+    }
+
+    /// Resolve a task's command the way `execute_task` would, without running it or
+    /// touching the task's status
+    pub async fn execute_task_dry_run(&self, task_id: &TaskId) -> Result<DryRunReport, BuildError> {
+        let task = self.state_manager.get_task(task_id).await?;
+        let mut parts = task.metadata.name.split_whitespace().map(str::to_string);
+        let program = parts
+            .next()
+            .ok_or_else(|| BuildError::InvalidCommand("Empty command".to_string()))?;
+
+        let working_dir = task_working_dir(&task, &self.working_dir);
+        Ok(DryRunReport {
+            task_id: task_id.clone(),
+            resolved_program: find_program(&program, &working_dir),
+            args: parts.collect(),
+            working_dir_exists: working_dir.is_dir(),
+            working_dir,
+            program,
+        })
+    }
+
+    /// Execute a batch of tasks in dependency order. Tasks whose dependencies within the
+    /// batch did not succeed are skipped; `mode` decides whether independent tasks still run
+    /// after a failure.
+    pub async fn execute_all(&self, task_ids: &[TaskId], mode: FailureMode) -> Result<BatchReport, BuildError> {
+        // Dependencies on tasks outside the batch are not this batch's concern
+        let batch: HashSet<TaskId> = task_ids.iter().cloned().collect();
+        let mut pending = Vec::new();
+        for id in task_ids {
+            let task = self.state_manager.get_task(id).await?;
+            let mut dependencies: HashSet<TaskId> = task.metadata.dependencies.iter().cloned().collect();
+            dependencies.extend(self.state_manager.get_task_dependencies(id).await?);
+            dependencies.retain(|dep| batch.contains(dep));
+            pending.push((id.clone(), dependencies));
+        }
+
+        let mut report = BatchReport::default();
+        let mut stopped = false;
+
+        while !pending.is_empty() {
+            let resolved = |dep: &TaskId| {
+                report.succeeded.contains(dep)
+                    || report.skipped.contains(dep)
+                    || report.failures.iter().any(|(id, _)| id == dep)
+            };
+            let Some(index) = pending.iter().position(|(_, deps)| deps.iter().all(resolved)) else {
+                let remaining: Vec<String> = pending.iter().map(|(id, _)| id.to_string()).collect();
+                return Err(StateError::CircularDependency(remaining.join(", ")).into());
+            };
+            let (task_id, dependencies) = pending.remove(index);
+
+            if stopped || !dependencies.iter().all(|dep| report.succeeded.contains(dep)) {
+                report.skipped.push(task_id);
+                continue;
+            }
+
+            match self.execute_task(&task_id).await {
+                Ok(_) => report.succeeded.push(task_id),
+                Err(e) => {
+                    tracing::warn!("Task {} failed: {}", task_id, e);
+                    self.state_manager.update_task_status(&task_id, TaskStatus::Failed).await?;
+                    report.failures.push((task_id, e));
+                    stopped = mode == FailureMode::FailFast;
+                }
+            }
+        }
+
+        Ok(report)
     }
 
-    async fn execute_command(&self, task: &TaskState) -> Result<(), BuildError> {
+    async fn execute_command(&self, task: &TaskState, base_dir: &Path) -> Result<CommandOutput, BuildError> {
         let command = &task.metadata.name;
         let args: Vec<&str> = command.split_whitespace().collect();
 
@@ -312,24 +816,110 @@ edition = "2021"
 
         let output = Command::new(args[0])
             .args(&args[1..])
-            .current_dir(&self.working_dir)
+            .current_dir(task_working_dir(task, base_dir))
             .output()
             .await?;
 
         if !output.status.success() {
-            let error_message = String::from_utf8_lossy(&output.stderr).to_string();
-            return Err(BuildError::CommandFailed(error_message));
+            // The failure matters more than its encoding, so keep undecodable output raw
+            let decoded = CommandOutput::decode(&output, self.output_decoding)
+                .or_else(|_| CommandOutput::decode(&output, OutputDecoding::Raw))?;
+            return Err(BuildError::CommandFailed(Box::new(decoded)));
         }
 
-        Ok(())
+        CommandOutput::decode(&output, self.output_decoding)
     }
 }
 
+/// Directory a task's command runs in: its own `working_dir` joined onto `base_dir` when
+/// relative, used as-is when absolute, or `base_dir` when unset
+fn task_working_dir(task: &TaskState, base_dir: &Path) -> PathBuf {
+    match &task.metadata.working_dir {
+        Some(dir) if dir.is_absolute() => dir.clone(),
+        Some(dir) => base_dir.join(dir),
+        None => base_dir.to_path_buf(),
+    }
+}
+
+/// Entries of a JSON object sorted by key, so generated manifests don't depend on the
+/// order the config listed them in; empty for anything but an object
+fn sorted_entries(value: &Value) -> Vec<(&String, &Value)> {
+    let mut entries: Vec<_> = value.as_object().map(|map| map.iter().collect()).unwrap_or_default();
+    entries.sort_by_key(|(a, _)| *a);
+    entries
+}
+
+fn sorted_object(value: &Value) -> serde_json::Map<String, Value> {
+    sorted_entries(value)
+        .into_iter()
+        .map(|(name, version)| (name.clone(), version.clone()))
+        .collect()
+}
+
+/// Locate `program` as the OS would when spawning it: paths containing a separator are
+/// taken relative to `working_dir`, bare names are searched for on `PATH`
+fn find_program(program: &str, working_dir: &Path) -> Option<PathBuf> {
+    if program.contains(std::path::MAIN_SEPARATOR) || program.contains('/') {
+        let path = working_dir.join(program);
+        return is_executable(&path).then_some(path);
+    }
+
+    let search_path = std::env::var_os("PATH")?;
+    std::env::split_paths(&search_path)
+        .map(|dir| dir.join(program))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file() || path.with_extension("exe").is_file()
+}
+
+/// Replace `${VAR}` placeholders with values from the environment. `${VAR:-default}`
+/// falls back to `default` when `VAR` is unset; any other undefined variable is an error.
+pub fn resolve_env_placeholders(content: &str) -> Result<String> {
+    let mut resolved = String::with_capacity(content.len());
+    let mut rest = content;
+
+    while let Some(start) = rest.find("${") {
+        resolved.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find('}')
+            .ok_or_else(|| anyhow::anyhow!("Unterminated placeholder: ${{{}", after))?;
+
+        let placeholder = &after[..end];
+        let (name, default) = match placeholder.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (placeholder, None),
+        };
+
+        match (std::env::var(name), default) {
+            (Ok(value), _) => resolved.push_str(&value),
+            (Err(_), Some(default)) => resolved.push_str(default),
+            (Err(_), None) => anyhow::bail!("Undefined environment variable: {}", name),
+        }
+
+        rest = &after[end + 1..];
+    }
+
+    resolved.push_str(rest);
+    Ok(resolved)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::path::PathBuf;
     use chrono::Utc;
+    use tracing_test::traced_test;
 
     #[tokio::test]
     async fn test_execute_task() -> Result<(), BuildError> {
@@ -350,13 +940,17 @@ mod tests {
                 priority: 1,
                 tags: vec!["test".to_string()],
                 additional_info: std::collections::HashMap::new(),
+                working_dir: None,
             },
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            history: Vec::new(),
         };
 
-        state_manager.create_task(task).await.map_err(BuildError::StateError)?;
-        build_manager.execute_task(&task_id).await?;
+        state_manager.create_task(task).await?;
+        let output = build_manager.execute_task(&task_id).await?;
+        assert_eq!(output.exit_code, Some(0));
+        assert_eq!(output.stdout, "test\n");
 
         Ok(())
     }
@@ -425,7 +1019,7 @@ mod tests {
         std::fs::create_dir_all("./build")?;
 
         // Scaffold the project
-        let project_dir = build_manager.scaffold_project(&project_config.to_string())?;
+        let project_dir = build_manager.scaffold_project(&project_config.to_string())?.root;
 
         // Verify project directory exists
         println!("Project directory: {}", project_dir.display());
@@ -455,4 +1049,557 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    #[traced_test]
+    fn test_scaffold_project_records_span() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let build_manager = BuildManager::new(StateManager::new(), temp_dir.path().to_path_buf());
+
+        let config = serde_json::json!({ "project_name": "traced-demo", "language": "Rust" });
+        build_manager.scaffold_project(&config.to_string())?;
+
+        assert!(logs_contain("scaffold_project"));
+        assert!(logs_contain("project_name=\"traced-demo\""));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scaffold_project_idempotent() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let storage = Storage::new(temp_dir.path().join("db"))?;
+        let build_manager = BuildManager::new(StateManager::new(), temp_dir.path().join("out"));
+        let config = serde_json::json!({ "project_name": "idem-demo", "language": "Rust" }).to_string();
+
+        let first = build_manager.scaffold_project_idempotent(&config, &storage, Some("request-1"))?;
+        std::fs::write(first.join("README.md"), "edited")?;
+
+        let second = build_manager.scaffold_project_idempotent(&config, &storage, Some("request-1"))?;
+        assert_eq!(first, second);
+        assert_eq!(std::fs::read_to_string(second.join("README.md"))?, "edited");
+        assert_eq!(std::fs::read_dir(temp_dir.path().join("out"))?.count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scaffold_project_strict_mode() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let config = serde_json::json!({
+            "language": "Rust",
+            "dependencies": { "production": { "serde": null } }
+        }).to_string();
+
+        let strict = BuildManager::new(StateManager::new(), temp_dir.path().to_path_buf()).with_strict(true);
+        let error = strict.scaffold_project(&config).unwrap_err().to_string();
+        assert!(error.contains("project_name"));
+        assert!(error.contains("description"));
+        assert!(error.contains("dependencies.production.serde"));
+        assert_eq!(std::fs::read_dir(temp_dir.path())?.count(), 0);
+
+        let lenient = BuildManager::new(StateManager::new(), temp_dir.path().to_path_buf());
+        let project_dir = lenient.scaffold_project(&config)?.root;
+        assert!(project_dir.join("Cargo.toml").exists());
+
+        Ok(())
+    }
+
+    fn snapshot_tree(root: &Path) -> Vec<(PathBuf, Option<String>)> {
+        let mut entries: Vec<_> = walkdir::WalkDir::new(root)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| {
+                let relative = entry.path().strip_prefix(root).unwrap().to_path_buf();
+                let content = entry.file_type().is_file()
+                    .then(|| std::fs::read_to_string(entry.path()).unwrap());
+                (relative, content)
+            })
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    #[test]
+    fn test_scaffold_project_from_yaml_and_json_paths() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+
+        let yaml_path = temp_dir.path().join("project.yaml");
+        std::fs::write(&yaml_path, r#"
+project_name: yaml-demo
+description: A demo project
+language: Rust
+project_type: CommandLineInterface
+technologies: [Rust, Clap]
+dependencies:
+  production:
+    clap: "4.5"
+  development: {}
+directory_structure:
+  src: [main.rs, cli.rs]
+"#)?;
+
+        let json_path = temp_dir.path().join("project.json");
+        std::fs::write(&json_path, serde_json::json!({
+            "project_name": "yaml-demo",
+            "description": "A demo project",
+            "language": "Rust",
+            "project_type": "CommandLineInterface",
+            "technologies": ["Rust", "Clap"],
+            "dependencies": { "production": { "clap": "4.5" }, "development": {} },
+            "directory_structure": { "src": ["main.rs", "cli.rs"] }
+        }).to_string())?;
+
+        let from_yaml = BuildManager::new(StateManager::new(), temp_dir.path().join("yaml"))
+            .scaffold_project_from_path(&yaml_path)?.root;
+        let from_json = BuildManager::new(StateManager::new(), temp_dir.path().join("json"))
+            .scaffold_project_from_path(&json_path)?.root;
+
+        assert!(from_yaml.join("src/cli.rs").exists());
+        assert_eq!(snapshot_tree(&from_yaml), snapshot_tree(&from_json));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_main_file_uses_project_name() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let build_manager = BuildManager::new(StateManager::new(), temp_dir.path().to_path_buf());
+
+        let config = serde_json::json!({
+            "project_name": "inventory-service",
+            "description": "Tracks stock levels",
+            "language": "Rust"
+        });
+        let project_dir = build_manager.scaffold_project(&config.to_string())?.root;
+
+        let main_rs = std::fs::read_to_string(project_dir.join("src/main.rs"))?;
+        assert!(main_rs.contains("println!(\"Hello, inventory-service!\");"));
+        assert!(main_rs.contains("Tracks stock levels"));
+        assert!(!main_rs.contains("TaskMaster"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_manifest_dependencies_sorted() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let render = |name: &str, deps: Value, language: &str, manifest: &str| -> Result<String> {
+            let build_manager = BuildManager::new(StateManager::new(), temp_dir.path().join(name));
+            let config = serde_json::json!({
+                "project_name": "sorted-deps",
+                "language": language,
+                "dependencies": { "production": deps.clone(), "development": deps }
+            });
+            let project_dir = build_manager.scaffold_project(&config.to_string())?.root;
+            Ok(std::fs::read_to_string(project_dir.join(manifest))?)
+        };
+
+        let forward = serde_json::json!({ "anyhow": "1.0", "serde": "1.0", "tokio": "1" });
+        let reverse = serde_json::json!({ "tokio": "1", "serde": "1.0", "anyhow": "1.0" });
+
+        let first = render("first", forward.clone(), "Rust", "Cargo.toml")?;
+        let second = render("second", reverse.clone(), "Rust", "Cargo.toml")?;
+        assert_eq!(first, second);
+        assert!(first.contains("anyhow = \"1.0\"\nserde = \"1.0\"\ntokio = \"1\"\n"));
+
+        let first = render("first-js", forward, "JavaScript", "package.json")?;
+        let second = render("second-js", reverse, "JavaScript", "package.json")?;
+        assert_eq!(first, second);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_main_file_custom_template() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let build_manager = BuildManager::new(StateManager::new(), temp_dir.path().to_path_buf());
+
+        let config = serde_json::json!({
+            "project_name": "custom-app",
+            "language": "Python",
+            "main_template": "print('custom {{project_name}}')"
+        });
+        let project_dir = build_manager.scaffold_project(&config.to_string())?.root;
+
+        let main_py = std::fs::read_to_string(project_dir.join("src/main.py"))?;
+        assert_eq!(main_py, "print('custom custom-app')");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_scaffold_report_lists_created_paths() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let build_manager = BuildManager::new(StateManager::new(), temp_dir.path().to_path_buf());
+
+        let config = serde_json::json!({
+            "project_name": "reported",
+            "language": "Rust",
+            "directory_structure": {
+                "src": ["main.rs"],
+                "config": ["database.toml", "jwt.toml"]
+            }
+        });
+        let report = build_manager.scaffold_project(&config.to_string())?;
+        let root = &report.root;
+
+        for file in ["Cargo.toml", "src/main.rs", "config/database.toml", "config/jwt.toml", "README.md"] {
+            let path = root.join(file);
+            assert_eq!(report.created_files.iter().filter(|created| **created == path).count(), 1, "{}", file);
+            assert!(path.is_file());
+        }
+        for dir in ["", "src", "tests", "migrations", "config"] {
+            assert!(report.created_dirs.contains(&root.join(dir)), "{:?} not reported", dir);
+        }
+        assert!(report.skipped.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_configured_base_dirs_merge_with_defaults() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let build_manager = BuildManager::new(StateManager::new(), temp_dir.path().to_path_buf());
+
+        let config = serde_json::json!({
+            "project_name": "merged-dirs",
+            "language": "Rust",
+            "directory_structure": {
+                "src": ["lib.rs", "cli/", "lib.rs"],
+                "src/": [],
+                "tests": ["integration.rs"],
+                "docs/": ["guide.md"]
+            }
+        });
+        let project_dir = build_manager.scaffold_project(&config.to_string())?.root;
+
+        for dir in ["src", "src/cli", "tests", "migrations", "config", "docs"] {
+            assert!(project_dir.join(dir).is_dir(), "{} is not a directory", dir);
+        }
+        for file in ["src/lib.rs", "src/main.rs", "tests/integration.rs", "docs/guide.md"] {
+            assert!(project_dir.join(file).is_file(), "{} is not a file", file);
+        }
+        assert!(std::fs::read_to_string(project_dir.join("src/main.rs"))?.contains("fn main()"));
+
+        Ok(())
+    }
+
+    async fn run_touch_task(build_manager: &BuildManager, state_manager: &StateManager, id: &str, working_dir: PathBuf) -> Result<(), BuildError> {
+        let task_id = TaskId::new(id);
+        let mut task = TaskState::new(task_id.clone());
+        task.metadata.name = "touch ran_here".to_string();
+        task.metadata.working_dir = Some(working_dir);
+
+        state_manager.create_task(task).await?;
+        build_manager.execute_task(&task_id).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_task_relative_working_dir() -> Result<(), BuildError> {
+        let temp_dir = tempfile::tempdir()?;
+        std::fs::create_dir_all(temp_dir.path().join("sub/project"))?;
+        let state_manager = StateManager::new();
+        let build_manager = BuildManager::new(state_manager.clone(), temp_dir.path().to_path_buf());
+
+        run_touch_task(&build_manager, &state_manager, "relative-dir", PathBuf::from("sub/project")).await?;
+
+        assert!(temp_dir.path().join("sub/project/ran_here").exists());
+        assert!(!temp_dir.path().join("ran_here").exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_task_absolute_working_dir() -> Result<(), BuildError> {
+        let base_dir = tempfile::tempdir()?;
+        let other_dir = tempfile::tempdir()?;
+        let state_manager = StateManager::new();
+        let build_manager = BuildManager::new(state_manager.clone(), base_dir.path().to_path_buf());
+
+        run_touch_task(&build_manager, &state_manager, "absolute-dir", other_dir.path().to_path_buf()).await?;
+
+        assert!(other_dir.path().join("ran_here").exists());
+        assert!(!base_dir.path().join("ran_here").exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_execute_task_in_overrides_working_dir() -> Result<(), BuildError> {
+        let first = tempfile::tempdir()?;
+        let second = tempfile::tempdir()?;
+        let state_manager = StateManager::new();
+        let mut build_manager = BuildManager::new(state_manager.clone(), PathBuf::from("unused"));
+        build_manager.set_working_dir(first.path().to_path_buf());
+        assert_eq!(build_manager.working_dir(), first.path());
+
+        let task_id = create_command_task(&state_manager, "record-pwd", "sh -c pwd>pwd.txt").await?;
+        build_manager.execute_task(&task_id).await?;
+        build_manager.execute_task_in(&task_id, second.path()).await?;
+
+        for dir in [first.path(), second.path()] {
+            let recorded = std::fs::read_to_string(dir.join("pwd.txt"))?;
+            assert_eq!(PathBuf::from(recorded.trim()).canonicalize()?, dir.canonicalize()?);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_raw_output_preserves_invalid_utf8() -> Result<(), BuildError> {
+        let temp_dir = tempfile::tempdir()?;
+        std::fs::write(temp_dir.path().join("emit.sh"), "printf '\\377\\376bad' >&2\nexit 3\n")?;
+        let state_manager = StateManager::new();
+        let task_id = create_command_task(&state_manager, "emit-bytes", "sh emit.sh").await?;
+
+        let raw = BuildManager::new(state_manager.clone(), temp_dir.path().to_path_buf())
+            .with_output_decoding(OutputDecoding::Raw);
+        match raw.execute_task(&task_id).await {
+            Err(BuildError::CommandFailed(output)) => {
+                assert_eq!(output.exit_code, Some(3));
+                assert_eq!(output.raw_stderr.as_deref(), Some(&b"\xff\xfebad"[..]));
+                assert_eq!(output.stderr, "\u{FFFD}\u{FFFD}bad");
+            }
+            other => panic!("expected CommandFailed, got {:?}", other),
+        }
+
+        let strict = BuildManager::new(state_manager.clone(), temp_dir.path().to_path_buf())
+            .with_output_decoding(OutputDecoding::Strict);
+        match strict.execute_task(&task_id).await {
+            Err(BuildError::CommandFailed(output)) => {
+                assert_eq!(output.exit_code, Some(3));
+                assert_eq!(output.raw_stderr.as_deref(), Some(&b"\xff\xfebad"[..]));
+            }
+            other => panic!("expected CommandFailed, got {:?}", other),
+        }
+
+        // Only a command that succeeded fails on its encoding
+        std::fs::write(temp_dir.path().join("emit.sh"), "printf '\\377\\376bad' >&2\n")?;
+        match strict.execute_task(&task_id).await {
+            Err(BuildError::InvalidUtf8 { stream, bytes }) => {
+                assert_eq!(stream, "stderr");
+                assert_eq!(bytes, b"\xff\xfebad");
+            }
+            other => panic!("expected InvalidUtf8, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    async fn create_command_task(state_manager: &StateManager, id: &str, command: &str) -> Result<TaskId, BuildError> {
+        let task_id = TaskId::new(id);
+        let mut task = TaskState::new(task_id.clone());
+        task.metadata.name = command.to_string();
+        state_manager.create_task(task).await?;
+        Ok(task_id)
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_flags_missing_program() -> Result<(), BuildError> {
+        let temp_dir = tempfile::tempdir()?;
+        let state_manager = StateManager::new();
+        let build_manager = BuildManager::new(state_manager.clone(), temp_dir.path().to_path_buf());
+        let task_id = create_command_task(&state_manager, "dry-run", "definitely-not-a-real-binary --flag value").await?;
+
+        let report = build_manager.execute_task_dry_run(&task_id).await?;
+        assert_eq!(report.program, "definitely-not-a-real-binary");
+        assert_eq!(report.args, ["--flag", "value"]);
+        assert_eq!(report.working_dir, temp_dir.path());
+        assert!(report.working_dir_exists);
+        assert!(!report.program_found());
+        assert!(!report.is_runnable());
+
+        // Nothing ran, so the task is untouched
+        assert_eq!(state_manager.get_task(&task_id).await?.status, TaskStatus::Pending);
+        Ok(())
+    }
+
+    async fn failing_batch(state_manager: &StateManager) -> Result<Vec<TaskId>, BuildError> {
+        Ok(vec![
+            create_command_task(state_manager, "fail-a", "false").await?,
+            create_command_task(state_manager, "fail-b", "false").await?,
+            create_command_task(state_manager, "ok-c", "true").await?,
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_execute_all_fail_fast() -> Result<(), BuildError> {
+        let temp_dir = tempfile::tempdir()?;
+        let state_manager = StateManager::new();
+        let build_manager = BuildManager::new(state_manager.clone(), temp_dir.path().to_path_buf());
+        let batch = failing_batch(&state_manager).await?;
+
+        let report = build_manager.execute_all(&batch, FailureMode::FailFast).await?;
+
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].0, TaskId::new("fail-a"));
+        assert_eq!(report.skipped, vec![TaskId::new("fail-b"), TaskId::new("ok-c")]);
+        assert!(report.succeeded.is_empty());
+        assert_eq!(state_manager.get_task(&TaskId::new("fail-a")).await?.status, TaskStatus::Failed);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_execute_all_keep_going() -> Result<(), BuildError> {
+        let temp_dir = tempfile::tempdir()?;
+        let state_manager = StateManager::new();
+        let build_manager = BuildManager::new(state_manager.clone(), temp_dir.path().to_path_buf());
+        let batch = failing_batch(&state_manager).await?;
+
+        let report = build_manager.execute_all(&batch, FailureMode::KeepGoing).await?;
+
+        let failed: Vec<TaskId> = report.failures.iter().map(|(id, _)| id.clone()).collect();
+        assert_eq!(failed, vec![TaskId::new("fail-a"), TaskId::new("fail-b")]);
+        assert_eq!(report.succeeded, vec![TaskId::new("ok-c")]);
+        assert!(report.skipped.is_empty());
+        assert!(!report.is_success());
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_env_placeholder_set() -> Result<()> {
+        std::env::set_var("BUILD_SYSTEM_TEST_DATABASE_URL", "postgres://db:5432/app");
+        let resolved = resolve_env_placeholders("url = \"${BUILD_SYSTEM_TEST_DATABASE_URL}\"")?;
+        assert_eq!(resolved, "url = \"postgres://db:5432/app\"");
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_env_placeholder_default() -> Result<()> {
+        std::env::remove_var("BUILD_SYSTEM_TEST_UNSET_PORT");
+        let resolved = resolve_env_placeholders("port = ${BUILD_SYSTEM_TEST_UNSET_PORT:-5432}")?;
+        assert_eq!(resolved, "port = 5432");
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_env_placeholder_undefined() {
+        std::env::remove_var("BUILD_SYSTEM_TEST_UNDEFINED");
+        let err = resolve_env_placeholders("secret = ${BUILD_SYSTEM_TEST_UNDEFINED}").unwrap_err();
+        assert!(err.to_string().contains("BUILD_SYSTEM_TEST_UNDEFINED"));
+    }
+
+    #[test]
+    fn test_config_templates_resolve_placeholders() -> Result<()> {
+        std::env::set_var("BUILD_SYSTEM_TEST_DB_HOST", "db.internal");
+        let temp_dir = tempfile::tempdir()?;
+        let build_manager = BuildManager::new(StateManager::new(), temp_dir.path().to_path_buf());
+
+        let config = serde_json::json!({
+            "project_name": "env-app",
+            "language": "Rust",
+            "directory_structure": { "config": ["database.toml"] },
+            "config_templates": {
+                "database.toml": "host = \"${BUILD_SYSTEM_TEST_DB_HOST}\"\nport = ${BUILD_SYSTEM_TEST_DB_PORT:-5432}\n"
+            }
+        });
+        let project_dir = build_manager.scaffold_project(&config.to_string())?.root;
+
+        let database = std::fs::read_to_string(project_dir.join("config/database.toml"))?;
+        assert_eq!(database, "host = \"db.internal\"\nport = 5432\n");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_generated_file_modes() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = |path: PathBuf| -> Result<u32> { Ok(fs::metadata(path)?.permissions().mode() & 0o777) };
+
+        let temp_dir = tempfile::tempdir()?;
+        let build_manager = BuildManager::new(StateManager::new(), temp_dir.path().to_path_buf());
+        let config = serde_json::json!({
+            "project_name": "moded-app",
+            "language": "Rust",
+            "directory_structure": {
+                "config": ["database.toml", "jwt.toml"],
+                "scripts": ["init.sh", "seed.sh"]
+            },
+            "file_modes": { "config/database.toml": "0640", "scripts/seed.sh": "0700" }
+        });
+        let project_dir = build_manager.scaffold_project(&config.to_string())?.root;
+
+        assert_eq!(mode(project_dir.join("config/jwt.toml"))?, 0o600);
+        assert_eq!(mode(project_dir.join("scripts/init.sh"))?, 0o755);
+        assert_eq!(mode(project_dir.join("config/database.toml"))?, 0o640);
+        assert_eq!(mode(project_dir.join("scripts/seed.sh"))?, 0o700);
+
+        let invalid = serde_json::json!({ "project_name": "bad-modes", "file_modes": { "src/main": "rwx" } });
+        let err = build_manager.scaffold_project(&invalid.to_string()).unwrap_err();
+        assert!(err.to_string().contains("src/main"), "{}", err);
+
+        for escaping in ["../outside.txt", "/etc/passwd", "config/../../outside.txt"] {
+            let config = serde_json::json!({ "project_name": "escape", "file_modes": { escaping: "0777" } });
+            let err = build_manager.scaffold_project(&config.to_string()).unwrap_err();
+            assert!(err.to_string().contains("inside the project"), "{}", err);
+        }
+
+        // Secrets are private from the moment they are written, not only once scaffolding ends
+        let mut report = ScaffoldReport::new(temp_dir.path().join("direct"));
+        report.write_file(&temp_dir.path().join("direct/config/.env"), "TOKEN=secret\n")?;
+        assert_eq!(mode(temp_dir.path().join("direct/config/.env"))?, 0o600);
+        Ok(())
+    }
+
+    #[test]
+    fn test_recommended_dependencies_fill_empty_rust_web_app() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let build_manager = BuildManager::new(StateManager::new(), temp_dir.path().to_path_buf())
+            .with_recommended_dependencies(true);
+
+        let config = serde_json::json!({
+            "project_name": "web-app",
+            "project_type": "WebApplication",
+            "language": "Rust"
+        });
+        let project_dir = build_manager.scaffold_project(&config.to_string())?.root;
+
+        let manifest: toml::Value = std::fs::read_to_string(project_dir.join("Cargo.toml"))?.parse()?;
+        assert!(manifest["dependencies"].get("axum").is_some());
+        assert!(manifest["dependencies"].get("tokio").is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_recommended_dependencies_do_not_override_config() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let build_manager = BuildManager::new(StateManager::new(), temp_dir.path().to_path_buf())
+            .with_recommended_dependencies(true);
+
+        let config = serde_json::json!({
+            "project_name": "cli-app",
+            "project_type": "CommandLineInterface",
+            "language": "Rust",
+            "dependencies": { "production": { "structopt": "0.3" } }
+        });
+        let project_dir = build_manager.scaffold_project(&config.to_string())?.root;
+
+        let manifest: toml::Value = std::fs::read_to_string(project_dir.join("Cargo.toml"))?.parse()?;
+        assert!(manifest["dependencies"].get("structopt").is_some());
+        assert!(manifest["dependencies"].get("clap").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_package_json_preserves_script_order() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let build_manager = BuildManager::new(StateManager::new(), temp_dir.path().to_path_buf());
+
+        let mut config = crate::prompt::ProjectConfig::new(
+            "ordered-scripts".to_string(),
+            "Script ordering".to_string(),
+            "JavaScript".to_string(),
+            "Express".to_string(),
+            GenerationProjectType::WebApplication,
+        ).map_err(anyhow::Error::msg)?;
+        config.add_script("start", "node src/app.js").map_err(anyhow::Error::msg)?;
+        config.add_script("build", "webpack").map_err(anyhow::Error::msg)?;
+        config.add_script("lint", "eslint .").map_err(anyhow::Error::msg)?;
+        config.add_script("test", "jest").map_err(anyhow::Error::msg)?;
+
+        let project_dir = build_manager.scaffold_project(&serde_json::to_string(&config)?)?.root;
+
+        let package_json: Value = serde_json::from_str(&std::fs::read_to_string(project_dir.join("package.json"))?)?;
+        let scripts: Vec<&String> = package_json["scripts"].as_object().unwrap().keys().collect();
+        assert_eq!(scripts, ["start", "build", "lint", "test"]);
+        assert_eq!(package_json["scripts"]["lint"], "eslint .");
+        Ok(())
+    }
 }
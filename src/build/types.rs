@@ -1,44 +1,50 @@
-use std::collections::HashMap;
 use std::path::PathBuf;
-use std::time::Duration;
 use async_trait::async_trait;
 use serde::{Serialize, Deserialize};
 
-use crate::state::TaskStatus;
+use crate::build::error::BuildError;
+use crate::state::types::{TaskMetadata, TaskStatus};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Inclusive range of a resource a task needs: it cannot run with less than `min`
+/// and never uses more than `max`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct ResourceConstraint {
     pub min: f64,
     pub max: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ResourceConstraint {
+    /// Whether `available` covers the minimum, and the range itself is well formed
+    pub fn is_satisfied_by(&self, available: f64) -> bool {
+        self.min <= self.max && self.min <= available
+    }
+}
+
+/// Resources a build task needs: CPU in cores, memory and disk in megabytes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ResourceRequirements {
     pub cpu: ResourceConstraint,
     pub memory: ResourceConstraint,
     pub disk: ResourceConstraint,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ResourceRequirements {
+    /// Whether every minimum fits in `allocation`
+    pub fn fits_within(&self, allocation: &ResourceAllocation) -> bool {
+        self.cpu.is_satisfied_by(allocation.cpu_cores as f64)
+            && self.memory.is_satisfied_by(allocation.memory_mb as f64)
+            && self.disk.is_satisfied_by(allocation.disk_gb as f64 * 1024.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FileChange {
     pub path: PathBuf,
     pub content: String,
     pub is_executable: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TaskMetadata {
-    pub name: String,
-    pub description: Option<String>,
-    pub owner: String,
-    pub priority: String,
-    pub tags: Vec<String>,
-    pub estimated_duration: Duration,
-    pub dependencies: Vec<String>,
-    pub additional_info: HashMap<String, String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BuildTask {
     pub id: String,
     pub resources: ResourceRequirements,
@@ -46,7 +52,8 @@ pub struct BuildTask {
     pub metadata: TaskMetadata,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Resources available to run build tasks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ResourceAllocation {
     pub cpu_cores: u32,
     pub memory_mb: u64,
@@ -55,9 +62,57 @@ pub struct ResourceAllocation {
 
 #[async_trait]
 pub trait BuildExecutor: Send + Sync {
-    async fn execute_task(&self, task: BuildTask) -> Result<(), crate::build::error::BuildError>;
-    async fn get_task_status(&self, id: &str) -> Result<TaskStatus, crate::build::error::BuildError>;
-    async fn cancel_task(&self, id: &str) -> Result<(), crate::build::error::BuildError>;
-    async fn apply_changes(&self, changes: &[FileChange]) -> Result<(), crate::build::error::BuildError>;
-    async fn check_resource_availability(&self, requirements: &ResourceRequirements) -> Result<bool, crate::build::error::BuildError>;
+    async fn execute_task(&self, task: BuildTask) -> Result<(), BuildError>;
+    async fn get_task_status(&self, id: &str) -> Result<TaskStatus, BuildError>;
+    async fn cancel_task(&self, id: &str) -> Result<(), BuildError>;
+    async fn apply_changes(&self, changes: &[FileChange]) -> Result<(), BuildError>;
+    async fn check_resource_availability(&self, requirements: &ResourceRequirements) -> Result<bool, BuildError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn requirements(cpu: f64, memory: f64, disk: f64) -> ResourceRequirements {
+        ResourceRequirements {
+            cpu: ResourceConstraint { min: cpu, max: cpu * 2.0 },
+            memory: ResourceConstraint { min: memory, max: memory * 2.0 },
+            disk: ResourceConstraint { min: disk, max: disk * 2.0 },
+        }
+    }
+
+    #[test]
+    fn test_build_task_round_trips() -> Result<(), serde_json::Error> {
+        let task = BuildTask {
+            id: "test-task-1".to_string(),
+            resources: requirements(2.0, 4096.0, 10240.0),
+            changes: vec![FileChange {
+                path: PathBuf::from("src/main.rs"),
+                content: "fn main() {}\n".to_string(),
+                is_executable: false,
+            }],
+            metadata: TaskMetadata { name: "Test Task".to_string(), ..Default::default() },
+        };
+
+        let parsed: BuildTask = serde_json::from_str(&serde_json::to_string(&task)?)?;
+        assert_eq!(parsed, task);
+        Ok(())
+    }
+
+    #[test]
+    fn test_requirements_fit_within_allocation() {
+        let allocation = ResourceAllocation { cpu_cores: 4, memory_mb: 8192, disk_gb: 100 };
+
+        assert!(requirements(2.0, 4096.0, 10240.0).fits_within(&allocation));
+        assert!(requirements(4.0, 8192.0, 102400.0).fits_within(&allocation));
+        assert!(!requirements(8.0, 4096.0, 10240.0).fits_within(&allocation));
+        assert!(!requirements(2.0, 16384.0, 10240.0).fits_within(&allocation));
+        assert!(!requirements(2.0, 4096.0, 204800.0).fits_within(&allocation));
+
+        let inverted = ResourceRequirements {
+            cpu: ResourceConstraint { min: 3.0, max: 1.0 },
+            ..requirements(2.0, 4096.0, 10240.0)
+        };
+        assert!(!inverted.fits_within(&allocation));
+    }
 }
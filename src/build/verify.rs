@@ -0,0 +1,124 @@
+use std::path::Path;
+use walkdir::WalkDir;
+
+use super::{BatchReport, BuildError, BuildManager, FailureMode};
+use crate::state::types::{TaskId, TaskState};
+
+/// Commands that check a generated project builds, in the order they must run.
+/// Python projects compile each of their `.py` files; `None` for languages without a
+/// known build command.
+pub fn verification_commands(language: &str, project_dir: &Path) -> Option<Vec<String>> {
+    match language.to_lowercase().as_str() {
+        "rust" => Some(vec!["cargo check".to_string()]),
+        "javascript" | "typescript" => Some(vec!["npm install".to_string(), "npm run build".to_string()]),
+        "python" => {
+            let mut sources: Vec<String> = WalkDir::new(project_dir)
+                .sort_by_file_name()
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_file())
+                .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "py"))
+                .filter_map(|entry| {
+                    let relative = entry.path().strip_prefix(project_dir).ok()?;
+                    Some(relative.to_string_lossy().into_owned())
+                })
+                .collect();
+            if sources.is_empty() {
+                return Some(Vec::new());
+            }
+            sources.insert(0, "python -m py_compile".to_string());
+            Some(vec![sources.join(" ")])
+        }
+        _ => None,
+    }
+}
+
+impl BuildManager {
+    /// Run the language's build command in `project_dir` to check a freshly generated
+    /// project compiles. Each command becomes a task depending on the previous one, so
+    /// `npm run build` is skipped when `npm install` fails.
+    pub async fn verify_project(&self, project_dir: &Path, language: &str) -> Result<BatchReport, BuildError> {
+        let commands = verification_commands(language, project_dir).ok_or_else(|| {
+            BuildError::InvalidCommand(format!("No build command known for language '{}'", language))
+        })?;
+        let project_dir = project_dir.canonicalize()?;
+
+        let run_id = uuid::Uuid::new_v4();
+        let mut task_ids: Vec<TaskId> = Vec::new();
+        for (index, command) in commands.into_iter().enumerate() {
+            let task_id = TaskId::new(&format!("verify-{}-{}", run_id, index));
+            let mut task = TaskState::new(task_id.clone());
+            task.metadata.name = command;
+            task.metadata.working_dir = Some(project_dir.clone());
+            task.metadata.dependencies = task_ids.last().cloned().into_iter().collect();
+            self.state_manager.create_task(task).await?;
+            task_ids.push(task_id);
+        }
+
+        self.execute_all(&task_ids, FailureMode::FailFast).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::StateManager;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_verification_commands_per_language() -> Result<(), BuildError> {
+        let temp_dir = tempfile::tempdir()?;
+        std::fs::create_dir_all(temp_dir.path().join("src"))?;
+        std::fs::write(temp_dir.path().join("src/main.py"), "print('hi')\n")?;
+        std::fs::write(temp_dir.path().join("setup.py"), "")?;
+
+        assert_eq!(verification_commands("Rust", temp_dir.path()), Some(vec!["cargo check".to_string()]));
+        assert_eq!(
+            verification_commands("typescript", temp_dir.path()),
+            Some(vec!["npm install".to_string(), "npm run build".to_string()])
+        );
+        assert_eq!(
+            verification_commands("python", temp_dir.path()),
+            Some(vec!["python -m py_compile setup.py src/main.py".to_string()])
+        );
+        assert_eq!(verification_commands("cobol", temp_dir.path()), None);
+        Ok(())
+    }
+
+    fn cargo_available() -> bool {
+        std::process::Command::new("cargo").arg("--version").output().is_ok_and(|out| out.status.success())
+    }
+
+    #[tokio::test]
+    async fn test_verify_generated_rust_project() -> Result<(), BuildError> {
+        if !cargo_available() {
+            eprintln!("cargo not found; skipping build verification test");
+            return Ok(());
+        }
+
+        let temp_dir = tempfile::tempdir()?;
+        let build_manager = BuildManager::new(StateManager::new(), temp_dir.path().to_path_buf());
+        let config = serde_json::json!({
+            "project_name": "verify-me",
+            "description": "Trivial project for build verification",
+            "language": "Rust"
+        });
+        let project_dir = build_manager.scaffold_project(&config.to_string()).map_err(BuildError::Scaffold)?.root;
+
+        let report = build_manager.verify_project(&project_dir, "Rust").await?;
+        assert!(report.is_success(), "{:?}", report.failures);
+        assert_eq!(report.succeeded.len(), 1);
+
+        std::fs::write(project_dir.join("src/main.rs"), "fn main() { let x: u32 = \"no\"; }")?;
+        let report = build_manager.verify_project(&project_dir, "rust").await?;
+        assert!(!report.is_success());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_unknown_language() {
+        let build_manager = BuildManager::new(StateManager::new(), PathBuf::from("."));
+        let err = build_manager.verify_project(Path::new("."), "cobol").await.unwrap_err();
+        assert!(err.to_string().contains("cobol"), "{}", err);
+    }
+}
@@ -0,0 +1,223 @@
+use anyhow::{Context, Result, anyhow};
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::prompt::storage::Storage;
+
+/// Default crates.io API root
+pub const CRATES_IO_API: &str = "https://crates.io/api/v1";
+
+/// Version written when a crate's newest version cannot be looked up
+pub const FALLBACK_VERSION: &str = "*";
+
+/// Storage key prefix for resolved versions; an entry pins the crate to that version
+/// for every later resolution
+const CACHE_PREFIX: &str = "crate_version_";
+
+/// Replaces `"latest"` and missing Rust dependency versions with the newest stable
+/// release published on crates.io
+#[derive(Debug, Clone)]
+pub struct CrateVersionResolver {
+    api_base: String,
+    cache: Option<Arc<Storage>>,
+}
+
+impl Default for CrateVersionResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CrateVersionResolver {
+    pub fn new() -> Self {
+        Self::with_api_base(CRATES_IO_API)
+    }
+
+    /// Query a different API root, e.g. a mirror or a test server
+    pub fn with_api_base(api_base: &str) -> Self {
+        Self {
+            api_base: api_base.trim_end_matches('/').to_string(),
+            cache: None,
+        }
+    }
+
+    /// Remember resolved versions in `storage` and reuse them instead of querying again
+    pub fn with_cache(mut self, storage: Arc<Storage>) -> Self {
+        self.cache = Some(storage);
+        self
+    }
+
+    /// Whether `version` needs resolving: `"latest"`, `"*"`, empty, or missing
+    pub fn needs_resolution(version: &Value) -> bool {
+        match version {
+            Value::String(version) => {
+                let version = version.trim();
+                version.is_empty() || version == "*" || version.eq_ignore_ascii_case("latest")
+            }
+            Value::Null => true,
+            _ => false,
+        }
+    }
+
+    /// Newest stable version of `name`, or `FALLBACK_VERSION` when the lookup fails
+    pub async fn resolve(&self, name: &str) -> String {
+        match self.lookup(name).await {
+            Ok(version) => version,
+            Err(e) => {
+                tracing::warn!("Could not resolve version of crate {}: {:#}", name, e);
+                FALLBACK_VERSION.to_string()
+            }
+        }
+    }
+
+    async fn lookup(&self, name: &str) -> Result<String> {
+        let key = format!("{}{}", CACHE_PREFIX, name);
+        if let Some(cache) = &self.cache {
+            if let Some(version) = cache.load::<String>(&key)? {
+                return Ok(version);
+            }
+        }
+
+        let url = format!("{}/crates/{}", self.api_base, name);
+        // crates.io rejects requests without a user agent
+        let response = reqwest::Client::new()
+            .get(&url)
+            .header("User-Agent", concat!("build-system/", env!("CARGO_PKG_VERSION")))
+            .send()
+            .await
+            .with_context(|| format!("Failed to query {}", url))?;
+        if !response.status().is_success() {
+            return Err(anyhow!("{} returned status {}", url, response.status()));
+        }
+        let body: Value = response.json().await.context("Invalid crates.io response")?;
+        let version = ["max_stable_version", "newest_version", "max_version"]
+            .iter()
+            .find_map(|field| body["crate"][field].as_str())
+            .ok_or_else(|| anyhow!("No version listed for crate {}", name))?
+            .to_string();
+
+        if let Some(cache) = &self.cache {
+            cache.store(&key, &version)?;
+        }
+        Ok(version)
+    }
+
+    /// Resolve every unresolved production and development dependency of a Rust project
+    /// config in place, returning how many versions were substituted. Configs for other
+    /// languages are left untouched.
+    pub async fn resolve_config(&self, config: &mut Value) -> usize {
+        let is_rust = config["language"]
+            .as_str()
+            .is_some_and(|language| language.eq_ignore_ascii_case("rust"));
+        if !is_rust {
+            return 0;
+        }
+
+        let mut resolved = 0;
+        for group in ["production", "development"] {
+            let Some(dependencies) = config["dependencies"][group].as_object_mut() else {
+                continue;
+            };
+            for (name, version) in dependencies.iter_mut() {
+                // Detailed `{ "version": ..., "features": [...] }` entries keep their other keys
+                let version = match version {
+                    Value::Object(detail) => match detail.get_mut("version") {
+                        Some(version) => version,
+                        None => continue,
+                    },
+                    version => version,
+                };
+                if Self::needs_resolution(version) {
+                    *version = Value::String(self.resolve(name).await);
+                    resolved += 1;
+                }
+            }
+        }
+        resolved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::test_server::{MockResponse, MockServer};
+    use serde_json::json;
+
+    fn crate_response(version: &str) -> MockResponse {
+        let body = json!({ "crate": { "name": "serde", "max_stable_version": version, "newest_version": "2.0.0-rc.1" } });
+        MockResponse::Json(200, body.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_latest_replaced_with_newest_stable() -> Result<()> {
+        let server = MockServer::start(vec![crate_response("1.0.210")]).await;
+        let resolver = CrateVersionResolver::with_api_base(&server.base_url);
+
+        let mut config = json!({
+            "language": "Rust",
+            "dependencies": {
+                "production": { "serde": "latest", "tokio": "1.38" },
+                "development": {}
+            }
+        });
+        assert_eq!(resolver.resolve_config(&mut config).await, 1);
+        assert_eq!(config["dependencies"]["production"]["serde"], "1.0.210");
+        assert_eq!(config["dependencies"]["production"]["tokio"], "1.38");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /crates/serde "));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_detailed_dependencies_keep_their_fields() -> Result<()> {
+        let server = MockServer::start(vec![crate_response("1.0.210")]).await;
+        let resolver = CrateVersionResolver::with_api_base(&server.base_url);
+
+        let mut config = json!({
+            "language": "Rust",
+            "dependencies": {
+                "production": {
+                    "serde": { "version": "latest", "features": ["derive"] },
+                    "tokio": { "version": "1.38", "features": ["full"] },
+                    "local": { "path": "../local" }
+                },
+                "development": { "proptest": "*" }
+            }
+        });
+        assert_eq!(resolver.resolve_config(&mut config).await, 2);
+        assert_eq!(config["dependencies"]["production"]["serde"], json!({ "version": "1.0.210", "features": ["derive"] }));
+        assert_eq!(config["dependencies"]["production"]["tokio"], json!({ "version": "1.38", "features": ["full"] }));
+        assert_eq!(config["dependencies"]["production"]["local"], json!({ "path": "../local" }));
+        assert_eq!(config["dependencies"]["development"]["proptest"], "1.0.210");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_lookup_failure_falls_back_to_wildcard() -> Result<()> {
+        let server = MockServer::start(vec![MockResponse::Json(404, "{\"errors\":[]}".to_string())]).await;
+        let resolver = CrateVersionResolver::with_api_base(&server.base_url);
+
+        let mut config = json!({
+            "language": "Rust",
+            "dependencies": { "production": { "no-such-crate": "latest" } }
+        });
+        resolver.resolve_config(&mut config).await;
+        assert_eq!(config["dependencies"]["production"]["no-such-crate"], FALLBACK_VERSION);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cached_version_is_reused() -> Result<()> {
+        let server = MockServer::start(vec![crate_response("1.0.210")]).await;
+        let temp_dir = tempfile::tempdir()?;
+        let storage = Arc::new(Storage::new(temp_dir.path())?);
+        let resolver = CrateVersionResolver::with_api_base(&server.base_url).with_cache(storage);
+
+        assert_eq!(resolver.resolve("serde").await, "1.0.210");
+        assert_eq!(resolver.resolve("serde").await, "1.0.210");
+        assert_eq!(server.requests().len(), 1);
+        Ok(())
+    }
+}
@@ -1,6 +1,11 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use std::io::Write;
 
+use crate::tools::DryRun;
+
+pub mod style;
 mod tools;
 use tools::ToolsCli;
 
@@ -8,6 +13,14 @@ use tools::ToolsCli;
 #[command(name = "build-system")]
 #[command(about = "AI-powered build system")]
 pub struct Cli {
+    /// Disable colored output (also disabled by NO_COLOR or when stdout is not a terminal)
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Print what commands would do without writing files or calling the model
+    #[arg(long, global = true)]
+    dry_run: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -15,11 +28,73 @@ pub struct Cli {
 #[derive(Subcommand)]
 pub enum Commands {
     /// Execute build system tools
-    Tools(ToolsCli),
+    Tools(Box<ToolsCli>),
+
+    /// Print a shell completion script
+    #[command(hide = true)]
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+/// Write the completion script for `shell` to `out`
+pub fn write_completions(shell: Shell, out: &mut dyn Write) {
+    let mut command = Cli::command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, out);
 }
 
 pub async fn handle_cli_command(cli: Cli) -> Result<()> {
+    style::init(style::Palette::detect(cli.no_color));
     match cli.command {
-        Commands::Tools(tools) => tools.execute().await,
+        Commands::Tools(tools) => tools.execute(DryRun(cli.dry_run)).await,
+        Commands::Completions { shell } => {
+            write_completions(shell, &mut std::io::stdout());
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bash_completions_mention_tools() {
+        let mut out = Vec::new();
+        write_completions(Shell::Bash, &mut out);
+        let script = String::from_utf8(out).unwrap();
+        assert!(script.contains("build-system"));
+        assert!(script.contains("tools"));
+    }
+
+    #[test]
+    fn test_completions_subcommand_parses() {
+        let cli = Cli::try_parse_from(["build-system", "completions", "zsh"]).unwrap();
+        assert!(matches!(cli.command, Commands::Completions { shell: Shell::Zsh }));
+    }
+
+    #[test]
+    fn test_no_color_flag_is_global() {
+        let cli = Cli::try_parse_from(["build-system", "tools", "list", "--no-color"]).unwrap();
+        assert!(cli.no_color);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_project_writes_nothing() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let output = temp_dir.path().join("config.json");
+        let cli = Cli::try_parse_from([
+            "build-system", "tools", "project", "--name", "cli-dry-run", "--language", "rust",
+            "--config-only", "--output", output.to_str().unwrap(), "--dry-run",
+        ])?;
+        assert!(cli.dry_run);
+
+        handle_cli_command(cli).await?;
+        assert!(!output.exists());
+        assert!(!std::path::Path::new("build/cli-dry-run").exists());
+        Ok(())
     }
 }
@@ -0,0 +1,83 @@
+use once_cell::sync::OnceCell;
+use owo_colors::OwoColorize;
+use std::io::IsTerminal;
+
+static PALETTE: OnceCell<Palette> = OnceCell::new();
+
+/// Colors for CLI messages, or plain text when color is disabled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    enabled: bool,
+}
+
+impl Palette {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    /// Color only when stdout is a terminal and neither `--no-color` nor a non-empty
+    /// `NO_COLOR` asks otherwise
+    pub fn detect(no_color: bool) -> Self {
+        let env_disabled = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Self::new(!no_color && !env_disabled && std::io::stdout().is_terminal())
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn heading(&self, text: &str) -> String {
+        self.paint(text, |text| text.bold().to_string())
+    }
+
+    pub fn success(&self, text: &str) -> String {
+        self.paint(text, |text| text.green().to_string())
+    }
+
+    pub fn warning(&self, text: &str) -> String {
+        self.paint(text, |text| text.yellow().to_string())
+    }
+
+    pub fn error(&self, text: &str) -> String {
+        self.paint(text, |text| text.red().to_string())
+    }
+
+    /// Command, flag and tool names
+    pub fn name(&self, text: &str) -> String {
+        self.paint(text, |text| text.cyan().to_string())
+    }
+
+    fn paint(&self, text: &str, style: impl Fn(&str) -> String) -> String {
+        if self.enabled {
+            style(text)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Set the process-wide palette; only the first call has any effect
+pub fn init(palette: Palette) {
+    let _ = PALETTE.set(palette);
+}
+
+/// The palette set by `init`, detected from the environment if `init` was never called
+pub fn palette() -> Palette {
+    *PALETTE.get_or_init(|| Palette::detect(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_palette_respects_enabled() {
+        let plain = Palette::new(false);
+        assert_eq!(plain.success("done"), "done");
+        assert_eq!(plain.heading("Tools"), "Tools");
+
+        let colored = Palette::new(true);
+        assert!(colored.success("done").contains("\x1b["));
+        assert!(colored.success("done").contains("done"));
+    }
+}
@@ -1,6 +1,12 @@
-use anyhow::Result;
-use clap::{Parser, Subcommand};
-use crate::tools;
+use anyhow::{Context, Result};
+use clap::{ArgGroup, Parser, Subcommand};
+use std::io::Write;
+use super::style::{self, Palette};
+use crate::tools::{self, DryRun};
+use crate::inference::{InferenceClient, ModelInfo};
+use crate::project_generator::{language_value_parser, supported_languages};
+use crate::state::StateManager;
+use crate::state::types::{StateSnapshot, TaskStatus};
 
 #[derive(Parser, Debug)]
 #[command(name = "tools")]
@@ -33,48 +39,217 @@ enum ToolCommands {
     },
 
     /// Generate a new project
+    #[command(group(ArgGroup::new("config_output").args(["config_only", "from_existing"]).multiple(true)))]
     Project {
         /// Project name (in kebab-case)
-        #[arg(long)]
-        name: String,
+        #[arg(long, required_unless_present = "from_existing")]
+        name: Option<String>,
         
         /// Programming language for the project
-        #[arg(long)]
-        language: String,
+        #[arg(long, required_unless_present = "from_existing", ignore_case = true, value_parser = language_value_parser())]
+        language: Option<String>,
+
+        /// Infer the configuration from an existing project directory instead of generating one
+        #[arg(long, conflicts_with = "strict")]
+        from_existing: Option<String>,
         
         /// Optional project description
         #[arg(long)]
         description: Option<String>,
+
+        /// Fail instead of defaulting fields missing from the generated config
+        #[arg(long)]
+        strict: bool,
+
+        /// Only generate the project configuration; skip scaffolding
+        #[arg(long)]
+        config_only: bool,
+
+        /// Where to write the generated configuration (stdout if omitted)
+        #[arg(long, requires = "config_output")]
+        output: Option<String>,
+
+        /// Abort on validation "errors" (default) or also on "warnings"
+        #[arg(long, default_value = "errors")]
+        fail_on: String,
+
+        /// Replace "latest" Rust dependency versions with the newest crates.io release
+        #[arg(long)]
+        resolve_versions: bool,
+
+        /// Generation profile from the system config (e.g. "draft")
+        #[arg(long, conflicts_with = "from_existing")]
+        profile: Option<String>,
+
+        /// System config file to read profiles from
+        #[arg(long, requires = "profile")]
+        config: Option<String>,
+
+        /// Build the generated project (cargo check, npm run build, py_compile) and fail if it does not compile
+        #[arg(long)]
+        verify: bool,
+
+        /// Generate reproducibly: temperature 0 and a fixed sampling seed
+        #[arg(long)]
+        deterministic: bool,
+
+        /// Sampling seed sent to providers that support seeded generation
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+
+    /// Summarize task counts by status and the estimated remaining duration
+    Status {
+        /// State snapshot (JSON) to summarize
+        #[arg(long)]
+        snapshot: Option<String>,
     },
+
+    /// Generate one project per line of a prompts file, several at a time
+    Batch {
+        /// File with one project prompt per line
+        #[arg(long)]
+        prompts: String,
+
+        /// Maximum number of projects generated at once
+        #[arg(long, default_value = "1")]
+        parallel: String,
+
+        /// Directory the generated projects are written under
+        #[arg(long, default_value = "build")]
+        output_dir: String,
+    },
+
+    /// List the models offered by the inference endpoint
+    Models,
+
+    /// Create, read, update, delete or list documentation files
+    Doc(tools::DocArgs),
+
+    /// Add a dependency to a generated project's Cargo.toml or package.json
+    AddDependency {
+        /// Directory of the generated project
+        #[arg(long)]
+        dir: String,
+
+        /// Name of the crate or package to add
+        #[arg(long)]
+        name: String,
+
+        /// Version requirement for the dependency
+        #[arg(long)]
+        version: String,
+
+        /// Add as a development dependency
+        #[arg(long)]
+        dev: bool,
+    },
+}
+
+const TOOLS: &[(&str, &str)] = &[
+    ("project", "Generate a new project"),
+    ("build", "Execute build commands"),
+    ("add-dependency", "Add a dependency to a generated project"),
+    ("status", "Summarize task statuses"),
+    ("batch", "Generate projects for many prompts concurrently"),
+    ("doc", "Manage documentation files"),
+    ("models", "List the models offered by the inference endpoint"),
+];
+
+fn write_tool_list(out: &mut dyn Write, palette: Palette) -> Result<()> {
+    writeln!(out, "{}", palette.heading("Available tools:"))?;
+    for (name, description) in TOOLS {
+        writeln!(out, "  - {}: {}", palette.name(name), description)?;
+    }
+    Ok(())
+}
+
+fn write_model_list(out: &mut dyn Write, models: &[ModelInfo]) -> Result<()> {
+    if models.is_empty() {
+        writeln!(out, "No models available")?;
+    }
+    for model in models {
+        if model.owned_by.is_empty() {
+            writeln!(out, "  - {}", model.id)?;
+        } else {
+            writeln!(out, "  - {} ({})", model.id, model.owned_by)?;
+        }
+    }
+    Ok(())
 }
 
 impl ToolsCli {
-    pub async fn execute(&self) -> Result<()> {
+    pub async fn execute(&self, dry_run: DryRun) -> Result<()> {
         match &self.command {
-            ToolCommands::List => {
-                println!("Available tools:");
-                println!("  - project: Generate a new project");
-                println!("  - build: Execute build commands");
-                Ok(())
-            },
+            ToolCommands::List => write_tool_list(&mut std::io::stdout(), style::palette()),
             ToolCommands::Info { name } => {
+                let palette = style::palette();
                 match name.as_str() {
                     "project" => {
-                        println!("project - Generate a new project");
+                        println!("{} - Generate a new project", palette.name("project"));
                         println!("\nUsage: build-system tools project --name <name> --language <language>");
-                        println!("\nArguments:");
+                        println!("\n{}", palette.heading("Arguments:"));
                         println!("  --name        Project name (in kebab-case)");
-                        println!("  --language    Programming language for the project");
+                        println!("  --language    Programming language for the project ({})", supported_languages().join(", "));
                         println!("  --description Optional project description");
+                        println!("  --from-existing Infer the configuration from an existing project directory");
+                        println!("  --strict      Fail instead of defaulting missing config fields");
+                        println!("  --config-only Only generate the project configuration");
+                        println!("  --output      File to write the configuration to (with --config-only)");
+                        println!("  --fail-on     Abort on validation errors (default) or warnings");
+                        println!("  --resolve-versions Resolve \"latest\" Rust dependency versions via crates.io");
+                        println!("  --profile     Generation profile from the system config");
+                        println!("  --config      System config file to read profiles from (default: config.toml)");
+                        println!("  --verify      Build the generated project and fail if it does not compile");
+                        println!("  --deterministic Use temperature 0 and a fixed seed for reproducible output");
+                        println!("  --seed        Sampling seed for providers that support it");
                     },
                     "build" => {
-                        println!("build - Execute build commands");
+                        println!("{} - Execute build commands", palette.name("build"));
                         println!("\nUsage: build-system tools build --command <command> --dir <directory>");
-                        println!("\nArguments:");
+                        println!("\n{}", palette.heading("Arguments:"));
                         println!("  --command    Build command to execute (build, test, dev, clean)");
                         println!("  --dir        Working directory for the build command");
                     },
-                    _ => println!("Unknown tool: {}", name),
+                    "status" => {
+                        println!("{} - Summarize task statuses", palette.name("status"));
+                        println!("\nUsage: build-system tools status [--snapshot <file>]");
+                        println!("\n{}", palette.heading("Arguments:"));
+                        println!("  --snapshot   State snapshot (JSON) to summarize");
+                    },
+                    "batch" => {
+                        println!("{} - Generate projects for many prompts concurrently", palette.name("batch"));
+                        println!("\nUsage: build-system tools batch --prompts <file> [--parallel <n>] [--output-dir <dir>]");
+                        println!("\n{}", palette.heading("Arguments:"));
+                        println!("  --prompts    File with one project prompt per line");
+                        println!("  --parallel   Maximum number of projects generated at once (default: 1)");
+                        println!("  --output-dir Directory the projects are written under (default: build)");
+                    },
+                    "doc" => {
+                        println!("{} - Manage documentation files", palette.name("doc"));
+                        println!("\nUsage: build-system tools doc <create|read|update|delete|list> [options]");
+                        println!("\n{}", palette.heading("Subcommands:"));
+                        println!("  create --path <file> --title <title> [--type <type>] [--content <text>]");
+                        println!("  read   --path <file>");
+                        println!("  update --path <file> --title <title> [--type <type>] [--content <text>]");
+                        println!("  delete --path <file>");
+                        println!("  list   [--dir <directory>]");
+                    },
+                    "models" => {
+                        println!("{} - List the models offered by the inference endpoint", palette.name("models"));
+                        println!("\nUsage: build-system tools models");
+                        println!("\nReads INFERENCE_API_BASE_URL and INFERENCE_API_KEY like the project tool.");
+                    },
+                    "add-dependency" => {
+                        println!("{} - Add a dependency to a generated project", palette.name("add-dependency"));
+                        println!("\nUsage: build-system tools add-dependency --dir <project> --name <name> --version <version> [--dev]");
+                        println!("\n{}", palette.heading("Arguments:"));
+                        println!("  --dir        Directory containing Cargo.toml or package.json");
+                        println!("  --name       Crate or package name");
+                        println!("  --version    Version requirement");
+                        println!("  --dev        Add as a development dependency");
+                    },
+                    _ => println!("{}", palette.error(&format!("Unknown tool: {}", name))),
                 }
                 Ok(())
             },
@@ -82,17 +257,144 @@ impl ToolsCli {
                 println!("Executing build command: {} in directory: {}", command, dir);
                 Ok(())
             },
-            ToolCommands::Project { name, language, description } => {
+            ToolCommands::Project { name, language, description, from_existing, strict, config_only, output, fail_on, resolve_versions, profile, config, verify, deterministic, seed } => {
                 // Pass the arguments directly to the project tool
+                let mut args = vec!["project".to_string()];
+                if let Some(name) = name {
+                    args.push("--name".to_string());
+                    args.push(name.clone());
+                }
+                if let Some(language) = language {
+                    args.push("--language".to_string());
+                    args.push(language.clone());
+                }
+                if let Some(dir) = from_existing {
+                    args.push("--from-existing".to_string());
+                    args.push(dir.clone());
+                }
+                if *strict {
+                    args.push("--strict".to_string());
+                }
+                if *config_only {
+                    args.push("--config-only".to_string());
+                }
+                if let Some(output) = output {
+                    args.push("--output".to_string());
+                    args.push(output.clone());
+                }
+                args.push("--fail-on".to_string());
+                args.push(fail_on.clone());
+                if *resolve_versions {
+                    args.push("--resolve-versions".to_string());
+                }
+                if let Some(profile) = profile {
+                    args.push("--profile".to_string());
+                    args.push(profile.clone());
+                }
+                if let Some(config) = config {
+                    args.push("--config".to_string());
+                    args.push(config.clone());
+                }
+                if *verify {
+                    args.push("--verify".to_string());
+                }
+                if *deterministic {
+                    args.push("--deterministic".to_string());
+                }
+                if let Some(seed) = seed {
+                    args.push("--seed".to_string());
+                    args.push(seed.to_string());
+                }
+                tools::run_tool("project", args, dry_run).await
+            }
+            ToolCommands::Status { snapshot } => {
+                let state_manager = StateManager::new();
+                if let Some(path) = snapshot {
+                    let bytes = std::fs::read(path)
+                        .with_context(|| format!("Failed to read snapshot: {}", path))?;
+                    state_manager.restore_snapshot(StateSnapshot::load(&bytes)?).await?;
+                }
+
+                let counts = state_manager.status_counts().await?;
+                println!("Task status summary:");
+                for status in TaskStatus::ALL.iter() {
+                    println!("  {:<10} {}", format!("{:?}:", status), counts.get(status).copied().unwrap_or(0));
+                }
+                println!("  {:<10} {}", "Total:", counts.values().sum::<usize>());
+                println!("Estimated remaining: {:?}", state_manager.estimated_remaining().await?);
+                Ok(())
+            },
+            ToolCommands::Batch { prompts, parallel, output_dir } => {
                 let args = vec![
-                    "project".to_string(),
+                    "batch".to_string(),
+                    "--prompts".to_string(),
+                    prompts.clone(),
+                    "--parallel".to_string(),
+                    parallel.clone(),
+                    "--output-dir".to_string(),
+                    output_dir.clone(),
+                ];
+                tools::run_tool("batch", args, dry_run).await
+            }
+            ToolCommands::Models => {
+                let models = InferenceClient::new()?.list_models().await?;
+                write_model_list(&mut std::io::stdout(), &models)
+            }
+            ToolCommands::Doc(args) => tools::handle_doc(args.clone(), dry_run).await,
+            ToolCommands::AddDependency { dir, name, version, dev } => {
+                let mut args = vec![
+                    "add-dependency".to_string(),
+                    "--dir".to_string(),
+                    dir.clone(),
                     "--name".to_string(),
                     name.clone(),
-                    "--language".to_string(),
-                    language.clone(),
+                    "--version".to_string(),
+                    version.clone(),
                 ];
-                tools::run_tool("project", args).await
+                if *dev {
+                    args.push("--dev".to_string());
+                }
+                tools::run_tool("add-dependency", args, dry_run).await
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool_list(palette: Palette) -> String {
+        let mut out = Vec::new();
+        write_tool_list(&mut out, palette).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_project_language_must_be_supported() {
+        assert!(ToolsCli::try_parse_from(["tools", "project", "--name", "demo", "--language", "cobol"]).is_err());
+        assert!(ToolsCli::try_parse_from(["tools", "project", "--name", "demo", "--language", "go"]).is_ok());
+    }
+
+    #[test]
+    fn test_tool_list_color() {
+        let plain = tool_list(Palette::new(false));
+        assert!(!plain.contains('\x1b'));
+        assert!(plain.contains("  - project: Generate a new project"));
+
+        let colored = tool_list(Palette::new(true));
+        assert!(colored.contains("\x1b["));
+        assert!(colored.contains("project"));
+    }
+
+    #[test]
+    fn test_model_list() {
+        let models = vec![
+            ModelInfo { id: "gpt-4o".to_string(), owned_by: "openai".to_string() },
+            ModelInfo { id: "local-llama".to_string(), owned_by: String::new() },
+        ];
+        let mut out = Vec::new();
+        write_model_list(&mut out, &models).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "  - gpt-4o (openai)\n  - local-llama\n");
+    }
+}
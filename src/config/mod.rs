@@ -1,5 +1,8 @@
+use anyhow::{Context, Result, anyhow};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use crate::inference::{GenerationProfile, StreamFormat};
 
 /// Configuration management for the build system
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +15,40 @@ pub struct SystemConfig {
     
     /// Logging configuration
     pub log_level: String,
+
+    /// Directory for streamed AI response files; the inference client's default when unset
+    #[serde(default)]
+    pub response_dir: Option<PathBuf>,
+
+    /// Whether streamed AI responses are written to disk at all
+    #[serde(default = "default_persist_responses")]
+    pub persist_responses: bool,
+
+    /// External command tools registered alongside the built-in ones
+    #[serde(default)]
+    pub tools: Vec<ExternalToolConfig>,
+
+    /// Extra regex patterns masked in response files and logs, on top of the defaults
+    #[serde(default)]
+    pub redaction_patterns: Vec<String>,
+
+    /// Wire format of streamed completions (`open_ai_sse` or `json_lines`)
+    #[serde(default)]
+    pub stream_format: StreamFormat,
+
+    /// Named generation profiles, declared as `[profiles.<name>]` tables
+    #[serde(default)]
+    pub profiles: BTreeMap<String, GenerationProfile>,
+}
+
+/// An external tool declared in configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalToolConfig {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Shell command template; `{{key}}` is replaced with the `key` argument
+    pub command: String,
 }
 
 impl Default for SystemConfig {
@@ -20,6 +57,40 @@ impl Default for SystemConfig {
             base_project_dir: PathBuf::from("build"),
             template_dir: PathBuf::from(".reference/templates"),
             log_level: "info".to_string(),
+            response_dir: None,
+            persist_responses: true,
+            tools: Vec::new(),
+            redaction_patterns: Vec::new(),
+            stream_format: StreamFormat::default(),
+            profiles: BTreeMap::new(),
         }
     }
 }
+
+impl SystemConfig {
+    pub fn from_toml(content: &str) -> Result<Self> {
+        toml::from_str(content).context("Failed to parse system config")
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read system config: {}", path.display()))?;
+        Self::from_toml(&content)
+    }
+
+    /// Look up a generation profile by name, with its `name` filled in
+    pub fn profile(&self, name: &str) -> Result<GenerationProfile> {
+        let profile = self.profiles.get(name).ok_or_else(|| {
+            let known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            anyhow!("Unknown generation profile '{}' (available: {})", name, known.join(", "))
+        })?;
+        Ok(GenerationProfile {
+            name: name.to_string(),
+            ..profile.clone()
+        })
+    }
+}
+
+fn default_persist_responses() -> bool {
+    true
+}
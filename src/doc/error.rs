@@ -2,6 +2,8 @@ use thiserror::Error;
 use std::io;
 use serde_json;
 
+use crate::doc::types::DocumentationStepStatus;
+
 #[derive(Error, Debug)]
 pub enum DocumentationError {
     #[error("IO error: {0}")]
@@ -16,6 +18,12 @@ pub enum DocumentationError {
     #[error("Permission denied")]
     PermissionDenied,
 
+    #[error("Invalid step status transition from {from} to {to}")]
+    InvalidTransition {
+        from: DocumentationStepStatus,
+        to: DocumentationStepStatus,
+    },
+
     #[error("Unknown documentation error: {0}")]
     Other(String),
 }
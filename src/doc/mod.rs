@@ -4,6 +4,7 @@ use async_trait::async_trait;
 use std::fs;
 
 use crate::doc::types::{Documentation, DocType};
+use pulldown_cmark::{html, Options, Parser};
 use crate::doc::error::DocumentationError;
 
 pub mod error;
@@ -36,6 +37,15 @@ impl FileDocumentationEngine {
         Self { base_path }
     }
 
+    /// Resolve a doc path against `base_path` when it is relative
+    pub fn resolve_path(&self, path: &Path) -> PathBuf {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.base_path.join(path)
+        }
+    }
+
     pub async fn generate_markdown(&self, doc: &Documentation) -> Result<String, DocumentationError> {
         let md_content = match doc.doc_type {
             DocType::ProjectOverview => {
@@ -54,11 +64,91 @@ impl FileDocumentationEngine {
                     doc.metadata.get("design_principles").cloned().unwrap_or_default()
                 )
             },
+            DocType::Json => {
+                // Re-emit structured content in canonical pretty form, rejecting invalid JSON
+                let value: serde_json::Value = serde_json::from_str(&doc.content)?;
+                serde_json::to_string_pretty(&value)?
+            },
             _ => doc.content.clone(),
         };
 
         Ok(md_content)
     }
+
+    /// Render `doc` as a standalone HTML page and write it to `out` (resolved against
+    /// `base_path` when relative). The generated markdown becomes the page body, followed
+    /// by each step with its code and output in escaped `<pre><code>` blocks.
+    pub async fn export_html(&self, doc: &Documentation, out: &Path) -> Result<(), DocumentationError> {
+        let markdown = match doc.doc_type {
+            DocType::Json => format!("```json\n{}\n```\n", self.generate_markdown(doc).await?),
+            _ => self.generate_markdown(doc).await?,
+        };
+
+        let mut page = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        page.push_str(&format!("<title>{}</title>\n</head>\n<body>\n<header>\n", escape_html(&doc.title)));
+        page.push_str(&format!("<h1>{}</h1>\n", escape_html(&doc.title)));
+        if !doc.tags.is_empty() {
+            page.push_str("<ul class=\"tags\">\n");
+            for tag in &doc.tags {
+                page.push_str(&format!("<li>{}</li>\n", escape_html(tag)));
+            }
+            page.push_str("</ul>\n");
+        }
+        page.push_str("</header>\n<main>\n");
+        page.push_str(&render_markdown(&markdown));
+        page.push_str("</main>\n");
+
+        if !doc.steps.is_empty() {
+            page.push_str("<section class=\"steps\">\n<h2>Steps</h2>\n<ol>\n");
+            for step in &doc.steps {
+                page.push_str(&format!(
+                    "<li>\n<h3>{}</h3>\n<p class=\"status\">{}</p>\n",
+                    escape_html(&step.title),
+                    step.status
+                ));
+                if let Some(description) = &step.description {
+                    page.push_str(&render_markdown(description));
+                }
+                // Code and output are shown verbatim rather than parsed as markdown
+                if let Some(code) = &step.code {
+                    page.push_str(&format!("<pre><code>{}</code></pre>\n", escape_html(code)));
+                }
+                if let Some(output) = &step.output {
+                    page.push_str(&format!("<pre class=\"output\"><code>{}</code></pre>\n", escape_html(output)));
+                }
+                page.push_str("</li>\n");
+            }
+            page.push_str("</ol>\n</section>\n");
+        }
+        page.push_str("</body>\n</html>\n");
+
+        let path = self.resolve_path(out);
+        fs::create_dir_all(path.parent().unwrap_or(Path::new(".")))?;
+        fs::write(&path, page)?;
+
+        Ok(())
+    }
+}
+
+fn render_markdown(markdown: &str) -> String {
+    let mut rendered = String::new();
+    html::push_html(&mut rendered, Parser::new_ext(markdown, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH));
+    rendered
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
 }
 
 #[async_trait]
@@ -70,21 +160,23 @@ impl DocumentationEngine for FileDocumentationEngine {
     }
 
     async fn create_doc(&self, doc: &Documentation) -> Result<(), DocumentationError> {
+        let path = self.resolve_path(&doc.path);
+
         // Ensure the directory exists
-        fs::create_dir_all(doc.path.parent().unwrap_or(Path::new(".")))?;
+        fs::create_dir_all(path.parent().unwrap_or(Path::new(".")))?;
 
         // Generate markdown content
         let markdown_content = self.generate_markdown(doc).await?;
 
         // Write to file
-        fs::write(&doc.path, markdown_content)?;
+        fs::write(&path, markdown_content)?;
 
         Ok(())
     }
 
     async fn read_doc(&self, path: &Path) -> Result<Documentation, DocumentationError> {
         // Read the markdown content
-        let content = fs::read_to_string(path)?;
+        let content = fs::read_to_string(self.resolve_path(path))?;
 
         // TODO: Implement proper parsing of markdown to Documentation
         Ok(Documentation {
@@ -97,27 +189,116 @@ impl DocumentationEngine for FileDocumentationEngine {
     async fn update_doc(&self, doc: Documentation) -> Result<(), DocumentationError> {
         // Regenerate markdown and write to file
         let markdown_content = self.generate_markdown(&doc).await?;
-        fs::write(&doc.path, markdown_content)?;
+        fs::write(self.resolve_path(&doc.path), markdown_content)?;
 
         Ok(())
     }
 
     async fn delete_doc(&self, path: &Path) -> Result<(), DocumentationError> {
+        let path = self.resolve_path(path);
         if !path.exists() {
             return Err(DocumentationError::DocumentNotFound);
         }
 
-        fs::remove_file(path)?;
+        fs::remove_file(&path)?;
 
         Ok(())
     }
 
     async fn save_doc(&self, doc: &Documentation) -> Result<(), DocumentationError> {
-        let path = &doc.path;
-        
+        let path = self.resolve_path(&doc.path);
+
         let md_content = self.generate_markdown(doc).await?;
-        fs::write(path, md_content)?;
+        fs::write(&path, md_content)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_relative_doc_paths_resolve_against_base_path() -> Result<(), DocumentationError> {
+        let temp_dir = tempfile::tempdir()?;
+        let engine = FileDocumentationEngine::new(temp_dir.path().to_path_buf());
+
+        let doc = Documentation {
+            path: PathBuf::from("docs/overview.md"),
+            content: "Relative doc".to_string(),
+            ..Default::default()
+        };
+
+        engine.create_doc(&doc).await?;
+        let expected = temp_dir.path().join("docs/overview.md");
+        assert!(expected.exists());
+
+        let read = engine.read_doc(&doc.path).await?;
+        assert!(read.content.contains("Relative doc"));
+
+        engine.delete_doc(&doc.path).await?;
+        assert!(!expected.exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_json_doc_rendered_pretty() -> Result<(), DocumentationError> {
+        let engine = FileDocumentationEngine::new(PathBuf::from("."));
+        let doc = Documentation {
+            doc_type: DocType::Json,
+            content: r#"{"name":"build-system","tags":["rust","cli"]}"#.to_string(),
+            ..Default::default()
+        };
+
+        let rendered = engine.generate_markdown(&doc).await?;
+        assert_eq!(
+            rendered,
+            "{\n  \"name\": \"build-system\",\n  \"tags\": [\n    \"rust\",\n    \"cli\"\n  ]\n}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_json_doc_invalid_content_errors() {
+        let engine = FileDocumentationEngine::new(PathBuf::from("."));
+        let doc = Documentation {
+            doc_type: DocType::Json,
+            content: "{not json".to_string(),
+            ..Default::default()
+        };
+
+        let result = engine.generate_markdown(&doc).await;
+        assert!(matches!(result, Err(DocumentationError::SerializationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_export_html_escapes_step_code() -> Result<(), DocumentationError> {
+        let temp_dir = tempfile::tempdir()?;
+        let engine = FileDocumentationEngine::new(temp_dir.path().to_path_buf());
+
+        let mut doc = Documentation {
+            title: "Setup <guide>".to_string(),
+            content: "Run the **installer** first.".to_string(),
+            tags: vec!["setup".to_string()],
+            ..Default::default()
+        };
+        doc.steps.push(crate::doc::types::DocumentationStep {
+            title: "Compile".to_string(),
+            code: Some("if a < b && b > c { println!(\"</code>\"); }".to_string()),
+            ..Default::default()
+        });
+
+        engine.export_html(&doc, Path::new("site/setup.html")).await?;
+        let html = fs::read_to_string(temp_dir.path().join("site/setup.html"))?;
 
+        assert!(html.contains("<title>Setup &lt;guide&gt;</title>"));
+        assert!(html.contains("<li>setup</li>"));
+        assert!(html.contains("<strong>installer</strong>"));
+        assert!(html.contains(
+            "<pre><code>if a &lt; b &amp;&amp; b &gt; c { println!(&quot;&lt;/code&gt;&quot;); }</code></pre>"
+        ));
         Ok(())
     }
 }
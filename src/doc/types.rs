@@ -1,11 +1,17 @@
 use serde::{Serialize, Deserialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use std::time::Duration;
 use uuid::Uuid;
 use std::str::FromStr;
 use clap::ValueEnum;
 
+use crate::doc::error::DocumentationError;
+
+/// Reading speed assumed by `Documentation::estimated_reading_time`
+pub const WORDS_PER_MINUTE: u64 = 200;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ValueEnum)]
 pub enum DocType {
     /// Project overview documentation
@@ -58,6 +64,27 @@ impl DocType {
             DocType::Other => "other",
         }
     }
+
+    /// Conventional file name for a document of this type
+    pub fn default_filename(&self) -> &'static str {
+        match self {
+            DocType::ProjectOverview => "README.md",
+            DocType::TechnicalSpec => "TECHNICAL_SPEC.md",
+            DocType::Architecture => "ARCHITECTURE.md",
+            DocType::Api => "API.md",
+            DocType::UserManual => "USER_MANUAL.md",
+            DocType::Json => "config.json",
+            DocType::Markdown => "DOCUMENTATION.md",
+            DocType::Other => "NOTES.txt",
+        }
+    }
+
+    /// Extension of `default_filename`, without the dot
+    pub fn extension(&self) -> &'static str {
+        self.default_filename()
+            .rsplit_once('.')
+            .map_or("", |(_, extension)| extension)
+    }
 }
 
 impl Default for DocType {
@@ -125,6 +152,40 @@ impl std::fmt::Display for DocumentationStepStatus {
     }
 }
 
+impl DocumentationStepStatus {
+    /// Whether a step may move from this status to `next`
+    pub fn can_transition_to(&self, next: &DocumentationStepStatus) -> bool {
+        use DocumentationStepStatus::*;
+        matches!(
+            (self, next),
+            (Pending, InProgress) | (InProgress, Completed) | (InProgress, Failed) | (Failed, InProgress)
+        )
+    }
+}
+
+impl DocumentationStep {
+    /// Move the step to `new_status`, rejecting illegal transitions.
+    /// `completed_at` is set when the step completes and cleared otherwise.
+    pub fn set_status(&mut self, new_status: DocumentationStepStatus) -> Result<(), DocumentationError> {
+        if !self.status.can_transition_to(&new_status) {
+            return Err(DocumentationError::InvalidTransition {
+                from: self.status.clone(),
+                to: new_status,
+            });
+        }
+
+        let now = Utc::now();
+        self.completed_at = if new_status == DocumentationStepStatus::Completed {
+            Some(now)
+        } else {
+            None
+        };
+        self.status = new_status;
+        self.updated_at = now;
+        Ok(())
+    }
+}
+
 impl Documentation {
     pub fn new(
         title: String,
@@ -157,4 +218,117 @@ impl Documentation {
         self.metadata.insert(key.to_string(), value.to_string());
         self
     }
+
+    /// Where this document conventionally lives under `base`, based on its type
+    pub fn suggested_path(&self, base: &Path) -> PathBuf {
+        base.join(self.doc_type.default_filename())
+    }
+
+    /// Whitespace-separated words in the content and in each step's description and output
+    pub fn word_count(&self) -> usize {
+        let steps = self.steps.iter().flat_map(|step| [&step.description, &step.output]).flatten();
+        std::iter::once(&self.content)
+            .chain(steps)
+            .map(|text| text.split_whitespace().count())
+            .sum()
+    }
+
+    /// Time to read `word_count` words at `WORDS_PER_MINUTE`
+    pub fn estimated_reading_time(&self) -> Duration {
+        Duration::from_secs(60) * self.word_count() as u32 / WORDS_PER_MINUTE as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_filenames() {
+        let expected = [
+            (DocType::ProjectOverview, "README.md", "md"),
+            (DocType::TechnicalSpec, "TECHNICAL_SPEC.md", "md"),
+            (DocType::Architecture, "ARCHITECTURE.md", "md"),
+            (DocType::Api, "API.md", "md"),
+            (DocType::UserManual, "USER_MANUAL.md", "md"),
+            (DocType::Json, "config.json", "json"),
+            (DocType::Markdown, "DOCUMENTATION.md", "md"),
+            (DocType::Other, "NOTES.txt", "txt"),
+        ];
+        assert_eq!(expected.len(), DocType::value_variants().len());
+        for (doc_type, filename, extension) in expected {
+            assert_eq!(doc_type.default_filename(), filename);
+            assert_eq!(doc_type.extension(), extension);
+        }
+    }
+
+    #[test]
+    fn test_suggested_path() {
+        let doc = Documentation::new(
+            "Design".to_string(),
+            String::new(),
+            DocType::Architecture,
+            PathBuf::new(),
+            "demo".to_string(),
+        );
+        assert_eq!(doc.suggested_path(Path::new("docs")), PathBuf::from("docs/ARCHITECTURE.md"));
+    }
+
+    #[test]
+    fn test_word_count_and_reading_time() {
+        let mut doc = Documentation::new(
+            "Setup".to_string(),
+            "Install the   toolchain\nthen build.".to_string(),
+            DocType::UserManual,
+            PathBuf::new(),
+            "demo".to_string(),
+        );
+        doc.steps.push(DocumentationStep {
+            description: Some("Run cargo build".to_string()),
+            code: Some("cargo build --release".to_string()),
+            output: Some("Finished release profile".to_string()),
+            ..Default::default()
+        });
+        doc.steps.push(DocumentationStep {
+            description: Some("Done".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(doc.word_count(), 12);
+        assert_eq!(doc.estimated_reading_time(), Duration::from_millis(3600));
+
+        doc.content = "word ".repeat(1000);
+        assert_eq!(doc.estimated_reading_time(), Duration::from_millis(302_100));
+    }
+
+    #[test]
+    fn test_step_status_legal_transitions() {
+        let mut step = DocumentationStep::default();
+
+        step.set_status(DocumentationStepStatus::InProgress).unwrap();
+        step.set_status(DocumentationStepStatus::Failed).unwrap();
+        assert!(step.completed_at.is_none());
+
+        step.set_status(DocumentationStepStatus::InProgress).unwrap();
+        step.set_status(DocumentationStepStatus::Completed).unwrap();
+        assert_eq!(step.status, DocumentationStepStatus::Completed);
+        assert!(step.completed_at.is_some());
+    }
+
+    #[test]
+    fn test_step_status_illegal_transition() {
+        let mut step = DocumentationStep::default();
+        step.set_status(DocumentationStepStatus::InProgress).unwrap();
+        step.set_status(DocumentationStepStatus::Completed).unwrap();
+
+        let result = step.set_status(DocumentationStepStatus::Pending);
+        assert!(matches!(
+            result,
+            Err(DocumentationError::InvalidTransition {
+                from: DocumentationStepStatus::Completed,
+                to: DocumentationStepStatus::Pending,
+            })
+        ));
+        assert_eq!(step.status, DocumentationStepStatus::Completed);
+        assert!(step.completed_at.is_some());
+    }
 }
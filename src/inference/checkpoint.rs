@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+use crate::util::stable_hash;
+
+/// Storage key prefix for saved generations, followed by the prompt hash
+pub const CHECKPOINT_PREFIX: &str = "generation_";
+
+/// Model output saved before scaffolding starts, so a failed scaffold can be retried
+/// with `InferenceClient::generate_project_resume` instead of another API call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenerationCheckpoint {
+    pub prompt: String,
+    /// Message content exactly as the model returned it
+    pub raw_response: String,
+    /// Config JSON parsed from `raw_response`
+    pub config: String,
+}
+
+impl GenerationCheckpoint {
+    pub fn key(prompt_hash: &str) -> String {
+        format!("{}{}", CHECKPOINT_PREFIX, prompt_hash)
+    }
+}
+
+/// Hash identifying a prompt's checkpoint. It is stable across builds, so a hash
+/// printed by one run can be resumed by another.
+pub fn prompt_hash(prompt: &str) -> String {
+    stable_hash(prompt)
+}
@@ -0,0 +1,35 @@
+use thiserror::Error;
+
+use crate::build::BuildError;
+
+#[derive(Error, Debug)]
+pub enum InferenceError {
+    #[error("Request failed: {0}")]
+    Request(#[from] anyhow::Error),
+
+    #[error("No JSON object found in model response")]
+    NoJson,
+
+    #[error("Model response does not match schema: {}", .0.join("; "))]
+    SchemaMismatch(Vec<String>),
+
+    #[error("Model response does not match the expected type: {0}")]
+    Deserialize(#[from] serde_json::Error),
+
+    #[error("Request was cancelled")]
+    Cancelled,
+}
+
+/// Which stage of project generation failed, so callers can retry only that stage:
+/// a config failure is worth another model call, a scaffold failure is not
+#[derive(Error, Debug)]
+pub enum GenerationError {
+    #[error("Failed to generate project config: {0}")]
+    Config(#[from] InferenceError),
+
+    #[error("Failed to scaffold project: {0}")]
+    Scaffold(#[from] BuildError),
+
+    #[error("Generated project config is invalid: {}", .0.join("; "))]
+    Validation(Vec<String>),
+}
@@ -0,0 +1,137 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::inference::InferenceClient;
+use crate::prompt::storage::Storage;
+
+/// Key prefix under which prompt embeddings are stored
+const INDEX_KEY_PREFIX: &str = "project_index_";
+
+/// A previously generated project's prompt and its embedding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedPrompt {
+    pub prompt: String,
+    pub embedding: Vec<f32>,
+}
+
+/// A stored prompt ranked by similarity to a query
+#[derive(Debug, Clone)]
+pub struct SimilarProject {
+    pub prompt: String,
+    pub similarity: f32,
+}
+
+/// Similarity index over the prompts of previously generated projects
+pub struct ProjectIndex<'a> {
+    storage: &'a Storage,
+}
+
+impl<'a> ProjectIndex<'a> {
+    pub fn new(storage: &'a Storage) -> Self {
+        Self { storage }
+    }
+
+    /// Embed `prompts` in batches and add them to the index
+    pub async fn add_prompts(&self, client: &InferenceClient, prompts: &[String]) -> Result<()> {
+        let embeddings = client.embed(prompts).await?;
+        for (prompt, embedding) in prompts.iter().zip(embeddings) {
+            self.insert(prompt, embedding)?;
+        }
+        Ok(())
+    }
+
+    /// Add a prompt with a precomputed embedding
+    pub fn insert(&self, prompt: &str, embedding: Vec<f32>) -> Result<()> {
+        let entry = IndexedPrompt {
+            prompt: prompt.to_string(),
+            embedding,
+        };
+        self.storage.store(&format!("{}{}", INDEX_KEY_PREFIX, Uuid::new_v4()), &entry)
+    }
+
+    /// Find the `k` stored prompts most similar to `prompt`
+    pub async fn nearest(&self, client: &InferenceClient, prompt: &str, k: usize) -> Result<Vec<SimilarProject>> {
+        let embedding = client.embed(&[prompt.to_string()]).await?
+            .pop()
+            .unwrap_or_default();
+        self.nearest_to(&embedding, k)
+    }
+
+    /// Find the `k` stored prompts whose embeddings are closest to `embedding`
+    pub fn nearest_to(&self, embedding: &[f32], k: usize) -> Result<Vec<SimilarProject>> {
+        let mut ranked = Vec::new();
+        for key in self.storage.list_keys()? {
+            if !key.starts_with(INDEX_KEY_PREFIX) {
+                continue;
+            }
+            if let Some(entry) = self.storage.load::<IndexedPrompt>(&key)? {
+                ranked.push(SimilarProject {
+                    similarity: cosine_similarity(embedding, &entry.embedding),
+                    prompt: entry.prompt,
+                });
+            }
+        }
+
+        ranked.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+        ranked.truncate(k);
+        Ok(ranked)
+    }
+}
+
+/// Cosine similarity of two vectors; 0.0 when either is empty, zero, or lengths differ
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::test_server::{MockResponse, MockServer};
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_embed_and_nearest() -> Result<()> {
+        let server = MockServer::start(vec![
+            MockResponse::Json(200, serde_json::json!({
+                "data": [
+                    { "index": 1, "embedding": [0.0, 1.0, 0.0] },
+                    { "index": 0, "embedding": [1.0, 0.0, 0.0] }
+                ]
+            }).to_string()),
+            MockResponse::Json(200, serde_json::json!({
+                "data": [{ "index": 0, "embedding": [0.9, 0.1, 0.0] }]
+            }).to_string()),
+        ]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+
+        let prompts = vec!["A Rust web server".to_string(), "A Python CLI".to_string()];
+        let embeddings = client.embed(&prompts).await?;
+        assert_eq!(embeddings.len(), 2);
+        assert!(embeddings.iter().all(|e| e.len() == 3));
+        assert_eq!(embeddings[0], vec![1.0, 0.0, 0.0]);
+
+        let dir = tempdir()?;
+        let storage = Storage::new(dir.path())?;
+        let index = ProjectIndex::new(&storage);
+        for (prompt, embedding) in prompts.iter().zip(embeddings) {
+            index.insert(prompt, embedding)?;
+        }
+
+        let nearest = index.nearest(&client, "A Rust HTTP API", 1).await?;
+        assert_eq!(nearest.len(), 1);
+        assert_eq!(nearest[0].prompt, "A Rust web server");
+
+        Ok(())
+    }
+}
@@ -3,13 +3,95 @@ use async_openai::{
     types::Role,
 };
 use anyhow::{Context, Result, anyhow};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde_json::json;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tokio_util::sync::CancellationToken;
 
-use crate::prompt::Prompt;
+use crate::prompt::{Conversation, Prompt};
+use crate::prompt::project_generation::ProjectGenerationConfig;
 use crate::state::types::TaskId;
 use crate::state::StateManager;
-use crate::build::BuildManager;
+use crate::build::{BuildError, BuildManager};
+use crate::config::SystemConfig;
+use crate::prompt::storage::{SchemaValidator, Storage};
+
+pub mod checkpoint;
+pub mod error;
+pub mod index;
+pub mod profile;
+pub mod redact;
+mod single_flight;
+pub mod stream;
+
+pub use checkpoint::{prompt_hash, GenerationCheckpoint};
+pub use error::{GenerationError, InferenceError};
+pub use profile::GenerationProfile;
+pub use redact::{redact, Redactor};
+pub use stream::StreamFormat;
+
+use single_flight::SingleFlight;
+
+#[cfg(test)]
+pub(crate) mod test_server;
+
+/// Directory where streamed responses are written for later inspection, unless overridden
+/// via `INFERENCE_RESPONSE_DIR`, `SystemConfig::response_dir` or `with_response_dir`
+fn default_response_dir() -> PathBuf {
+    std::env::temp_dir().join("build-system").join("ai_responses")
+}
+
+/// Seed sent in deterministic mode when no explicit seed is configured
+pub const DETERMINISTIC_SEED: u64 = 0;
+
+/// Maximum number of texts sent in a single embeddings request
+const EMBEDDING_BATCH_SIZE: usize = 64;
+
+/// On-disk override for the project generation system prompt
+const PROJECT_TEMPLATE_PATH: &str = "templates/project_generation.txt";
+
+/// Project generation system prompt used when no on-disk override exists
+const DEFAULT_PROJECT_TEMPLATE: &str = include_str!("../../templates/project_generation.txt");
+
+/// Load the project generation system prompt, preferring `path` and falling back to the
+/// embedded default when the file does not exist
+pub fn load_project_template(path: &Path) -> Result<String> {
+    match std::fs::read_to_string(path) {
+        Ok(template) => Ok(template),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(DEFAULT_PROJECT_TEMPLATE.to_string()),
+        Err(e) => Err(e)
+            .with_context(|| format!("Failed to read project generation prompt template: {}", path.display())),
+    }
+}
+
+/// Validate an API base URL and normalize it to have no trailing slash. A bare host
+/// gets the conventional `/v1` prefix, and a repeated `/v1/v1` collapses to one.
+pub fn normalize_base_url(base_url: &str) -> Result<String> {
+    let mut url = url::Url::parse(base_url.trim()).map_err(|e| anyhow!(
+        "Invalid inference base URL '{}': {} (expected something like https://api.openai.com/v1)",
+        base_url, e
+    ))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(anyhow!(
+            "Invalid inference base URL '{}': must be an http(s) URL such as https://api.openai.com/v1",
+            base_url
+        ));
+    }
+
+    let mut path = url.path().trim_end_matches('/').to_string();
+    while path.ends_with("/v1/v1") {
+        path.truncate(path.len() - "/v1".len());
+    }
+    if path.is_empty() {
+        path = "/v1".to_string();
+    }
+    url.set_path(&path);
+
+    Ok(url.as_str().trim_end_matches('/').to_string())
+}
 
 #[derive(Clone)]
 pub struct OpenAIConfigWrapper(OpenAIConfig);
@@ -24,10 +106,111 @@ impl OpenAIConfigWrapper {
     }
 }
 
+/// Outgoing request details passed to an exchange hook, with the API key redacted
+#[derive(Debug, Clone)]
+pub struct RequestLog {
+    pub url: String,
+    pub model: String,
+    pub headers: Vec<(String, String)>,
+    pub body: serde_json::Value,
+}
+
+/// Response details passed to an exchange hook
+#[derive(Debug, Clone)]
+pub struct ResponseLog {
+    pub status: u16,
+    pub body: String,
+    pub latency: Duration,
+}
+
+/// An API endpoint with its own credentials and model, tried when the ones before it
+/// in the fallback chain fail
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelEndpoint {
+    pub base_url: String,
+    pub api_key: String,
+    pub model: String,
+}
+
+impl ModelEndpoint {
+    /// The base URL is validated and normalized with `normalize_base_url`
+    pub fn new(base_url: &str, api_key: &str, model: &str) -> Result<Self> {
+        Ok(Self {
+            base_url: normalize_base_url(base_url)?,
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+        })
+    }
+}
+
+/// A model the endpoint offers, as listed by `/models`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct ModelInfo {
+    pub id: String,
+    /// Organization owning the model; empty when the endpoint does not say
+    #[serde(default)]
+    pub owned_by: String,
+}
+
+/// A failed request and whether the next endpoint in the fallback chain should be tried
+struct Failure {
+    error: anyhow::Error,
+    fall_back: bool,
+}
+
+impl Failure {
+    fn fatal(error: anyhow::Error) -> Self {
+        Self { error, fall_back: false }
+    }
+}
+
+/// Unauthorized, rate limited and unavailable endpoints may work for another provider;
+/// other errors would fail the same way everywhere
+fn falls_back(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 401 | 403 | 408 | 429) || status.is_server_error()
+}
+
+/// Callback invoked after every request/response exchange
+pub type ExchangeHook = Arc<dyn Fn(&RequestLog, &ResponseLog) + Send + Sync>;
+
+/// Undecoded reply to a POST, shared by deduplicated callers
+#[derive(Debug, Clone)]
+struct RawReply {
+    status: reqwest::StatusCode,
+    body: String,
+    latency: Duration,
+}
+
+type SharedReply = std::result::Result<RawReply, Arc<anyhow::Error>>;
+
 pub struct InferenceClient {
     api_key: String,
     base_url: String,
     model: String,
+    embedding_model: String,
+    headers: HeaderMap,
+    /// Shared by every request so connections are pooled
+    http: reqwest::Client,
+    on_exchange: Option<ExchangeHook>,
+    response_dir: PathBuf,
+    persist_responses: bool,
+    redactor: Redactor,
+    stream_format: StreamFormat,
+    /// Identical requests in flight at the same time share one API call
+    requests: SingleFlight<SharedReply>,
+    deduplicate: bool,
+    /// Where `generate_project_in` saves the model output before scaffolding
+    checkpoints: Option<Arc<Storage>>,
+    /// Tried in order when the primary endpoint fails
+    fallbacks: Vec<ModelEndpoint>,
+    /// Request `response_format: json_object` and parse replies as JSON whole
+    json_mode: bool,
+    /// Force temperature 0 and a fixed seed on chat completions
+    deterministic: bool,
+    /// Sampling seed sent with chat completions, for providers that honor it
+    seed: Option<u64>,
+    /// On-disk project generation prompt; the embedded default is used when it is missing
+    template_path: PathBuf,
 }
 
 impl InferenceClient {
@@ -39,16 +222,344 @@ impl InferenceClient {
         let model = std::env::var("INFERENCE_API_MODEL")
             .unwrap_or_else(|_| "gpt-3.5-turbo".to_string());
 
-        println!("Using inference model: {}", model);
-        println!("Using base URL: {}", base_url);
+        eprintln!("Using inference model: {}", model);
+        eprintln!("Using base URL: {}", base_url);
+
+        let mut client = Self::with_endpoint(&api_key, &base_url, &model)?;
+        if let Ok(embedding_model) = std::env::var("INFERENCE_EMBEDDING_MODEL") {
+            client.embedding_model = embedding_model;
+        }
+        if let Ok(response_dir) = std::env::var("INFERENCE_RESPONSE_DIR") {
+            client.response_dir = PathBuf::from(response_dir);
+        }
+        Ok(client)
+    }
 
+    /// Create a client for an explicit endpoint instead of reading the environment.
+    /// The base URL is validated and normalized with `normalize_base_url`.
+    pub fn with_endpoint(api_key: &str, base_url: &str, model: &str) -> Result<Self> {
         Ok(Self {
-            api_key,
-            base_url,
-            model,
+            api_key: api_key.to_string(),
+            base_url: normalize_base_url(base_url)?,
+            model: model.to_string(),
+            embedding_model: "text-embedding-3-small".to_string(),
+            headers: HeaderMap::new(),
+            http: reqwest::Client::builder().build().context("Failed to build HTTP client")?,
+            on_exchange: None,
+            response_dir: default_response_dir(),
+            persist_responses: true,
+            redactor: Redactor::default(),
+            stream_format: StreamFormat::default(),
+            requests: SingleFlight::new(),
+            deduplicate: true,
+            checkpoints: None,
+            fallbacks: Vec::new(),
+            json_mode: false,
+            deterministic: false,
+            seed: None,
+            template_path: PathBuf::from(PROJECT_TEMPLATE_PATH),
         })
     }
 
+    /// Write streamed response files to `dir` instead of the default cache directory
+    pub fn with_response_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.response_dir = dir.into();
+        self
+    }
+
+    /// Read the project generation prompt from `path` instead of `templates/project_generation.txt`
+    pub fn with_template_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.template_path = path.into();
+        self
+    }
+
+    /// Enable or disable writing streamed responses to disk
+    pub fn with_response_persistence(mut self, enabled: bool) -> Self {
+        self.persist_responses = enabled;
+        self
+    }
+
+    /// Apply the response file, redaction and stream format settings from `config`
+    pub fn with_config(mut self, config: &SystemConfig) -> Result<Self> {
+        if let Some(dir) = &config.response_dir {
+            self.response_dir = dir.clone();
+        }
+        self.persist_responses = config.persist_responses;
+        self.stream_format = config.stream_format;
+        self.redactor = Redactor::with_defaults(&config.redaction_patterns)?;
+        Ok(self)
+    }
+
+    /// Parse streamed completions as `format` instead of OpenAI-style SSE
+    pub fn with_stream_format(mut self, format: StreamFormat) -> Self {
+        self.stream_format = format;
+        self
+    }
+
+    /// Replace the redactor applied to response files and logged exchanges
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    /// Use a different model for `embed` than the default `text-embedding-3-small`
+    pub fn with_embedding_model(mut self, model: &str) -> Self {
+        self.embedding_model = model.to_string();
+        self
+    }
+
+    /// Add a header sent with every request, e.g. a gateway's `X-Org-Id`
+    pub fn with_header(mut self, key: &str, value: &str) -> Result<Self> {
+        let name = HeaderName::from_bytes(key.as_bytes())
+            .with_context(|| format!("Invalid header name: {}", key))?;
+        let value = HeaderValue::from_str(value)
+            .with_context(|| format!("Invalid value for header {}", key))?;
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+
+    /// Route all requests through the given HTTP(S) proxy. This builds a new HTTP
+    /// client, replacing one passed to `with_client`.
+    pub fn with_proxy(mut self, url: &str) -> Result<Self> {
+        let proxy = reqwest::Proxy::all(url)
+            .with_context(|| format!("Invalid proxy URL: {}", url))?;
+        self.http = reqwest::Client::builder()
+            .proxy(proxy)
+            .build()
+            .context("Failed to build HTTP client")?;
+        Ok(self)
+    }
+
+    /// Whether concurrent identical requests share one API call (on by default). Turn it
+    /// off when callers want independent samples for the same prompt.
+    pub fn with_request_deduplication(mut self, enabled: bool) -> Self {
+        self.deduplicate = enabled;
+        self
+    }
+
+    /// Send requests through `client`, e.g. to share one connection pool between
+    /// several inference clients. `reqwest::Client` clones share their pool.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.http = client;
+        self
+    }
+
+    /// Retry failed JSON requests against `fallbacks`, in order, when the primary endpoint
+    /// is unreachable, rejects the credentials, is rate limited or returns a server error.
+    /// The request's `model` is replaced with the fallback's model.
+    pub fn with_fallbacks(mut self, fallbacks: Vec<ModelEndpoint>) -> Self {
+        self.fallbacks = fallbacks;
+        self
+    }
+
+    /// Ask the provider for JSON mode (`response_format: {"type": "json_object"}`) when
+    /// generating configs, and parse the whole reply as JSON instead of scanning it for
+    /// objects. Only enable it for providers that support JSON mode.
+    pub fn with_json_mode(mut self, enabled: bool) -> Self {
+        self.json_mode = enabled;
+        self
+    }
+
+    /// Send `seed` with every chat completion so providers that support seeded sampling
+    /// repeat their output
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Make chat completions reproducible: temperature 0 and the configured seed, or
+    /// `DETERMINISTIC_SEED` when none is set. Overrides profile and caller temperatures.
+    pub fn with_deterministic(mut self, enabled: bool) -> Self {
+        self.deterministic = enabled;
+        self
+    }
+
+    /// Save each generated config to `storage` before scaffolding it, keyed by the
+    /// prompt hash, so `generate_project_resume` can retry a failed scaffold
+    pub fn with_checkpoints(mut self, storage: Arc<Storage>) -> Self {
+        self.checkpoints = Some(storage);
+        self
+    }
+
+    /// Install a hook called with the (redacted) request and the response after each call
+    pub fn with_exchange_hook(mut self, hook: impl Fn(&RequestLog, &ResponseLog) + Send + Sync + 'static) -> Self {
+        self.on_exchange = Some(Arc::new(hook));
+        self
+    }
+
+    /// Full URL for an API path such as `/chat/completions`
+    fn endpoint(&self, path: &str) -> String {
+        endpoint_url(&self.base_url, path)
+    }
+
+    /// Build a POST request to `path` with authorization and custom headers applied
+    fn post(&self, path: &str) -> reqwest::RequestBuilder {
+        self.post_to(&self.base_url, &self.api_key, path)
+    }
+
+    fn post_to(&self, base_url: &str, api_key: &str, path: &str) -> reqwest::RequestBuilder {
+        self.http
+            .post(endpoint_url(base_url, path))
+            .header("Authorization", format!("Bearer {}", api_key))
+            .headers(self.headers.clone())
+    }
+
+    /// Apply the deterministic mode and seed to a chat completion body; other requests
+    /// (such as embeddings) have no `messages` and are left alone
+    fn apply_sampling(&self, request_body: &mut serde_json::Value) {
+        if request_body.get("messages").is_none() {
+            return;
+        }
+        if self.deterministic {
+            request_body["temperature"] = json!(0.0);
+        }
+        let seed = match self.seed {
+            Some(seed) => Some(seed),
+            None if self.deterministic => Some(DETERMINISTIC_SEED),
+            None => None,
+        };
+        if let Some(seed) = seed {
+            request_body["seed"] = seed.into();
+        }
+    }
+
+    /// POST a JSON body to `path` and parse the JSON response, reporting the exchange to
+    /// the hook. Moves down the fallback chain while endpoints fail in a way another
+    /// endpoint might not, returning the last error if every endpoint fails.
+    async fn send_json(&self, path: &str, request_body: &serde_json::Value) -> Result<serde_json::Value> {
+        let mut request_body = request_body.clone();
+        self.apply_sampling(&mut request_body);
+        let request_body = &request_body;
+
+        let primary = ModelEndpoint {
+            base_url: self.base_url.clone(),
+            api_key: self.api_key.clone(),
+            model: self.model.clone(),
+        };
+
+        let mut last_error = None;
+        for (index, endpoint) in std::iter::once(&primary).chain(&self.fallbacks).enumerate() {
+            let body = if index == 0 || request_body.get("model").is_none() {
+                request_body.clone()
+            } else {
+                let mut body = request_body.clone();
+                body["model"] = endpoint.model.clone().into();
+                body
+            };
+
+            match self.send_json_to(endpoint, path, &body).await {
+                Ok(response) => return Ok(response),
+                Err(failure) if failure.fall_back && index < self.fallbacks.len() => {
+                    tracing::warn!(
+                        "Request to {} failed, falling back to {}: {}",
+                        endpoint.base_url,
+                        self.fallbacks[index].base_url,
+                        self.redact_api_key(&format!("{:#}", failure.error))
+                    );
+                    last_error = Some(failure.error);
+                }
+                Err(failure) => return Err(failure.error),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow!("No inference endpoint configured")))
+    }
+
+    async fn send_json_to(
+        &self,
+        endpoint: &ModelEndpoint,
+        path: &str,
+        request_body: &serde_json::Value,
+    ) -> std::result::Result<serde_json::Value, Failure> {
+        let request = self.post_to(&endpoint.base_url, &endpoint.api_key, path).json(request_body);
+        let send = move || async move {
+            let started = Instant::now();
+            let response = request.send().await?;
+            let status = response.status();
+            let body = response.text().await?;
+            Ok::<_, anyhow::Error>(RawReply { status, body, latency: started.elapsed() })
+        };
+
+        let (reply, leader) = if self.deduplicate {
+            let key = SingleFlight::<SharedReply>::key(&(&endpoint.base_url, path, request_body.to_string()));
+            self.requests
+                .run(key, move || async move { send().await.map_err(Arc::new) })
+                .await
+        } else {
+            (send().await.map_err(Arc::new), true)
+        };
+        // A request that never got a response failed on the network
+        let reply = reply.map_err(|e| Failure { error: anyhow!("{:#}", e), fall_back: true })?;
+
+        // Followers did not make a request of their own, so only the leader reports it
+        if leader {
+            self.log_exchange_to(endpoint, path, request_body, reply.status.as_u16(), &reply.body, reply.latency);
+        }
+
+        if !reply.status.is_success() {
+            return Err(Failure {
+                error: anyhow!("Inference request to {} failed with status {}: {}", path, reply.status, reply.body),
+                fall_back: falls_back(reply.status),
+            });
+        }
+        serde_json::from_str(&reply.body)
+            .with_context(|| format!("Failed to parse response from {} as JSON", path))
+            .map_err(Failure::fatal)
+    }
+
+    fn log_exchange(&self, path: &str, request_body: &serde_json::Value, status: u16, body: &str, latency: Duration) {
+        let primary = ModelEndpoint {
+            base_url: self.base_url.clone(),
+            api_key: self.api_key.clone(),
+            model: self.model.clone(),
+        };
+        self.log_exchange_to(&primary, path, request_body, status, body, latency);
+    }
+
+    fn log_exchange_to(
+        &self,
+        endpoint: &ModelEndpoint,
+        path: &str,
+        request_body: &serde_json::Value,
+        status: u16,
+        body: &str,
+        latency: Duration,
+    ) {
+        let hook = match &self.on_exchange {
+            Some(hook) => hook,
+            None => return,
+        };
+
+        let mut headers = vec![("authorization".to_string(), "Bearer ***".to_string())];
+        headers.extend(self.headers.iter().map(|(name, value)| {
+            (name.to_string(), self.redact_api_key(&String::from_utf8_lossy(value.as_bytes())))
+        }));
+
+        let redacted_body = serde_json::from_str(&self.redact_api_key(&request_body.to_string()))
+            .unwrap_or_else(|_| request_body.clone());
+
+        let request = RequestLog {
+            url: endpoint_url(&endpoint.base_url, path),
+            model: endpoint.model.clone(),
+            headers,
+            body: redacted_body,
+        };
+        let response = ResponseLog {
+            status,
+            body: self.redact_api_key(body),
+            latency,
+        };
+        hook(&request, &response);
+    }
+
+    /// Mask our own API keys plus anything the redactor recognizes as a secret
+    fn redact_api_key(&self, text: &str) -> String {
+        let keys = std::iter::once(&self.api_key).chain(self.fallbacks.iter().map(|endpoint| &endpoint.api_key));
+        let text = keys
+            .filter(|key| !key.is_empty())
+            .fold(text.to_string(), |text, key| text.replace(key.as_str(), "***"));
+        self.redactor.redact(&text)
+    }
+
     pub async fn execute_task_prompt(&self, prompt: &Prompt, _task_id: &TaskId) -> Result<String> {
         // Create OpenAI API request
         let request_body = json!({
@@ -67,15 +578,7 @@ impl InferenceClient {
         });
 
         // Send request to OpenAI API
-        let client = reqwest::Client::new();
-        let response = client
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .json(&request_body)
-            .send()
-            .await?
-            .json::<serde_json::Value>()
-            .await?;
+        let response = self.send_json("/chat/completions", &request_body).await?;
 
         // Extract response content
         response.get("choices")
@@ -87,11 +590,109 @@ impl InferenceClient {
             .ok_or_else(|| anyhow!("Failed to extract content from OpenAI response"))
     }
 
+    /// `execute_task_prompt` that gives up with `InferenceError::Cancelled` as soon as
+    /// `cancel` fires, dropping the in-flight request
+    pub async fn execute_task_prompt_cancellable(
+        &self,
+        prompt: &Prompt,
+        task_id: &TaskId,
+        cancel: &CancellationToken,
+    ) -> std::result::Result<String, InferenceError> {
+        until_cancelled(cancel, self.execute_task_prompt(prompt, task_id)).await
+    }
+
+    /// Send the full conversation history and return the assistant's reply
+    pub async fn complete_conversation(&self, conversation: &Conversation) -> Result<String> {
+        let request_body = json!({
+            "model": self.model,
+            "messages": conversation.to_messages(),
+            "temperature": 0.7
+        });
+
+        let response = self.send_json("/chat/completions", &request_body).await?;
+
+        response.get("choices")
+            .and_then(|choices| choices.get(0))
+            .and_then(|choice| choice.get("message"))
+            .and_then(|message| message.get("content"))
+            .and_then(|content| content.as_str())
+            .map(|s| s.to_string())
+            .ok_or_else(|| anyhow!("Failed to extract content from OpenAI response"))
+    }
+
+    /// Complete `prompt` and deserialize the reply into `T`. When `schema` is given it is
+    /// sent as a `response_format` hint and the reply must validate against it. The first
+    /// JSON object in the reply that validates and deserializes is used.
+    pub async fn complete_json<T: serde::de::DeserializeOwned>(
+        &self,
+        prompt: &Prompt,
+        schema: Option<serde_json::Value>,
+    ) -> std::result::Result<T, InferenceError> {
+        let mut request_body = json!({
+            "model": self.model,
+            "messages": Conversation::from(prompt).to_messages(),
+            "temperature": 0.2
+        });
+        if let Some(schema) = &schema {
+            request_body["response_format"] = json!({
+                "type": "json_schema",
+                "json_schema": { "name": "response", "schema": schema }
+            });
+        } else if self.json_mode {
+            request_body["response_format"] = json!({ "type": "json_object" });
+        }
+
+        let response = self.send_json("/chat/completions", &request_body).await?;
+        let content = response.get("choices")
+            .and_then(|choices| choices.get(0))
+            .and_then(|choice| choice.get("message"))
+            .and_then(|message| message.get("content"))
+            .and_then(|content| content.as_str())
+            .ok_or_else(|| anyhow!("Failed to extract content from OpenAI response"))?;
+
+        let candidates = if self.json_mode {
+            vec![serde_json::from_str(content)?]
+        } else {
+            extract_all_json(content)
+        };
+        if candidates.is_empty() {
+            return Err(InferenceError::NoJson);
+        }
+
+        let validator = schema.as_ref().map(SchemaValidator::new).transpose()?;
+        let mut last_error = None;
+        for candidate in candidates {
+            if let Some(validator) = &validator {
+                if let Err(e) = validator.validate(&candidate) {
+                    last_error = Some(InferenceError::SchemaMismatch(vec![e.to_string()]));
+                    continue;
+                }
+            }
+            match serde_json::from_value(candidate) {
+                Ok(value) => return Ok(value),
+                Err(e) => last_error = Some(InferenceError::Deserialize(e)),
+            }
+        }
+
+        Err(last_error.unwrap_or(InferenceError::NoJson))
+    }
+
     pub async fn generate_project_config(&self, prompt: &str) -> Result<String> {
-        // Read the project generation prompt template
-        let template_path = std::path::Path::new("templates/project_generation.txt");
-        let system_prompt = std::fs::read_to_string(template_path)
-            .context("Failed to read project generation prompt template")?;
+        self.generate_project_config_with(prompt, &GenerationProfile::default()).await
+    }
+
+    /// Generate a project config with the model, temperature, token limit and template
+    /// overridden by `profile` for this call only
+    pub async fn generate_project_config_with(&self, prompt: &str, profile: &GenerationProfile) -> Result<String> {
+        let (_, config) = self.request_project_config(prompt, profile).await?;
+        Ok(config)
+    }
+
+    /// Ask the model for a project config, returning its raw reply and the config JSON
+    /// picked out of it
+    async fn request_project_config(&self, prompt: &str, profile: &GenerationProfile) -> Result<(String, String)> {
+        // Read the project generation prompt template, using the embedded one if absent
+        let system_prompt = profile.load_template(&self.template_path)?;
 
         // Get temperature from env or use default
         let temperature = std::env::var("INFERENCE_API_TEMPERATURE")
@@ -99,7 +700,7 @@ impl InferenceClient {
             .and_then(|t| t.parse::<f32>().ok())
             .unwrap_or(0.7);
 
-        let request_body = json!({
+        let mut request_body = json!({
             "model": self.model,
             "messages": [
                 {
@@ -113,20 +714,14 @@ impl InferenceClient {
             ],
             "temperature": temperature
         });
+        profile.apply(&mut request_body);
+        if self.json_mode {
+            request_body["response_format"] = json!({ "type": "json_object" });
+        }
 
-        println!("Sending request to: {}/chat/completions", self.base_url);
+        eprintln!("Sending request to: {}", self.endpoint("/chat/completions"));
         
-        let client = reqwest::Client::new();
-        let response = client
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .json(&request_body)
-            .send()
-            .await?;
-
-        println!("Response status: {}", response.status());
-
-        let response_json = response.json::<serde_json::Value>().await?;
+        let response_json = self.send_json("/chat/completions", &request_body).await?;
         
         // Extract the content from the response
         let content = response_json.get("choices")
@@ -136,56 +731,148 @@ impl InferenceClient {
             .and_then(|content| content.as_str())
             .ok_or_else(|| anyhow!("Failed to extract content from OpenAI response"))?;
 
-        // Try to find JSON in the content
-        if let Some(json_str) = Self::extract_json_from_content(content) {
-            // Parse the JSON to transform the directory_structure
-            let mut value: serde_json::Value = serde_json::from_str(json_str)?;
-
-            // Transform directory_structure if it exists
-            if let Some(dir_struct) = value.get_mut("directory_structure") {
-                if let Some(obj) = dir_struct.as_object_mut() {
-                    let mut transformed = serde_json::Map::new();
-                    
-                    // For each directory
-                    for (dir, files) in obj.iter() {
-                        // If it's an object with a Files key, extract that array
-                        if let Some(files_obj) = files.as_object() {
-                            if let Some(files_array) = files_obj.get("Files") {
-                                transformed.insert(dir.clone(), files_array.clone());
-                            }
-                        }
-                        // If it's already an array, keep it as is
-                        else if files.is_array() {
-                            transformed.insert(dir.clone(), files.clone());
-                        }
-                    }
-                    
-                    // Replace with transformed structure
-                    *dir_struct = serde_json::Value::Object(transformed);
-                }
-            }
+        // JSON mode replies are the object itself; otherwise pick the most config-like
+        // JSON object in the content
+        let config = if self.json_mode {
+            let value: serde_json::Value = serde_json::from_str(content)
+                .with_context(|| format!("JSON mode reply is not valid JSON: {}", content))?;
+            Some(value).filter(serde_json::Value::is_object)
+        } else {
+            pick_project_config(extract_all_json(content))
+        };
+        if let Some(mut value) = config {
 
-            Ok(value.to_string())
+            normalize_directory_structure(&mut value);
+            Ok((content.to_string(), value.to_string()))
         } else {
             Err(anyhow!("Could not find valid JSON in model response: {}", content))
         }
     }
 
-    pub async fn generate_project(&self, prompt: &str) -> Result<PathBuf> {
+    /// Generate a project config, asking the model to correct it when it fails schema
+    /// validation. Each retry continues the conversation with the rejected JSON and its
+    /// validation errors; gives up after `max_attempts` replies.
+    pub async fn generate_config_with_repair(
+        &self,
+        prompt: &str,
+        max_attempts: usize,
+    ) -> std::result::Result<String, InferenceError> {
+        let system_prompt = load_project_template(&self.template_path)?;
+        let validator = SchemaValidator::new(&ProjectGenerationConfig::json_schema())?;
+        let mut conversation = Conversation::new().system(&system_prompt).user(prompt);
+
+        let mut last_error = InferenceError::NoJson;
+        for attempt in 1..=max_attempts.max(1) {
+            let reply = self.complete_conversation(&conversation).await?;
+
+            let repair_request = match pick_project_config(extract_all_json(&reply)) {
+                Some(mut value) => {
+                    let errors = validator.errors(&value);
+                    if errors.is_empty() {
+                        normalize_directory_structure(&mut value);
+                        return Ok(value.to_string());
+                    }
+                    tracing::warn!(attempt, errors = ?errors, "Generated config failed validation");
+                    let request = format!(
+                        "The configuration below failed validation with these errors:\n- {}\n\n\
+                         Configuration:\n{}\n\n\
+                         Reply with a corrected version of the complete configuration as a single JSON object.",
+                        errors.join("\n- "),
+                        serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string()),
+                    );
+                    last_error = InferenceError::SchemaMismatch(errors);
+                    request
+                }
+                None => {
+                    tracing::warn!(attempt, "Generated reply contained no JSON");
+                    last_error = InferenceError::NoJson;
+                    "Your reply did not contain a JSON object. Reply with the complete project configuration as a single JSON object.".to_string()
+                }
+            };
+
+            conversation = conversation.assistant(&reply).user(&repair_request);
+        }
+
+        Err(last_error)
+    }
+
+    pub async fn generate_project(&self, prompt: &str) -> std::result::Result<PathBuf, GenerationError> {
+        self.generate_project_in(prompt, Path::new("build")).await
+    }
+
+    /// Generate a project from `prompt`, scaffolding it under `output_dir`
+    #[tracing::instrument(skip(self, prompt), fields(model = %self.model))]
+    pub async fn generate_project_in(
+        &self,
+        prompt: &str,
+        output_dir: &Path,
+    ) -> std::result::Result<PathBuf, GenerationError> {
         // Generate project configuration
-        let config_json = self.generate_project_config(prompt).await?;
-        
-        // Initialize state and build managers
-        let state_manager = StateManager::new();
-        let build_manager = BuildManager::new(state_manager, PathBuf::from("build"));
-        
-        // Generate the project
-        let project_dir = build_manager.scaffold_project(&config_json)
-            .context("Failed to generate project")?;
+        let (raw_response, config_json) = self
+            .request_project_config(prompt, &GenerationProfile::default())
+            .await
+            .map_err(InferenceError::Request)?;
+        validate_generated_config(&config_json)?;
+
+        // Save it before scaffolding so a failure doesn't cost another generation
+        let hash = prompt_hash(prompt);
+        if let Some(storage) = &self.checkpoints {
+            let checkpoint = GenerationCheckpoint {
+                prompt: prompt.to_string(),
+                raw_response,
+                config: config_json.clone(),
+            };
+            if let Err(e) = storage.store(&GenerationCheckpoint::key(&hash), &checkpoint) {
+                tracing::warn!("Failed to save generation checkpoint: {:#}", e);
+            }
+        }
+
+        let project_dir = scaffold(&config_json, output_dir).map_err(|e| match (&self.checkpoints, e) {
+            (Some(_), BuildError::Scaffold(e)) => {
+                BuildError::Scaffold(e.context(format!("resume with prompt hash {}", hash)))
+            }
+            (_, e) => e,
+        })?;
+
+        if let Some(storage) = &self.checkpoints {
+            if let Err(e) = storage.delete(&GenerationCheckpoint::key(&hash)) {
+                tracing::warn!("Failed to remove generation checkpoint: {:#}", e);
+            }
+        }
+        Ok(project_dir)
+    }
+
+    pub async fn generate_project_resume(&self, prompt_hash: &str) -> std::result::Result<PathBuf, GenerationError> {
+        self.generate_project_resume_in(prompt_hash, Path::new("build")).await
+    }
 
+    /// Scaffold the config checkpointed for `prompt_hash` under `output_dir` without
+    /// calling the model, removing the checkpoint once scaffolding succeeds. A missing
+    /// checkpoint is a config-stage failure.
+    pub async fn generate_project_resume_in(
+        &self,
+        prompt_hash: &str,
+        output_dir: &Path,
+    ) -> std::result::Result<PathBuf, GenerationError> {
+        let key = GenerationCheckpoint::key(prompt_hash);
+        let checkpoint = self.load_checkpoint(&key).map_err(InferenceError::Request)?;
+
+        let project_dir = scaffold(&checkpoint.config, output_dir)?;
+        if let Some(storage) = &self.checkpoints {
+            if let Err(e) = storage.delete(&key) {
+                tracing::warn!("Failed to remove generation checkpoint: {:#}", e);
+            }
+        }
         Ok(project_dir)
     }
 
+    fn load_checkpoint(&self, key: &str) -> Result<GenerationCheckpoint> {
+        let storage = self.checkpoints.as_ref()
+            .ok_or_else(|| anyhow!("Resuming requires checkpoint storage; see with_checkpoints"))?;
+        storage.load(key)?
+            .ok_or_else(|| anyhow!("No saved generation for {}", key))
+    }
+
     pub async fn conditional_check(
         &self,
         _initial_prompt: &str,
@@ -211,15 +898,7 @@ impl InferenceClient {
             "temperature": 0.7
         });
 
-        let client = reqwest::Client::new();
-        let response = client
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .json(&request_body)
-            .send()
-            .await?
-            .json::<serde_json::Value>()
-            .await?;
+        let response = self.send_json("/chat/completions", &request_body).await?;
 
         response.get("choices")
             .and_then(|choices| choices.get(0))
@@ -254,15 +933,7 @@ impl InferenceClient {
                 "temperature": 0.7
             });
 
-            let client = reqwest::Client::new();
-            let response = client
-                .post(format!("{}/chat/completions", self.base_url))
-                .header("Authorization", format!("Bearer {}", self.api_key))
-                .json(&request_body)
-                .send()
-                .await?
-                .json::<serde_json::Value>()
-                .await?;
+            let response = self.send_json("/chat/completions", &request_body).await?;
 
             let refined_response = response.get("choices")
                 .and_then(|choices| choices.get(0))
@@ -282,47 +953,698 @@ impl InferenceClient {
         Ok(current_response)
     }
 
-    fn extract_json_from_content(content: &str) -> Option<&str> {
-        // Find the first { character
-        let start = content.find('{')?;
-        
-        // Find the last } character
-        let end = content.rfind('}')?;
-        
-        // Extract everything between { and }
-        if start < end {
-            Some(&content[start..=end])
-        } else {
-            None
-        }
-    }
-}
+    /// Generate embeddings for `texts`, batching requests to the `/embeddings` endpoint.
+    /// The returned vectors are in the same order as the input.
+    pub async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut embeddings = Vec::with_capacity(texts.len());
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        for batch in texts.chunks(EMBEDDING_BATCH_SIZE) {
+            let request_body = json!({
+                "model": self.embedding_model,
+                "input": batch
+            });
 
-    #[tokio::test]
-    async fn test_generate_project() -> Result<()> {
-        // Skip this test if no API key is set
-        match std::env::var("INFERENCE_API_KEY") {
-            Ok(_) => (),
-            Err(_) => {
-                println!("Skipping test_generate_project: No INFERENCE_API_KEY set");
-                return Ok(());
+            let response = self.send_json("/embeddings", &request_body).await?;
+
+            let mut data: Vec<(u64, Vec<f32>)> = response.get("data")
+                .and_then(|data| data.as_array())
+                .ok_or_else(|| anyhow!("Failed to extract data from embeddings response"))?
+                .iter()
+                .map(|item| -> Result<(u64, Vec<f32>)> {
+                    let index = item.get("index").and_then(|i| i.as_u64()).unwrap_or(0);
+                    let embedding = item.get("embedding")
+                        .and_then(|e| e.as_array())
+                        .ok_or_else(|| anyhow!("Embeddings response item has no embedding"))?
+                        .iter()
+                        .map(|v| v.as_f64().map(|v| v as f32))
+                        .collect::<Option<Vec<f32>>>()
+                        .ok_or_else(|| anyhow!("Embedding contains a non-numeric value"))?;
+                    Ok((index, embedding))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            if data.len() != batch.len() {
+                return Err(anyhow!("Expected {} embeddings, got {}", batch.len(), data.len()));
             }
+            data.sort_by_key(|(index, _)| *index);
+            embeddings.extend(data.into_iter().map(|(_, embedding)| embedding));
         }
 
-        let client = InferenceClient::new()?;
-        let prompt = "Create a simple Rust web server project";
-        let project_dir = client.generate_project(prompt).await?;
+        Ok(embeddings)
+    }
 
-        assert!(project_dir.exists());
-        assert!(project_dir.join("Cargo.toml").exists());
-        assert!(project_dir.join("src").exists());
-        assert!(project_dir.join("src/main.rs").exists());
+    /// List the models the primary endpoint offers, from the `data` array of `GET /models`
+    pub async fn list_models(&self) -> std::result::Result<Vec<ModelInfo>, InferenceError> {
+        let response = self
+            .http
+            .get(self.endpoint("/models"))
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .headers(self.headers.clone())
+            .send()
+            .await
+            .context("Failed to request /models")?;
+        let status = response.status();
+        let body = response.text().await.context("Failed to read /models response")?;
+        if !status.is_success() {
+            return Err(anyhow!("Listing models failed with status {}: {}", status, self.redact_api_key(&body)).into());
+        }
 
-        Ok(())
+        #[derive(serde::Deserialize)]
+        struct ModelList {
+            data: Vec<ModelInfo>,
+        }
+        let models: ModelList = serde_json::from_str(&body)?;
+        Ok(models.data)
+    }
+
+    /// Stream a chat completion, appending each content delta to a response file as it arrives
+    pub async fn stream_completion(&self, prompt: &Prompt, temperature: f32) -> Result<String> {
+        let response_path = self.response_file_path("stream")?;
+        self.stream_to_file(prompt, temperature, response_path.as_deref()).await
+    }
+
+    /// `stream_completion` that stops reading the stream and returns
+    /// `InferenceError::Cancelled` as soon as `cancel` fires. Content already written to
+    /// the response file is left there.
+    pub async fn stream_completion_cancellable(
+        &self,
+        prompt: &Prompt,
+        temperature: f32,
+        cancel: &CancellationToken,
+    ) -> std::result::Result<String, InferenceError> {
+        until_cancelled(cancel, self.stream_completion(prompt, temperature)).await
+    }
+
+    /// Like `stream_completion`, but restarts the request when the stream breaks partway through.
+    ///
+    /// Chat completion providers cannot resume a generation, so each retry starts over; the
+    /// output received before the failure is kept next to the response file with a
+    /// `.partial.txt` suffix for inspection.
+    pub async fn stream_completion_resilient(
+        &self,
+        prompt: &Prompt,
+        temperature: f32,
+        max_retries: usize,
+    ) -> Result<String> {
+        let mut attempt = 0;
+        loop {
+            let response_path = self.response_file_path("stream")?;
+            match self.stream_to_file(prompt, temperature, response_path.as_deref()).await {
+                Ok(content) => return Ok(content),
+                Err(e) if attempt < max_retries => {
+                    attempt += 1;
+                    tracing::warn!("Stream attempt {} failed, retrying: {}", attempt, self.redact_api_key(&e.to_string()));
+
+                    if let Some(response_path) = response_path {
+                        let partial_path = response_path.with_extension(format!("attempt{}.partial.txt", attempt));
+                        if tokio::fs::rename(&response_path, &partial_path).await.is_ok() {
+                            tracing::info!("Partial response preserved at {}", partial_path.display());
+                        }
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Stream a completion, appending deltas to `path` when response persistence is enabled
+    async fn stream_to_file(&self, prompt: &Prompt, temperature: f32, path: Option<&Path>) -> Result<String> {
+        let mut request_body = json!({
+            "model": self.model,
+            "messages": [
+                {
+                    "role": Role::System,
+                    "content": &prompt.system_context
+                },
+                {
+                    "role": Role::User,
+                    "content": &prompt.user_request
+                }
+            ],
+            "temperature": temperature,
+            "stream": true
+        });
+        self.apply_sampling(&mut request_body);
+
+        let started = Instant::now();
+        let mut response = self
+            .post("/chat/completions")
+            .json(&request_body)
+            .send()
+            .await?
+            .error_for_status()?;
+        let status = response.status().as_u16();
+
+        let mut file = match path {
+            Some(path) => Some(
+                tokio::fs::OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(path)
+                    .await
+                    .with_context(|| format!("Failed to create response file: {}", path.display()))?,
+            ),
+            None => None,
+        };
+        let mut content = String::new();
+        let mut pending: Vec<u8> = Vec::new();
+        // Text received but not yet written; a secret can span deltas, so only whole
+        // lines are redacted and written as they complete
+        let mut unwritten = String::new();
+
+        // Events may be split across network chunks, so only complete lines are parsed
+        let mut stream_error = None;
+        loop {
+            let chunk = match response.chunk().await {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(e) => {
+                    stream_error = Some(e);
+                    break;
+                }
+            };
+            pending.extend_from_slice(&chunk);
+            while let Some(newline) = pending.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=newline).collect();
+                if let Some(delta) = self.stream_format.parse_line(&String::from_utf8_lossy(&line)) {
+                    content.push_str(&delta);
+                    unwritten.push_str(&delta);
+                    if let (Some(file), Some(newline)) = (file.as_mut(), unwritten.rfind('\n')) {
+                        let complete: String = unwritten.drain(..=newline).collect();
+                        file.write_all(self.redact_api_key(&complete).as_bytes()).await?;
+                        file.flush().await?;
+                    }
+                }
+            }
+        }
+        // JSON-lines streams may end without a trailing newline
+        if stream_error.is_none() {
+            if let Some(delta) = self.stream_format.parse_line(&String::from_utf8_lossy(&pending)) {
+                content.push_str(&delta);
+                unwritten.push_str(&delta);
+            }
+        }
+        // A broken stream still leaves everything received so far in the file
+        if let Some(file) = file.as_mut() {
+            file.write_all(self.redact_api_key(&unwritten).as_bytes()).await?;
+            file.flush().await?;
+        }
+        if let Some(e) = stream_error {
+            return Err(e.into());
+        }
+
+        self.log_exchange("/chat/completions", &request_body, status, &content, started.elapsed());
+        Ok(content)
+    }
+
+    /// Path for a new response file of the given kind, or `None` when persistence is disabled
+    fn response_file_path(&self, kind: &str) -> Result<Option<PathBuf>> {
+        if !self.persist_responses {
+            return Ok(None);
+        }
+
+        let dir = &self.response_dir;
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create response directory: {}", dir.display()))?;
+        // The UUID keeps concurrent calls within the same second from sharing a file
+        Ok(Some(dir.join(format!(
+            "{}_{}_{}.txt",
+            kind,
+            chrono::Utc::now().timestamp(),
+            uuid::Uuid::new_v4().simple()
+        ))))
+    }
+}
+
+/// Every balanced `{...}` span in `content` that parses as a JSON object, in order of
+/// appearance. Braces inside JSON strings are ignored when matching.
+pub fn extract_all_json(content: &str) -> Vec<serde_json::Value> {
+    let bytes = content.as_bytes();
+    let mut candidates = Vec::new();
+    let mut start = 0;
+
+    while let Some(offset) = content[start..].find('{') {
+        let open = start + offset;
+        match balanced_object_end(bytes, open)
+            .and_then(|close| serde_json::from_str::<serde_json::Value>(&content[open..=close]).ok().map(|v| (close, v)))
+        {
+            Some((close, value)) if value.is_object() => {
+                candidates.push(value);
+                start = close + 1;
+            }
+            // Not a complete object here; a nested or later brace may still start one
+            _ => start = open + 1,
+        }
+    }
+
+    candidates
+}
+
+fn balanced_object_end(bytes: &[u8], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, &byte) in bytes.iter().enumerate().skip(open) {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Run `request` unless `cancel` fires first; dropping the request future aborts the
+/// HTTP exchange
+async fn until_cancelled<T>(
+    cancel: &CancellationToken,
+    request: impl std::future::Future<Output = Result<T>>,
+) -> std::result::Result<T, InferenceError> {
+    tokio::select! {
+        biased;
+        _ = cancel.cancelled() => Err(InferenceError::Cancelled),
+        result = request => result.map_err(InferenceError::Request),
+    }
+}
+
+/// Full URL for an API path such as `/chat/completions` under `base_url`
+fn endpoint_url(base_url: &str, path: &str) -> String {
+    format!("{}/{}", base_url, path.trim_start_matches('/'))
+}
+
+/// Scaffold the project described by `config_json` under `output_dir`
+fn scaffold(config_json: &str, output_dir: &Path) -> std::result::Result<PathBuf, BuildError> {
+    let build_manager = BuildManager::new(StateManager::new(), output_dir.to_path_buf());
+    build_manager.scaffold_project(config_json).map(|report| report.root).map_err(BuildError::Scaffold)
+}
+
+/// Check a generated config against the project config schema before scaffolding it.
+/// Scaffolding defaults a missing name, language or project type, so only the types of
+/// the fields that are present are checked, not which fields are required.
+fn validate_generated_config(config_json: &str) -> std::result::Result<(), GenerationError> {
+    let config: serde_json::Value = serde_json::from_str(config_json).map_err(InferenceError::Deserialize)?;
+    let mut schema = ProjectGenerationConfig::json_schema();
+    if let Some(schema) = schema.as_object_mut() {
+        schema.remove("required");
+        schema.remove("anyOf");
+    }
+    let validator = SchemaValidator::new(&schema).map_err(InferenceError::Request)?;
+    let errors = validator.errors(&config);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(GenerationError::Validation(errors))
+    }
+}
+
+/// Flatten `{"dir": {"Files": [...]}}` entries in a config's `directory_structure` into
+/// plain file arrays, dropping entries that are neither form
+fn normalize_directory_structure(value: &mut serde_json::Value) {
+    if let Some(obj) = value.get_mut("directory_structure").and_then(|d| d.as_object_mut()) {
+        let mut transformed = serde_json::Map::new();
+
+        for (dir, files) in obj.iter() {
+            // If it's an object with a Files key, extract that array
+            if let Some(files_obj) = files.as_object() {
+                if let Some(files_array) = files_obj.get("Files") {
+                    transformed.insert(dir.clone(), files_array.clone());
+                }
+            }
+            // If it's already an array, keep it as is
+            else if files.is_array() {
+                transformed.insert(dir.clone(), files.clone());
+            }
+        }
+
+        *obj = transformed;
+    }
+}
+
+/// Pick the candidate that looks most like a project config, ranked by the presence of
+/// a name (`project_name` or `name`) and a `language`. Later candidates win ties.
+pub fn pick_project_config(candidates: Vec<serde_json::Value>) -> Option<serde_json::Value> {
+    candidates.into_iter().max_by_key(|candidate| {
+        let has_name = candidate.get("project_name").is_some() || candidate.get("name").is_some();
+        let has_language = candidate.get("language").is_some();
+        has_name as u8 + has_language as u8
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::test_server::{MockResponse, MockServer};
+
+    #[tokio::test]
+    async fn test_generate_project() -> Result<()> {
+        // Skip this test if no API key is set
+        match std::env::var("INFERENCE_API_KEY") {
+            Ok(_) => (),
+            Err(_) => {
+                println!("Skipping test_generate_project: No INFERENCE_API_KEY set");
+                return Ok(());
+            }
+        }
+
+        let client = InferenceClient::new()?;
+        let prompt = "Create a simple Rust web server project";
+        let project_dir = client.generate_project(prompt).await?;
+
+        assert!(project_dir.exists());
+        assert!(project_dir.join("Cargo.toml").exists());
+        assert!(project_dir.join("src").exists());
+        assert!(project_dir.join("src/main.rs").exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resume_failed_scaffold_from_checkpoint() -> Result<()> {
+        let config = json!({
+            "project_name": "resumable-tool",
+            "language": "Rust",
+            "project_type": "Tool",
+            "directory_structure": { "src": ["main.rs"] }
+        });
+        let server = MockServer::start(vec![MockResponse::completion(&config.to_string())]).await;
+        let temp_dir = tempfile::tempdir()?;
+        let storage = Arc::new(Storage::new(temp_dir.path().join("checkpoints"))?);
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?
+            .with_checkpoints(Arc::clone(&storage));
+
+        // A file where the output directory should be makes scaffolding fail
+        let blocked = temp_dir.path().join("blocked");
+        std::fs::write(&blocked, "not a directory")?;
+        let prompt = "A resumable command line tool";
+        let err = client.generate_project_in(prompt, &blocked).await.unwrap_err();
+        let hash = prompt_hash(prompt);
+        assert!(matches!(err, GenerationError::Scaffold(_)));
+        assert!(err.to_string().contains(&hash), "{}", err);
+
+        let checkpoint: GenerationCheckpoint = storage.load(&GenerationCheckpoint::key(&hash))?.unwrap();
+        assert_eq!(checkpoint.prompt, prompt);
+        assert_eq!(checkpoint.raw_response, config.to_string());
+
+        let project_dir = client.generate_project_resume_in(&hash, &temp_dir.path().join("out")).await?;
+        assert!(project_dir.join("src/main.rs").exists());
+        assert_eq!(server.requests().len(), 1);
+        assert!(storage.load::<GenerationCheckpoint>(&GenerationCheckpoint::key(&hash))?.is_none());
+        let err = client.generate_project_resume_in(&hash, temp_dir.path()).await.unwrap_err();
+        assert!(matches!(err, GenerationError::Config(_)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_generate_project_records_generation_span() -> Result<()> {
+        let config = json!({ "project_name": "traced-generation", "language": "Rust", "project_type": "Tool" });
+        let server = MockServer::start(vec![MockResponse::completion(&config.to_string())]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+        let temp_dir = tempfile::tempdir()?;
+
+        client.generate_project_in("a traced tool", temp_dir.path()).await?;
+
+        // Scaffolding runs inside the generation span, so its events carry both
+        assert!(logs_contain("generate_project_in{"));
+        assert!(logs_contain("model=test-model}:scaffold_project{project_name=\"traced-generation\"}"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generation_errors_name_the_failed_stage() -> Result<()> {
+        let valid = json!({ "project_name": "staged-tool", "language": "Rust", "project_type": "Tool" });
+        let server = MockServer::start(vec![
+            MockResponse::Json(500, "{\"error\":\"internal\"}".to_string()),
+            MockResponse::completion(&json!({ "project_name": "bad-type", "language": "Rust", "project_type": "Spaceship" }).to_string()),
+            MockResponse::completion(&valid.to_string()),
+            MockResponse::completion(&json!({ "project_name": "no-language", "project_type": "Tool" }).to_string()),
+        ]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?
+            .with_request_deduplication(false);
+        let temp_dir = tempfile::tempdir()?;
+        let blocked = temp_dir.path().join("blocked");
+        std::fs::write(&blocked, "not a directory")?;
+
+        let err = client.generate_project_in("a tool", temp_dir.path()).await.unwrap_err();
+        assert!(matches!(err, GenerationError::Config(InferenceError::Request(_))), "{}", err);
+
+        let err = client.generate_project_in("a tool", temp_dir.path()).await.unwrap_err();
+        assert!(matches!(err, GenerationError::Validation(ref errors) if !errors.is_empty()), "{}", err);
+
+        let err = client.generate_project_in("a tool", &blocked).await.unwrap_err();
+        assert!(matches!(err, GenerationError::Scaffold(BuildError::Scaffold(_))), "{}", err);
+
+        // Scaffolding defaults a missing language, so validation lets it through
+        client.generate_project_in("a tool", temp_dir.path()).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_next_endpoint() -> Result<()> {
+        let primary = MockServer::start(vec![MockResponse::Json(503, "{\"error\":\"overloaded\"}".to_string())]).await;
+        let secondary = MockServer::start(vec![MockResponse::completion("from the fallback")]).await;
+        let client = InferenceClient::with_endpoint("primary-key", &primary.base_url, "primary-model")?
+            .with_fallbacks(vec![ModelEndpoint::new(&secondary.base_url, "secondary-key", "secondary-model")?]);
+
+        let reply = client.complete_conversation(&Conversation::new().user("Hello")).await?;
+        assert_eq!(reply, "from the fallback");
+        assert_eq!(primary.requests().len(), 1);
+
+        let requests = secondary.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].to_lowercase().contains("authorization: bearer secondary-key"));
+        assert_eq!(secondary.request_bodies()[0]["model"], "secondary-model");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_do_not_fall_back() -> Result<()> {
+        let primary = MockServer::start(vec![MockResponse::Json(400, "{\"error\":\"bad request\"}".to_string())]).await;
+        let secondary = MockServer::start(vec![MockResponse::completion("unused")]).await;
+        let client = InferenceClient::with_endpoint("primary-key", &primary.base_url, "primary-model")?
+            .with_fallbacks(vec![ModelEndpoint::new(&secondary.base_url, "secondary-key", "secondary-model")?]);
+
+        let err = client.complete_conversation(&Conversation::new().user("Hello")).await.unwrap_err();
+        assert!(err.to_string().contains("400"), "{}", err);
+        assert!(secondary.requests().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_complete_conversation_sends_history() -> Result<()> {
+        let server = MockServer::start(vec![MockResponse::completion("Refined answer")]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+
+        let conversation = Conversation::new()
+            .system("You are a build assistant.")
+            .user("Create a CLI project")
+            .assistant("Here is a first draft");
+
+        let reply = client.complete_conversation(&conversation).await?;
+        assert_eq!(reply, "Refined answer");
+
+        let bodies = server.request_bodies();
+        assert_eq!(
+            bodies[0]["messages"],
+            json!([
+                { "role": "system", "content": "You are a build assistant." },
+                { "role": "user", "content": "Create a CLI project" },
+                { "role": "assistant", "content": "Here is a first draft" }
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_all_json_ignores_braces_in_strings() {
+        let content = r#"Note {not json} then {"a": "}{", "b": {"c": 1}} and {"d": 2}"#;
+        let candidates = extract_all_json(content);
+        assert_eq!(candidates, vec![json!({"a": "}{", "b": {"c": 1}}), json!({"d": 2})]);
+    }
+
+    #[tokio::test]
+    async fn test_generate_project_config_picks_real_config() -> Result<()> {
+        let content = r#"Here is an example of the format:
+```json
+{"example": true, "files": ["main.rs"]}
+```
+And here is your project:
+```json
+{"project_name": "picked-project", "language": "Rust", "directory_structure": {"src": ["main.rs"]}}
+```"#;
+        let server = MockServer::start(vec![MockResponse::completion(content)]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+
+        let config: serde_json::Value = serde_json::from_str(&client.generate_project_config("make it").await?)?;
+        assert_eq!(config["project_name"], "picked-project");
+        assert_eq!(config["language"], "Rust");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_config_with_selected_profile() -> Result<()> {
+        let config = SystemConfig::from_toml(r#"
+            base_project_dir = "build"
+            template_dir = "templates"
+            log_level = "info"
+
+            [profiles.draft]
+            model = "fast-model"
+            temperature = 0.9
+
+            [profiles.quality]
+            model = "large-model"
+            temperature = 0.2
+            max_tokens = 4096
+        "#)?;
+        let profile = config.profile("quality")?;
+        assert_eq!(profile.name, "quality");
+        assert!(config.profile("missing").is_err());
+
+        let generated = json!({ "project_name": "profiled", "language": "Rust" });
+        let server = MockServer::start(vec![MockResponse::completion(&generated.to_string())]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+        client.generate_project_config_with("Create a Rust CLI", &profile).await?;
+
+        let bodies = server.request_bodies();
+        assert_eq!(bodies[0]["model"], "large-model");
+        assert_eq!(bodies[0]["temperature"].as_f64(), Some(0.2f32 as f64));
+        assert_eq!(bodies[0]["max_tokens"], 4096);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_json_mode_parses_whole_reply() -> Result<()> {
+        let config = json!({ "project_name": "json-mode", "language": "Rust" }).to_string();
+        let server = MockServer::start(vec![
+            MockResponse::completion(&config),
+            MockResponse::completion(&format!("Here is the config: {}", config)),
+        ]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?
+            .with_json_mode(true)
+            .with_request_deduplication(false);
+
+        let generated: serde_json::Value = serde_json::from_str(&client.generate_project_config("Create a Rust CLI").await?)?;
+        assert_eq!(generated["project_name"], "json-mode");
+
+        // Prose around the object would be scraped away without JSON mode
+        let err = client.generate_project_config("Create a Rust CLI").await.unwrap_err();
+        assert!(err.to_string().contains("JSON mode reply is not valid JSON"), "{}", err);
+
+        let bodies = server.request_bodies();
+        assert_eq!(bodies.len(), 2);
+        for body in &bodies {
+            assert_eq!(body["response_format"], json!({ "type": "json_object" }));
+        }
+
+        let server = MockServer::start(vec![MockResponse::completion(&config)]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+        client.generate_project_config("Create a Rust CLI").await?;
+        assert!(server.request_bodies()[0].get("response_format").is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_config_with_repair_retries_invalid_config() -> Result<()> {
+        let invalid = json!({ "project_name": "repaired-app", "project_type": "Tool" });
+        let valid = json!({
+            "project_name": "repaired-app",
+            "language": "Rust",
+            "project_type": "Tool",
+            "directory_structure": { "src": { "Files": ["main.rs"] } }
+        });
+        let server = MockServer::start(vec![
+            MockResponse::completion(&invalid.to_string()),
+            MockResponse::completion(&valid.to_string()),
+        ]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+
+        let config: serde_json::Value = serde_json::from_str(&client.generate_config_with_repair("make it", 3).await?)?;
+        assert_eq!(config["language"], "Rust");
+        assert_eq!(config["directory_structure"]["src"], json!(["main.rs"]));
+
+        let bodies = server.request_bodies();
+        assert_eq!(bodies.len(), 2);
+        let messages = bodies[1]["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[2]["content"], invalid.to_string());
+        let repair = messages[3]["content"].as_str().unwrap();
+        assert!(repair.contains("language"));
+        assert!(repair.contains("\"project_name\": \"repaired-app\""));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_config_with_repair_gives_up() -> Result<()> {
+        let invalid = json!({ "project_name": "never-valid" }).to_string();
+        let server = MockServer::start(vec![
+            MockResponse::completion(&invalid),
+            MockResponse::completion(&invalid),
+        ]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+
+        let result = client.generate_config_with_repair("make it", 2).await;
+        assert!(matches!(result, Err(InferenceError::SchemaMismatch(_))));
+        assert_eq!(server.requests().len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_complete_json_into_project_config() -> Result<()> {
+        let content = r#"Sure! Here is the configuration:
+{"project_name": "typed-output", "language": "Rust", "project_type": "Library", "description": "Typed"}"#;
+        let server = MockServer::start(vec![MockResponse::completion(content)]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+
+        let prompt = Prompt::new("Return a project config", "A Rust library");
+        let config: crate::prompt::ProjectConfig = client.complete_json(&prompt, None).await?;
+
+        assert_eq!(config.project_name, "typed-output");
+        assert_eq!(config.language, "Rust");
+        assert!(server.request_bodies()[0].get("response_format").is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_complete_json_schema_mismatch() -> Result<()> {
+        let server = MockServer::start(vec![MockResponse::completion(r#"{"count": "three"}"#)]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+
+        let schema = json!({
+            "type": "object",
+            "properties": { "count": { "type": "integer" } },
+            "required": ["count"]
+        });
+        let prompt = Prompt::new("Count things", "How many?");
+        let result = client.complete_json::<serde_json::Value>(&prompt, Some(schema.clone())).await;
+
+        assert!(matches!(result, Err(InferenceError::SchemaMismatch(_))));
+        assert_eq!(server.request_bodies()[0]["response_format"]["json_schema"]["schema"], schema);
+
+        Ok(())
     }
 
     #[tokio::test]
@@ -371,6 +1693,458 @@ mod tests {
         
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_stream_completion_resilient_retries_dropped_stream() -> Result<()> {
+        let server = MockServer::start(vec![
+            MockResponse::Stream {
+                chunks: vec![MockResponse::sse_delta("Hel"), MockResponse::sse_delta("lo")],
+                complete: false,
+            },
+            MockResponse::Stream {
+                chunks: vec![
+                    MockResponse::sse_delta("Hello"),
+                    MockResponse::sse_delta(" world"),
+                    "data: [DONE]\n\n".to_string(),
+                ],
+                complete: true,
+            },
+        ]).await;
+
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+        let prompt = Prompt::new("You are a helpful assistant", "Say hello");
+        let content = client.stream_completion_resilient(&prompt, 0.7, 2).await?;
+
+        assert_eq!(content, "Hello world");
+        assert_eq!(server.requests().len(), 2);
+
+        Ok(())
+    }
+
+    fn streaming_server_responses() -> Vec<MockResponse> {
+        vec![MockResponse::Stream {
+            chunks: vec![MockResponse::sse_delta("saved"), "data: [DONE]\n\n".to_string()],
+            complete: true,
+        }]
+    }
+
+    #[tokio::test]
+    async fn test_stream_response_written_to_configured_dir() -> Result<()> {
+        let server = MockServer::start(streaming_server_responses()).await;
+        let response_dir = tempfile::tempdir()?;
+
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?
+            .with_response_dir(response_dir.path());
+        let prompt = Prompt::new("You are a helpful assistant", "Say saved");
+        client.stream_completion(&prompt, 0.7).await?;
+
+        let files: Vec<PathBuf> = std::fs::read_dir(response_dir.path())?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<std::io::Result<_>>()?;
+        assert_eq!(files.len(), 1);
+        assert!(files[0].file_name().unwrap().to_string_lossy().starts_with("stream_"));
+        assert_eq!(std::fs::read_to_string(&files[0])?, "saved");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_requests() -> Result<()> {
+        let server = MockServer::start(vec![MockResponse::completion("Hello")]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?
+            .with_deterministic(true)
+            .with_seed(Some(1234))
+            .with_request_deduplication(false);
+        let prompt = Prompt::new("You are a helpful assistant", "Greet me");
+
+        client.execute_task_prompt(&prompt, &TaskId::new("task")).await?;
+        client.embed(&["text".to_string()]).await.ok();
+
+        let bodies = server.request_bodies();
+        assert_eq!(bodies[0]["temperature"].as_f64(), Some(0.0));
+        assert_eq!(bodies[0]["seed"], 1234);
+        assert!(bodies[1].get("seed").is_none() && bodies[1].get("temperature").is_none());
+
+        // Without an explicit seed deterministic mode still pins one
+        let server = MockServer::start(vec![MockResponse::completion("Hello")]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?
+            .with_deterministic(true);
+        client.execute_task_prompt(&prompt, &TaskId::new("task")).await?;
+        assert_eq!(server.request_bodies()[0]["seed"], DETERMINISTIC_SEED);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_models() -> Result<()> {
+        let models = json!({
+            "object": "list",
+            "data": [
+                { "id": "gpt-4o", "object": "model", "owned_by": "openai" },
+                { "id": "local-llama", "object": "model" }
+            ]
+        });
+        let server = MockServer::start(vec![MockResponse::Json(200, models.to_string())]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+
+        let listed = client.list_models().await?;
+        let ids: Vec<&str> = listed.iter().map(|model| model.id.as_str()).collect();
+        assert_eq!(ids, vec!["gpt-4o", "local-llama"]);
+        assert_eq!(listed[0].owned_by, "openai");
+        assert_eq!(listed[1].owned_by, "");
+        assert!(server.requests()[0].starts_with("GET /v1/models "), "{}", server.requests()[0]);
+
+        let server = MockServer::start(vec![MockResponse::Json(401, "{\"error\":\"bad key\"}".to_string())]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+        assert!(matches!(client.list_models().await, Err(InferenceError::Request(_))));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stalled_stream() -> Result<()> {
+        let server = MockServer::start(vec![MockResponse::Stall(vec![MockResponse::sse_delta("Hel")])]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?
+            .with_response_persistence(false);
+        let prompt = Prompt::new("You are a helpful assistant", "Greet me");
+
+        let cancel = CancellationToken::new();
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            trigger.cancel();
+        });
+
+        let started = Instant::now();
+        let result = client.stream_completion_cancellable(&prompt, 0.7, &cancel).await;
+        assert!(matches!(result, Err(InferenceError::Cancelled)), "{:?}", result);
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(server.requests().len(), 1);
+
+        // A token cancelled up front means no request is sent at all
+        let server = MockServer::start(vec![MockResponse::completion("unused")]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+        let result = client.execute_task_prompt_cancellable(&prompt, &TaskId::new("task"), &cancel).await;
+        assert!(matches!(result, Err(InferenceError::Cancelled)));
+        assert!(server.requests().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stream_completion_json_lines() -> Result<()> {
+        let server = MockServer::start(vec![MockResponse::Stream {
+            chunks: vec![
+                "{\"message\":{\"content\":\"Hello\"},\"done\":false}\n".to_string(),
+                "{\"message\":{\"content\":\" there\"},\"done\":false}\n".to_string(),
+                "{\"done\":true}".to_string(),
+            ],
+            complete: true,
+        }]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?
+            .with_response_persistence(false)
+            .with_stream_format(StreamFormat::JsonLines);
+
+        let prompt = Prompt::new("You are a helpful assistant", "Greet me");
+        assert_eq!(client.stream_completion(&prompt, 0.7).await?, "Hello there");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_streamed_secrets_redacted_in_response_file() -> Result<()> {
+        let server = MockServer::start(vec![MockResponse::Stream {
+            chunks: vec![
+                MockResponse::sse_delta("Use key sk-abcdefgh"),
+                MockResponse::sse_delta("ijklmnopqrstuv\nthen run"),
+                MockResponse::sse_delta(" it with Bearer tok_123456789"),
+                "data: [DONE]\n\n".to_string(),
+            ],
+            complete: true,
+        }]).await;
+        let response_dir = tempfile::tempdir()?;
+
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?
+            .with_response_dir(response_dir.path());
+        let prompt = Prompt::new("You are a helpful assistant", "Leak something");
+        let content = client.stream_completion(&prompt, 0.7).await?;
+        assert!(content.contains("sk-abcdefghijklmnopqrstuv"));
+
+        let file = std::fs::read_dir(response_dir.path())?.next().unwrap()?.path();
+        assert_eq!(
+            std::fs::read_to_string(file)?,
+            "Use key ***\nthen run it with Bearer ***"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_broken_stream_keeps_partial_response() -> Result<()> {
+        let server = MockServer::start(vec![MockResponse::Stream {
+            chunks: vec![
+                MockResponse::sse_delta("first line\n"),
+                MockResponse::sse_delta("second line with sk-abcdefghijklmnopqrstuv"),
+            ],
+            complete: false,
+        }]).await;
+        let response_dir = tempfile::tempdir()?;
+
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?
+            .with_response_dir(response_dir.path());
+        let prompt = Prompt::new("You are a helpful assistant", "Break partway");
+        assert!(client.stream_completion(&prompt, 0.7).await.is_err());
+
+        let file = std::fs::read_dir(response_dir.path())?.next().unwrap()?.path();
+        assert_eq!(std::fs::read_to_string(file)?, "first line\nsecond line with ***");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_config_redaction_patterns_applied() -> Result<()> {
+        let server = MockServer::start(vec![MockResponse::Stream {
+            chunks: vec![MockResponse::sse_delta("password=hunter2"), "data: [DONE]\n\n".to_string()],
+            complete: true,
+        }]).await;
+        let response_dir = tempfile::tempdir()?;
+        let config = SystemConfig {
+            response_dir: Some(response_dir.path().to_path_buf()),
+            redaction_patterns: vec!["hunter2".to_string()],
+            ..Default::default()
+        };
+
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?
+            .with_config(&config)?;
+        let prompt = Prompt::new("You are a helpful assistant", "Leak something");
+        client.stream_completion(&prompt, 0.7).await?;
+
+        let file = std::fs::read_dir(response_dir.path())?.next().unwrap()?.path();
+        assert_eq!(std::fs::read_to_string(file)?, "password=***");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_streams_write_distinct_files() -> Result<()> {
+        let server = MockServer::start(streaming_server_responses()).await;
+        let response_dir = tempfile::tempdir()?;
+
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?
+            .with_response_dir(response_dir.path());
+        let prompt = Prompt::new("You are a helpful assistant", "Say saved");
+        let (first, second) = tokio::join!(
+            client.stream_completion(&prompt, 0.7),
+            client.stream_completion(&prompt, 0.7)
+        );
+        first?;
+        second?;
+
+        let files: Vec<PathBuf> = std::fs::read_dir(response_dir.path())?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<std::io::Result<_>>()?;
+        assert_eq!(files.len(), 2);
+        assert_ne!(files[0], files[1]);
+        for file in &files {
+            assert_eq!(std::fs::read_to_string(file)?, "saved");
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stream_response_not_written_when_disabled() -> Result<()> {
+        let server = MockServer::start(streaming_server_responses()).await;
+        let temp_dir = tempfile::tempdir()?;
+        let response_dir = temp_dir.path().join("responses");
+
+        let config = SystemConfig {
+            response_dir: Some(response_dir.clone()),
+            persist_responses: false,
+            ..Default::default()
+        };
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?
+            .with_config(&config)?;
+        let prompt = Prompt::new("You are a helpful assistant", "Say saved");
+        let content = client.stream_completion(&prompt, 0.7).await?;
+
+        assert_eq!(content, "saved");
+        assert!(!response_dir.exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_exchange_hook_captures_redacted_request() -> Result<()> {
+        let server = MockServer::start(vec![MockResponse::completion("hooked response")]).await;
+        let exchanges = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let captured = exchanges.clone();
+        let client = InferenceClient::with_endpoint("sk-secret-key", &server.base_url, "test-model")?
+            .with_exchange_hook(move |request, response| {
+                captured.lock().unwrap().push((request.clone(), response.clone()));
+            });
+        let prompt = Prompt::new("system", "my key is sk-secret-key");
+        client.execute_task_prompt(&prompt, &TaskId::new("task")).await?;
+
+        let exchanges = exchanges.lock().unwrap();
+        assert_eq!(exchanges.len(), 1);
+        let (request, response) = &exchanges[0];
+        assert_eq!(request.model, "test-model");
+        assert!(request.url.ends_with("/chat/completions"));
+        assert!(request.headers.contains(&("authorization".to_string(), "Bearer ***".to_string())));
+        assert!(!request.body.to_string().contains("sk-secret-key"));
+        assert_eq!(request.body["messages"][1]["content"], "my key is ***");
+        assert_eq!(response.status, 200);
+        assert!(response.body.contains("hooked response"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_custom_headers_sent_with_requests() -> Result<()> {
+        let server = MockServer::start(vec![MockResponse::completion("ok")]).await;
+
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?
+            .with_header("X-Org-Id", "org-123")?;
+        let prompt = Prompt::new("system", "user");
+        client.execute_task_prompt(&prompt, &TaskId::new("task")).await?;
+
+        let request = server.requests()[0].to_lowercase();
+        assert!(request.contains("x-org-id: org-123"));
+        assert!(request.contains("authorization: bearer test-key"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_base_url_normalization() -> Result<()> {
+        let expected = "https://api.openai.com/v1/chat/completions";
+        for base_url in [
+            "https://api.openai.com/v1",
+            "https://api.openai.com/v1/",
+            "https://api.openai.com",
+            "https://api.openai.com/",
+            "https://api.openai.com/v1/v1/",
+        ] {
+            let client = InferenceClient::with_endpoint("test-key", base_url, "test-model")?;
+            assert_eq!(client.endpoint("/chat/completions"), expected, "base URL {}", base_url);
+        }
+
+        let gateway = InferenceClient::with_endpoint("test-key", "http://localhost:8080/openai/v1/", "test-model")?;
+        assert_eq!(gateway.endpoint("/embeddings"), "http://localhost:8080/openai/v1/embeddings");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_base_url_rejected() {
+        for base_url in ["not a url", "ftp://example.com/v1", ""] {
+            let err = InferenceClient::with_endpoint("test-key", base_url, "test-model").err().unwrap();
+            assert!(err.to_string().contains("Invalid inference base URL"), "{}", err);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requests_reuse_pooled_connection() -> Result<()> {
+        let server = MockServer::start_keep_alive(vec![MockResponse::completion("pooled")]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+        let conversation = Conversation::new().user("Hello");
+
+        for _ in 0..3 {
+            assert_eq!(client.complete_conversation(&conversation).await?, "pooled");
+        }
+        assert_eq!(server.requests().len(), 3);
+        assert_eq!(server.connections(), 1);
+
+        // A shared client keeps its pool across inference clients
+        let shared = reqwest::Client::new();
+        let first = InferenceClient::with_endpoint("test-key", &server.base_url, "a")?.with_client(shared.clone());
+        let second = InferenceClient::with_endpoint("test-key", &server.base_url, "b")?.with_client(shared);
+        first.complete_conversation(&conversation).await?;
+        second.complete_conversation(&conversation).await?;
+        assert_eq!(server.connections(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_identical_requests_share_one_call() -> Result<()> {
+        let server = MockServer::start(vec![MockResponse::completion("shared answer")]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+        let conversation = Conversation::new().user("Generate the same thing");
+
+        let replies = futures::future::join_all((0..5).map(|_| client.complete_conversation(&conversation))).await;
+        for reply in replies {
+            assert_eq!(reply?, "shared answer");
+        }
+        assert_eq!(server.requests().len(), 1);
+
+        let independent = client.with_request_deduplication(false);
+        let replies = futures::future::join_all((0..2).map(|_| independent.complete_conversation(&conversation))).await;
+        assert!(replies.iter().all(|reply| reply.is_ok()));
+        assert_eq!(server.requests().len(), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_requests_routed_through_proxy() -> Result<()> {
+        let proxy = MockServer::start(vec![MockResponse::completion("ok")]).await;
+
+        let client = InferenceClient::with_endpoint("test-key", "http://inference.invalid/v1", "test-model")?
+            .with_proxy(&proxy.base_url)?;
+        let prompt = Prompt::new("system", "user");
+        client.execute_task_prompt(&prompt, &TaskId::new("task")).await?;
+
+        assert!(proxy.requests()[0].starts_with("POST http://inference.invalid/v1/chat/completions"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_project_template_falls_back_to_embedded() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let missing = dir.path().join("templates/project_generation.txt");
+        assert_eq!(load_project_template(&missing)?, DEFAULT_PROJECT_TEMPLATE);
+
+        let override_path = dir.path().join("override.txt");
+        std::fs::write(&override_path, "custom template")?;
+        assert_eq!(load_project_template(&override_path)?, "custom template");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_project_config_sends_template_prompt() -> Result<()> {
+        let server = MockServer::start(vec![MockResponse::completion(
+            r#"{"project_name": "demo", "language": "Rust"}"#,
+        )]).await;
+
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+        let config = client.generate_project_config("Create a demo project").await?;
+        assert!(config.contains("demo"));
+
+        let bodies = server.request_bodies();
+        let system_prompt = bodies[0]["messages"][0]["content"].as_str().unwrap();
+        assert!(system_prompt.starts_with("# Project Generation Guide"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_project_config_without_templates_dir() -> Result<()> {
+        let server = MockServer::start(vec![MockResponse::completion(
+            r#"{"project_name": "demo", "language": "Rust"}"#,
+        )]).await;
+        let dir = tempfile::tempdir()?;
+        assert!(!dir.path().join("templates").exists());
+
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?
+            .with_template_path(dir.path().join("templates/project_generation.txt"));
+        let config = client.generate_project_config("Create a demo project").await?;
+        assert!(config.contains("demo"));
+
+        let bodies = server.request_bodies();
+        assert_eq!(bodies[0]["messages"][0]["content"], DEFAULT_PROJECT_TEMPLATE);
+
+        Ok(())
+    }
 }
 
 // Fallback mock implementation for testing
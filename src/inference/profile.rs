@@ -0,0 +1,69 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Named bundle of generation settings, declared as a `[profiles.<name>]` table in the
+/// system config. Unset fields fall back to the client's own defaults.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationProfile {
+    /// Filled in from the table key by `SystemConfig::profile`
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// System prompt template replacing the default project generation template
+    #[serde(default)]
+    pub template: Option<PathBuf>,
+}
+
+impl GenerationProfile {
+    /// Overwrite the request's model, temperature and token limit with whichever of
+    /// them this profile sets
+    pub fn apply(&self, request_body: &mut serde_json::Value) {
+        if let Some(model) = &self.model {
+            request_body["model"] = model.clone().into();
+        }
+        if let Some(temperature) = self.temperature {
+            request_body["temperature"] = temperature.into();
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            request_body["max_tokens"] = max_tokens.into();
+        }
+    }
+
+    /// The profile's template if it names one, otherwise the template at `default_path`
+    /// (or the embedded default when that file does not exist)
+    pub fn load_template(&self, default_path: &Path) -> Result<String> {
+        match &self.template {
+            Some(path) => std::fs::read_to_string(path).with_context(|| {
+                format!("Failed to read template for profile '{}': {}", self.name, path.display())
+            }),
+            None => super::load_project_template(default_path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_apply_only_overrides_set_fields() {
+        let profile = GenerationProfile {
+            temperature: Some(0.25),
+            max_tokens: Some(512),
+            ..Default::default()
+        };
+        let mut body = json!({ "model": "base-model", "temperature": 0.7 });
+        profile.apply(&mut body);
+
+        assert_eq!(body["model"], "base-model");
+        assert_eq!(body["temperature"], 0.25);
+        assert_eq!(body["max_tokens"], 512);
+    }
+}
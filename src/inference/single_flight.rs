@@ -0,0 +1,99 @@
+use futures::future::{BoxFuture, FutureExt, Shared};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+/// Collapses concurrent calls with the same key into one: the first caller (the leader)
+/// starts the work and every caller that arrives while it is in flight awaits the same
+/// shared result
+pub struct SingleFlight<T: Clone> {
+    in_flight: Mutex<HashMap<u64, Shared<BoxFuture<'static, T>>>>,
+}
+
+impl<T: Clone + Send + Sync + 'static> SingleFlight<T> {
+    pub fn new() -> Self {
+        Self { in_flight: Mutex::new(HashMap::new()) }
+    }
+
+    /// Hash identifying a call
+    pub fn key(value: &impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Await the in-flight call for `key`, or start one with `start`. Also returns
+    /// whether this caller was the leader.
+    pub async fn run<F>(&self, key: u64, start: impl FnOnce() -> F) -> (T, bool)
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        let (shared, guard) = {
+            let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+            match in_flight.get(&key) {
+                Some(shared) => (shared.clone(), None),
+                None => {
+                    let shared = start().boxed().shared();
+                    in_flight.insert(key, shared.clone());
+                    (shared, Some(LeaderGuard { flight: self, key }))
+                }
+            }
+        };
+        let output = shared.await;
+        (output, guard.is_some())
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> Default for SingleFlight<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Forgets the leader's entry once it finishes or is dropped, so later calls start afresh
+/// instead of reusing a stale result
+struct LeaderGuard<'a, T: Clone> {
+    flight: &'a SingleFlight<T>,
+    key: u64,
+}
+
+impl<T: Clone> Drop for LeaderGuard<'_, T> {
+    fn drop(&mut self) {
+        self.flight
+            .in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_concurrent_calls_share_one_execution() {
+        let flight = SingleFlight::<usize>::new();
+        let started = Arc::new(AtomicUsize::new(0));
+        let call = || {
+            let started = started.clone();
+            flight.run(SingleFlight::<usize>::key(&"same"), move || async move {
+                tokio::task::yield_now().await;
+                started.fetch_add(1, Ordering::SeqCst) + 41
+            })
+        };
+
+        let results = futures::future::join_all((0..3).map(|_| call())).await;
+        assert_eq!(started.load(Ordering::SeqCst), 1);
+        assert!(results.iter().all(|(value, _)| *value == 41));
+        assert_eq!(results.iter().filter(|(_, leader)| *leader).count(), 1);
+
+        // Once finished, the same key runs again
+        call().await;
+        assert_eq!(started.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Wire format of a streamed completion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamFormat {
+    /// OpenAI-style server-sent events: `data: {json}` lines ending with `data: [DONE]`
+    #[default]
+    OpenAiSse,
+    /// One JSON object per line, as sent by Ollama and some gateways; a line with
+    /// `"done": true` ends the stream
+    JsonLines,
+}
+
+impl StreamFormat {
+    /// Content delta carried by one line of the stream, if any. Blank lines, keep-alives
+    /// and done markers yield `None`.
+    pub fn parse_line(&self, line: &str) -> Option<String> {
+        let line = line.trim();
+        let data = match self {
+            StreamFormat::OpenAiSse => line.strip_prefix("data:")?.trim(),
+            StreamFormat::JsonLines => line,
+        };
+        if data.is_empty() || data == "[DONE]" {
+            return None;
+        }
+
+        let value: Value = serde_json::from_str(data).ok()?;
+        let content = match self {
+            StreamFormat::OpenAiSse => value.pointer("/choices/0/delta/content"),
+            StreamFormat::JsonLines => value
+                .pointer("/choices/0/delta/content")
+                .or_else(|| value.pointer("/message/content"))
+                .or_else(|| value.get("response"))
+                .or_else(|| value.get("content")),
+        };
+        content
+            .and_then(|content| content.as_str())
+            .filter(|content| !content.is_empty())
+            .map(|content| content.to_string())
+    }
+
+    /// Concatenate the content of every complete line in `body`
+    pub fn accumulate(&self, body: &str) -> String {
+        body.lines().filter_map(|line| self.parse_line(line)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formats_accumulate_identically() {
+        let sse = concat!(
+            "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"Hello\"}}]}\n\n",
+            ": keep-alive\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\", world\"}}]}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let json_lines = concat!(
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"Hello\"},\"done\":false}\n",
+            "{\"response\":\", world\",\"done\":false}\n",
+            "{\"message\":{\"role\":\"assistant\",\"content\":\"\"},\"done\":true}\n",
+        );
+
+        let from_sse = StreamFormat::OpenAiSse.accumulate(sse);
+        assert_eq!(from_sse, "Hello, world");
+        assert_eq!(StreamFormat::JsonLines.accumulate(json_lines), from_sse);
+    }
+
+    #[test]
+    fn test_sse_parser_ignores_unprefixed_json() {
+        let line = "{\"choices\":[{\"delta\":{\"content\":\"raw\"}}]}";
+        assert_eq!(StreamFormat::OpenAiSse.parse_line(line), None);
+        assert_eq!(StreamFormat::JsonLines.parse_line(line), Some("raw".to_string()));
+    }
+}
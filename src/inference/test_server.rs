@@ -0,0 +1,211 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// A scripted reply for a single connection to the mock server
+#[derive(Debug, Clone)]
+pub enum MockResponse {
+    /// Plain JSON body with the given status code
+    Json(u16, String),
+    /// Chunked body; when `complete` is false the connection is dropped after the last chunk
+    Stream { chunks: Vec<String>, complete: bool },
+    /// Chunked body that sends `chunks` and then leaves the response unfinished until the
+    /// client hangs up
+    Stall(Vec<String>),
+}
+
+impl MockResponse {
+    /// A successful chat completion whose message content is `content`
+    pub fn completion(content: &str) -> Self {
+        let body = serde_json::json!({
+            "choices": [{ "message": { "role": "assistant", "content": content } }]
+        });
+        MockResponse::Json(200, body.to_string())
+    }
+
+    /// An OpenAI-style SSE frame carrying a single content delta
+    pub fn sse_delta(content: &str) -> String {
+        let body = serde_json::json!({ "choices": [{ "delta": { "content": content } }] });
+        format!("data: {}\n\n", body)
+    }
+}
+
+/// Minimal HTTP/1.1 server replying to each connection with the next scripted response.
+/// The last response is repeated once the script is exhausted.
+pub struct MockServer {
+    pub base_url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+    connections: Arc<AtomicUsize>,
+}
+
+impl MockServer {
+    pub async fn start(responses: Vec<MockResponse>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let connections = Arc::new(AtomicUsize::new(0));
+
+        let recorded = requests.clone();
+        let accepted = connections.clone();
+        tokio::spawn(async move {
+            let mut index = 0;
+            while let Ok((stream, _)) = listener.accept().await {
+                accepted.fetch_add(1, Ordering::SeqCst);
+                let response = responses[index.min(responses.len() - 1)].clone();
+                index += 1;
+                let recorded = recorded.clone();
+                tokio::spawn(async move {
+                    let _ = handle_connection(stream, response, recorded).await;
+                });
+            }
+        });
+
+        Self { base_url, requests, connections }
+    }
+
+    /// Like `start`, but serves any number of requests per connection with HTTP
+    /// keep-alive, so `connections` shows whether clients reuse them. Only JSON
+    /// responses are supported.
+    pub async fn start_keep_alive(responses: Vec<MockResponse>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let connections = Arc::new(AtomicUsize::new(0));
+        let next = Arc::new(AtomicUsize::new(0));
+        let responses = Arc::new(responses);
+
+        let recorded = requests.clone();
+        let accepted = connections.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                accepted.fetch_add(1, Ordering::SeqCst);
+                let (recorded, next, responses) = (recorded.clone(), next.clone(), responses.clone());
+                tokio::spawn(async move {
+                    while let Ok(Some(request)) = read_request(&mut stream).await {
+                        recorded.lock().unwrap().push(request);
+                        let index = next.fetch_add(1, Ordering::SeqCst);
+                        let MockResponse::Json(status, body) = &responses[index.min(responses.len() - 1)] else {
+                            panic!("keep-alive mock server only supports JSON responses");
+                        };
+                        let reply = format!(
+                            "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: keep-alive\r\n\r\n{}",
+                            status,
+                            body.len(),
+                            body
+                        );
+                        if stream.write_all(reply.as_bytes()).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        Self { base_url, requests, connections }
+    }
+
+    /// Number of TCP connections accepted so far
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::SeqCst)
+    }
+
+    /// Raw text (headers and body) of every request received so far
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Parsed JSON body of every request received so far
+    pub fn request_bodies(&self) -> Vec<serde_json::Value> {
+        self.requests()
+            .iter()
+            .filter_map(|request| request.split("\r\n\r\n").nth(1))
+            .filter_map(|body| serde_json::from_str(body).ok())
+            .collect()
+    }
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    response: MockResponse,
+    recorded: Arc<Mutex<Vec<String>>>,
+) -> std::io::Result<()> {
+    let Some(request) = read_request(&mut stream).await? else {
+        return Ok(());
+    };
+    recorded.lock().unwrap().push(request);
+
+    match response {
+        MockResponse::Json(status, body) => {
+            let reply = format!(
+                "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(reply.as_bytes()).await?;
+        }
+        MockResponse::Stream { chunks, complete } => {
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n")
+                .await?;
+            for data in chunks {
+                stream
+                    .write_all(format!("{:x}\r\n{}\r\n", data.len(), data).as_bytes())
+                    .await?;
+                stream.flush().await?;
+            }
+            if complete {
+                stream.write_all(b"0\r\n\r\n").await?;
+            }
+        }
+        MockResponse::Stall(chunks) => {
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n")
+                .await?;
+            for data in chunks {
+                stream
+                    .write_all(format!("{:x}\r\n{}\r\n", data.len(), data).as_bytes())
+                    .await?;
+                stream.flush().await?;
+            }
+            // Returns once the client closes the connection
+            let _ = stream.read(&mut [0u8; 1]).await;
+        }
+    }
+
+    stream.shutdown().await
+}
+
+/// Read one request (headers plus `Content-Length` bytes of body) from `stream`;
+/// `None` once the client closes the connection
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<Option<String>> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    // Read headers, then as much body as Content-Length announces
+    let header_end = loop {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+    let headers = String::from_utf8_lossy(&buffer[..header_end]).to_lowercase();
+    let content_length = headers
+        .lines()
+        .find_map(|line| line.strip_prefix("content-length:"))
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    while buffer.len() < header_end + content_length {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+    Ok(Some(String::from_utf8_lossy(&buffer).into_owned()))
+}
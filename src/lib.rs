@@ -12,6 +12,7 @@ pub mod validation;
 // Utility and support modules
 pub mod config;
 pub mod logging;
+pub mod util;
 
 // AI and inference modules
 pub mod llm;
@@ -28,7 +29,7 @@ pub mod error;
 // Exports
 pub use cli::handle_cli_command;
 pub use validation::BuildValidation;
-pub use prompt::storage::{PromptStorage, Storage};
+pub use prompt::storage::{PromptStorage, SchemaValidator, Storage};
 pub use state::manager::StateManager;
 pub use state::types::{TaskId, TaskState, TaskStatus, TaskMetadata};
 pub use build::error::BuildError;
@@ -64,9 +65,27 @@ pub async fn validate_saved_build(
 ) -> Result<validation::ValidationReport> {
     // Initialize storage
     let storage = prompt::storage::Storage::new(storage_path)?;
+    validate_stored_build(&storage, validation_key)
+}
 
+/// Validate a previously saved build and store the report next to it, where
+/// `ValidationReport::load` finds it by the same `validation_key`
+pub async fn validate_and_save_build(
+    storage_path: PathBuf,
+    validation_key: &str,
+) -> Result<validation::ValidationReport> {
+    let storage = prompt::storage::Storage::new(storage_path)?;
+    let report = validate_stored_build(&storage, validation_key)?;
+    report.save(&storage, validation_key)?;
+    Ok(report)
+}
+
+fn validate_stored_build(
+    storage: &prompt::storage::Storage,
+    validation_key: &str,
+) -> Result<validation::ValidationReport> {
     // Load the validation data
-    let validation = BuildValidation::load(&storage, validation_key)?
+    let validation = BuildValidation::load(storage, validation_key)?
         .ok_or_else(|| anyhow::anyhow!("Validation data not found for key: {}", validation_key))?;
 
     // Run validation
@@ -102,6 +121,7 @@ impl BuildSystem {
             directory_structure: config.directory_structure.into_iter()
                 .map(|(k, v)| (k, v.to_vec()))
                 .collect(),
+            generate_tests: false,
         };
 
         let generator = project_generator::ProjectGenerator::new(design);
@@ -137,6 +157,7 @@ impl ProjectManager {
             directory_structure: config.directory_structure.iter()
                 .map(|(k, v)| (k.clone(), v.to_vec()))
                 .collect(),
+            generate_tests: false,
         };
 
         let generator = project_generator::ProjectGenerator::new(design);
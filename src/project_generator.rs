@@ -1,8 +1,9 @@
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::Path};
+use std::{collections::HashMap, fmt, path::{Path, PathBuf}, str::FromStr};
 use tokio::fs;
 use async_trait::async_trait;
+use indexmap::IndexMap;
 use crate::tools::ExecutableTool;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -21,6 +22,9 @@ pub struct ProjectDesign {
     pub build_config: BuildConfig,
     #[serde(alias = "directories", alias = "files")]
     pub directory_structure: HashMap<String, Vec<String>>,
+    /// Add unit test stubs to generated Rust modules and an integration test file
+    #[serde(default)]
+    pub generate_tests: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -32,7 +36,86 @@ pub struct Dependencies {
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct BuildConfig {
     pub build_tool: String,
-    pub scripts: HashMap<String, String>,
+    pub scripts: IndexMap<String, String>,
+}
+
+/// Languages the generator knows how to scaffold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupportedLanguage {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Go,
+    Java,
+}
+
+impl SupportedLanguage {
+    pub const ALL: [SupportedLanguage; 6] = [
+        SupportedLanguage::Rust,
+        SupportedLanguage::Python,
+        SupportedLanguage::JavaScript,
+        SupportedLanguage::TypeScript,
+        SupportedLanguage::Go,
+        SupportedLanguage::Java,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SupportedLanguage::Rust => "rust",
+            SupportedLanguage::Python => "python",
+            SupportedLanguage::JavaScript => "javascript",
+            SupportedLanguage::TypeScript => "typescript",
+            SupportedLanguage::Go => "go",
+            SupportedLanguage::Java => "java",
+        }
+    }
+}
+
+impl fmt::Display for SupportedLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for SupportedLanguage {
+    type Err = ProjectGenerationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().to_lowercase();
+        SupportedLanguage::ALL
+            .iter()
+            .find(|language| language.as_str() == normalized)
+            .copied()
+            .ok_or_else(|| ProjectGenerationError::ValidationError(format!(
+                "Unsupported language '{}'. Supported languages: {}",
+                s,
+                supported_languages().join(", ")
+            )))
+    }
+}
+
+impl clap::ValueEnum for SupportedLanguage {
+    fn value_variants<'a>() -> &'a [Self] {
+        &SupportedLanguage::ALL
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        Some(clap::builder::PossibleValue::new(self.as_str()))
+    }
+}
+
+/// Clap value parser for `--language` arguments: rejects unsupported languages while
+/// parsing, listing the supported ones, and yields the canonical lowercase name. Pair it
+/// with `ignore_case = true` to accept e.g. `Rust`.
+pub fn language_value_parser() -> impl clap::builder::TypedValueParser<Value = String> {
+    use clap::builder::TypedValueParser;
+    clap::builder::EnumValueParser::<SupportedLanguage>::new().map(|language| language.as_str().to_string())
+}
+
+/// Names of every language accepted by `SupportedLanguage::from_str`
+pub fn supported_languages() -> Vec<&'static str> {
+    SupportedLanguage::ALL.iter().map(|language| language.as_str()).collect()
 }
 
 #[derive(Debug)]
@@ -68,6 +151,11 @@ impl std::error::Error for ProjectGenerationError {}
 
 impl ProjectDesign {
     pub fn validate(&self) -> Result<(), ProjectGenerationError> {
+        self.validate_with(&GeneratorRegistry::default())
+    }
+
+    /// Validate the design, accepting any language `registry` has a generator for
+    pub fn validate_with(&self, registry: &GeneratorRegistry) -> Result<(), ProjectGenerationError> {
         if self.name.is_empty() {
             return Err(ProjectGenerationError::ValidationError(
                 "Project name cannot be empty".to_string(),
@@ -80,13 +168,41 @@ impl ProjectDesign {
             ));
         }
 
+        if registry.get(&self.language).is_none() {
+            return Err(ProjectGenerationError::ValidationError(format!(
+                "No generator registered for language '{}'. Registered languages: {}",
+                self.language,
+                registry.languages().join(", ")
+            )));
+        }
+
         Ok(())
     }
 
     pub async fn generate_project_structure(&self) -> Result<(), ProjectGenerationError> {
-        let project_root = format!("build/{}", self.name);
+        self.generate_project_structure_in(&Path::new("build").join(&self.name)).await
+    }
+
+    /// Generate the project directly under `project_root`
+    pub async fn generate_project_structure_in(&self, project_root: &Path) -> Result<(), ProjectGenerationError> {
+        self.write_project_structure(project_root, &GeneratorRegistry::default()).await?;
+        Ok(())
+    }
+
+    /// Generate the project under `project_root`, returning every file written in the
+    /// order it was written
+    async fn write_project_structure(
+        &self,
+        project_root: &Path,
+        registry: &GeneratorRegistry,
+    ) -> Result<Vec<PathBuf>, ProjectGenerationError> {
+        let mut created = Vec::new();
+        let project_root = project_root.to_string_lossy().into_owned();
         fs::create_dir_all(&project_root).await?;
 
+        let with_tests = self.generate_tests
+            && self.language.parse::<SupportedLanguage>().ok() == Some(SupportedLanguage::Rust);
+
         // Create directory structure
         for (dir, files) in &self.directory_structure {
             let dir_path = format!("{}/{}", project_root, dir);
@@ -94,37 +210,61 @@ impl ProjectDesign {
             
             for file in files {
                 let file_path = format!("{}/{}", dir_path, file);
-                fs::write(&file_path, "").await?;
+                // Integration tests under tests/ are crates of their own and need no test module
+                let content = if with_tests && file.ends_with(".rs") && dir != "tests" {
+                    RUST_TEST_MODULE_STUB
+                } else {
+                    ""
+                };
+                fs::write(&file_path, content).await?;
+                created.push(PathBuf::from(file_path));
             }
         }
 
-        // Create dependency files
-        let requirements = self.dependencies.production
-            .iter()
-            .map(|(pkg, ver)| format!("{}=={}", pkg, ver))
-            .collect::<Vec<_>>()
-            .join("\n");
-        
-        let dev_requirements = self.dependencies.development
-            .iter()
-            .map(|(pkg, ver)| format!("{}=={}", pkg, ver))
-            .collect::<Vec<_>>()
-            .join("\n");
-        
-        fs::write(format!("{}/requirements.txt", project_root), requirements).await?;
-        fs::write(format!("{}/dev-requirements.txt", project_root), dev_requirements).await?;
+        if with_tests {
+            created.extend(self.generate_integration_test_stub(Path::new(&project_root)).await?);
+        }
 
-        // Create build.json
-        let build_json = serde_json::to_string_pretty(&self.build_config)?;
-        fs::write(format!("{}/build.json", project_root), build_json).await?;
+        // Language-specific files come from the registered generator
+        let project_root = Path::new(&project_root);
+        let generator = registry.get(&self.language).ok_or_else(|| {
+            ProjectGenerationError::ValidationError(format!(
+                "No generator registered for language '{}'. Registered languages: {}",
+                self.language,
+                registry.languages().join(", ")
+            ))
+        })?;
+        // A generator may fill in a file the directory structure already created
+        for path in generator.generate(self, project_root).await? {
+            if !created.contains(&path) {
+                created.push(path);
+            }
+        }
 
         // Generate architecture.md
-        self.generate_architecture_md(Path::new(&project_root)).await?;
+        created.push(self.generate_architecture_md(project_root).await?);
 
-        Ok(())
+        Ok(created)
     }
 
-    async fn generate_architecture_md(&self, project_root: &Path) -> Result<(), ProjectGenerationError> {
+    /// Create `tests/integration.rs` unless the design already lists integration tests,
+    /// returning its path if it was created
+    async fn generate_integration_test_stub(&self, project_root: &Path) -> Result<Option<PathBuf>, ProjectGenerationError> {
+        let has_tests = self
+            .directory_structure
+            .get("tests")
+            .is_some_and(|files| files.iter().any(|file| file.ends_with(".rs")));
+        if has_tests {
+            return Ok(None);
+        }
+
+        let tests_dir = project_root.join("tests");
+        fs::create_dir_all(&tests_dir).await?;
+        fs::write(tests_dir.join("integration.rs"), RUST_INTEGRATION_TEST_STUB).await?;
+        Ok(Some(tests_dir.join("integration.rs")))
+    }
+
+    async fn generate_architecture_md(&self, project_root: &Path) -> Result<PathBuf, ProjectGenerationError> {
         let mut content = format!(
             "# {} Architecture\n\n## Overview\n{}\n\n",
             self.name, self.description
@@ -145,12 +285,12 @@ impl ProjectDesign {
 
         content.push_str("\n## Dependencies\n");
         content.push_str("\n### Production Dependencies\n");
-        for (name, version) in &self.dependencies.production {
+        for (name, version) in sorted_dependencies(&self.dependencies.production) {
             content.push_str(&format!("- {} v{}\n", name, version));
         }
 
         content.push_str("\n### Development Dependencies\n");
-        for (name, version) in &self.dependencies.development {
+        for (name, version) in sorted_dependencies(&self.dependencies.development) {
             content.push_str(&format!("- {} v{}\n", name, version));
         }
 
@@ -161,8 +301,9 @@ impl ProjectDesign {
             content.push_str(&format!("- {}: `{}`\n", name, script));
         }
 
-        fs::write(project_root.join("architecture.md"), content).await?;
-        Ok(())
+        let path = project_root.join("architecture.md");
+        fs::write(&path, content).await?;
+        Ok(path)
     }
 }
 
@@ -219,20 +360,367 @@ The project design includes:
     }
 }
 
+const RUST_TEST_MODULE_STUB: &str = "#[cfg(test)]
+mod tests {
+    #[test]
+    fn placeholder() {
+        // TODO: test this module
+    }
+}
+";
+
+const RUST_INTEGRATION_TEST_STUB: &str = "#[test]
+fn placeholder() {
+    // TODO: exercise the crate's public API
+}
+";
+
+fn sorted_dependencies(dependencies: &HashMap<String, String>) -> Vec<(&String, &String)> {
+    let mut sorted: Vec<_> = dependencies.iter().collect();
+    sorted.sort();
+    sorted
+}
+
 pub fn parse_project_design(json: &str) -> Result<ProjectDesign, ProjectGenerationError> {
     serde_json::from_str(json).map_err(ProjectGenerationError::SerializationError)
 }
 
+/// Writes the language-specific part of a generated project, such as dependency
+/// manifests and build files. Register an implementation with [`GeneratorRegistry`]
+/// to support a language the crate does not know about.
+#[async_trait]
+pub trait LanguageGenerator: Send + Sync {
+    /// Language this generator handles, matched case-insensitively against
+    /// `ProjectDesign::language`
+    fn language(&self) -> &str;
+
+    /// Write the language-specific files under `root`, returning the path of every
+    /// file written in the order it was written
+    async fn generate(&self, design: &ProjectDesign, root: &Path) -> Result<Vec<PathBuf>, ProjectGenerationError>;
+}
+
+/// Built-in generator for the [`SupportedLanguage`]s: writes `requirements.txt`,
+/// `dev-requirements.txt` and `build.json`
+struct DependencyFilesGenerator {
+    language: SupportedLanguage,
+}
+
+#[async_trait]
+impl LanguageGenerator for DependencyFilesGenerator {
+    fn language(&self) -> &str {
+        self.language.as_str()
+    }
+
+    async fn generate(&self, design: &ProjectDesign, root: &Path) -> Result<Vec<PathBuf>, ProjectGenerationError> {
+        // Sorted so regenerating gives identical output
+        let requirements = |dependencies: &HashMap<String, String>| {
+            sorted_dependencies(dependencies)
+                .into_iter()
+                .map(|(pkg, ver)| format!("{}=={}", pkg, ver))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let files = [
+            (root.join("requirements.txt"), requirements(&design.dependencies.production)),
+            (root.join("dev-requirements.txt"), requirements(&design.dependencies.development)),
+            (root.join("build.json"), serde_json::to_string_pretty(&design.build_config)?),
+        ];
+        let mut written = Vec::new();
+        for (path, content) in files {
+            fs::write(&path, content).await?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+}
+
+/// Built-in generator for Rust: writes a `Cargo.toml` and makes sure the crate has an
+/// entry point, so the generated project passes `cargo check`
+struct CargoGenerator;
+
+#[async_trait]
+impl LanguageGenerator for CargoGenerator {
+    fn language(&self) -> &str {
+        SupportedLanguage::Rust.as_str()
+    }
+
+    async fn generate(&self, design: &ProjectDesign, root: &Path) -> Result<Vec<PathBuf>, ProjectGenerationError> {
+        let dependencies = |dependencies: &HashMap<String, String>| {
+            sorted_dependencies(dependencies)
+                .into_iter()
+                .map(|(name, version)| {
+                    let version = if version.is_empty() || version == "latest" { "*" } else { version.as_str() };
+                    format!("{} = \"{}\"\n", name, version)
+                })
+                .collect::<String>()
+        };
+        let manifest = format!(
+            "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n{}\n[dev-dependencies]\n{}",
+            crate_name(&design.name),
+            dependencies(&design.dependencies.production),
+            dependencies(&design.dependencies.development)
+        );
+        let cargo_toml = root.join("Cargo.toml");
+        fs::write(&cargo_toml, manifest).await?;
+        let build_json = root.join("build.json");
+        fs::write(&build_json, serde_json::to_string_pretty(&design.build_config)?).await?;
+        let mut written = vec![cargo_toml, build_json];
+
+        // Files from the directory structure are written empty, and a binary crate
+        // without `fn main` does not build
+        let main_rs = root.join("src/main.rs");
+        let main_source = match fs::read_to_string(&main_rs).await {
+            Ok(source) => Some(source),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let needs_main = match &main_source {
+            Some(source) => !source.contains("fn main"),
+            None => !root.join("src/lib.rs").exists(),
+        };
+        if needs_main {
+            fs::create_dir_all(root.join("src")).await?;
+            let content = match main_source {
+                Some(source) if !source.is_empty() => format!("{}\n{}", source, RUST_MAIN_STUB),
+                _ => RUST_MAIN_STUB.to_string(),
+            };
+            fs::write(&main_rs, content).await?;
+            written.push(main_rs);
+        }
+        Ok(written)
+    }
+}
+
+const RUST_MAIN_STUB: &str = "fn main() {
+    // TODO: implement the program
+}
+";
+
+/// `name` lowercased with every character Cargo does not allow in a package name
+/// replaced by `-`
+fn crate_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c.to_ascii_lowercase() } else { '-' })
+        .collect()
+}
+
+/// Language generators consulted during project generation, keyed by lowercased
+/// language name. The default registry holds a generator for every
+/// [`SupportedLanguage`]: Cargo files for Rust, dependency lists for the rest.
+pub struct GeneratorRegistry {
+    generators: HashMap<String, Box<dyn LanguageGenerator>>,
+}
+
+impl GeneratorRegistry {
+    /// An empty registry with no generators
+    pub fn new() -> Self {
+        Self { generators: HashMap::new() }
+    }
+
+    /// Add `generator`, replacing any generator already registered for its language
+    pub fn register(&mut self, generator: Box<dyn LanguageGenerator>) {
+        self.generators.insert(generator.language().trim().to_lowercase(), generator);
+    }
+
+    pub fn get(&self, language: &str) -> Option<&dyn LanguageGenerator> {
+        self.generators.get(&language.trim().to_lowercase()).map(|generator| generator.as_ref())
+    }
+
+    /// Registered language names, sorted
+    pub fn languages(&self) -> Vec<&str> {
+        let mut languages: Vec<&str> = self.generators.keys().map(String::as_str).collect();
+        languages.sort();
+        languages
+    }
+}
+
+impl Default for GeneratorRegistry {
+    fn default() -> Self {
+        let mut registry = Self::new();
+        for language in SupportedLanguage::ALL {
+            registry.register(Box::new(DependencyFilesGenerator { language }));
+        }
+        registry.register(Box::new(CargoGenerator));
+        registry
+    }
+}
+
 pub struct ProjectGenerator {
     config: ProjectDesign,
+    registry: GeneratorRegistry,
 }
 
 impl ProjectGenerator {
     pub fn new(config: ProjectDesign) -> Self {
-        Self { config }
+        Self { config, registry: GeneratorRegistry::default() }
+    }
+
+    /// Use `registry` instead of the built-in generators
+    pub fn with_registry(mut self, registry: GeneratorRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Register an extra language generator, replacing any built-in for that language
+    pub fn register(&mut self, generator: Box<dyn LanguageGenerator>) {
+        self.registry.register(generator);
     }
 
     pub async fn generate(&self) -> Result<(), ProjectGenerationError> {
-        self.config.generate_project_structure().await
+        self.generate_async().await?;
+        Ok(())
+    }
+
+    /// Generate the project under `build/<name>`, returning the files created
+    pub async fn generate_async(&self) -> Result<Vec<PathBuf>, ProjectGenerationError> {
+        self.generate_async_in(&Path::new("build").join(&self.config.name)).await
+    }
+
+    /// Generate the project directly under `project_root`, returning the files created
+    /// in the order they were written
+    pub async fn generate_async_in(&self, project_root: &Path) -> Result<Vec<PathBuf>, ProjectGenerationError> {
+        self.config.validate_with(&self.registry)?;
+        self.config.write_project_structure(project_root, &self.registry).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supported_language_recognized() {
+        assert_eq!("rust".parse::<SupportedLanguage>().unwrap(), SupportedLanguage::Rust);
+        assert_eq!("go".parse::<SupportedLanguage>().unwrap(), SupportedLanguage::Go);
+    }
+
+    #[test]
+    fn test_supported_language_case_insensitive() {
+        assert_eq!("TypeScript".parse::<SupportedLanguage>().unwrap(), SupportedLanguage::TypeScript);
+        assert_eq!("PYTHON".parse::<SupportedLanguage>().unwrap(), SupportedLanguage::Python);
+    }
+
+    #[test]
+    fn test_unsupported_language_lists_available() {
+        let err = "cobol".parse::<SupportedLanguage>().unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("Unsupported language 'cobol'"));
+        for language in supported_languages() {
+            assert!(message.contains(language), "missing {} in: {}", language, message);
+        }
+    }
+
+    fn rust_design(generate_tests: bool) -> ProjectDesign {
+        let mut directory_structure = HashMap::new();
+        directory_structure.insert("src".to_string(), vec!["main.rs".to_string(), "parser.rs".to_string(), "README.md".to_string()]);
+        ProjectDesign {
+            name: "stubbed".to_string(),
+            description: "Generated with test stubs".to_string(),
+            technologies: vec![],
+            project_type: "CommandLineInterface".to_string(),
+            language: "Rust".to_string(),
+            framework: String::new(),
+            dependencies: Dependencies::default(),
+            build_config: BuildConfig::default(),
+            directory_structure,
+            generate_tests,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_tests_adds_stubs() -> Result<(), ProjectGenerationError> {
+        let temp_dir = tempfile::tempdir()?;
+        rust_design(true).generate_project_structure_in(temp_dir.path()).await?;
+
+        let parser = std::fs::read_to_string(temp_dir.path().join("src/parser.rs"))?;
+        assert!(parser.contains("#[cfg(test)]\nmod tests {"));
+        assert_eq!(std::fs::read_to_string(temp_dir.path().join("src/README.md"))?, "");
+        let integration = std::fs::read_to_string(temp_dir.path().join("tests/integration.rs"))?;
+        assert!(integration.contains("#[test]"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generate_async_returns_created_files() -> Result<(), ProjectGenerationError> {
+        let temp_dir = tempfile::tempdir()?;
+        let generator = ProjectGenerator::new(rust_design(true));
+        let mut created = generator.generate_async_in(temp_dir.path()).await?;
+        created.sort();
+
+        let mut on_disk: Vec<PathBuf> = walkdir::WalkDir::new(temp_dir.path())
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path())
+            .collect();
+        on_disk.sort();
+
+        assert_eq!(created, on_disk);
+        assert!(created.contains(&temp_dir.path().join("tests/integration.rs")));
+        assert!(created.contains(&temp_dir.path().join("architecture.md")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_no_stubs_without_flag() -> Result<(), ProjectGenerationError> {
+        let temp_dir = tempfile::tempdir()?;
+        rust_design(false).generate_project_structure_in(temp_dir.path()).await?;
+
+        assert_eq!(std::fs::read_to_string(temp_dir.path().join("src/parser.rs"))?, "");
+        assert!(!temp_dir.path().join("tests").exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rust_project_gets_manifest_and_main() -> Result<(), ProjectGenerationError> {
+        let temp_dir = tempfile::tempdir()?;
+        let mut design = rust_design(true);
+        design.name = "My Tool".to_string();
+        design.dependencies.production.insert("serde".to_string(), "latest".to_string());
+        ProjectGenerator::new(design).generate_async_in(temp_dir.path()).await?;
+
+        let manifest = std::fs::read_to_string(temp_dir.path().join("Cargo.toml"))?;
+        assert!(manifest.contains("name = \"my-tool\""), "{}", manifest);
+        assert!(manifest.contains("serde = \"*\""), "{}", manifest);
+        let main = std::fs::read_to_string(temp_dir.path().join("src/main.rs"))?;
+        assert!(main.starts_with(RUST_TEST_MODULE_STUB));
+        assert!(main.contains("fn main() {"));
+        assert!(!temp_dir.path().join("requirements.txt").exists());
+        Ok(())
+    }
+
+    struct MixGenerator;
+
+    #[async_trait]
+    impl LanguageGenerator for MixGenerator {
+        fn language(&self) -> &str {
+            "Elixir"
+        }
+
+        async fn generate(&self, design: &ProjectDesign, root: &Path) -> Result<Vec<PathBuf>, ProjectGenerationError> {
+            let mix = root.join("mix.exs");
+            fs::write(&mix, format!("defmodule {}.MixProject do\nend\n", design.name)).await?;
+            Ok(vec![mix])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registered_generator_is_used() -> Result<(), ProjectGenerationError> {
+        let temp_dir = tempfile::tempdir()?;
+        let design = || ProjectDesign { language: "elixir".to_string(), ..rust_design(false) };
+        assert!(ProjectGenerator::new(design()).generate_async_in(temp_dir.path()).await.is_err());
+
+        let mut generator = ProjectGenerator::new(design());
+        generator.register(Box::new(MixGenerator));
+        assert!(design().validate().is_err());
+        assert!(design().validate_with(&generator.registry).is_ok());
+        let created = generator.generate_async_in(temp_dir.path()).await?;
+
+        let mix = temp_dir.path().join("mix.exs");
+        assert!(created.contains(&mix));
+        assert_eq!(std::fs::read_to_string(mix)?, "defmodule stubbed.MixProject do\nend\n");
+        assert!(!temp_dir.path().join("requirements.txt").exists());
+        Ok(())
     }
 }
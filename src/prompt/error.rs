@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use thiserror::Error;
 
 /// Custom error types for prompt-related operations
@@ -11,4 +12,21 @@ pub enum PromptError {
 
     #[error("Prompt not found")]
     NotFound,
+
+    #[error("Prompt template is missing variables: {}", .0.join(", "))]
+    MissingVariables(Vec<String>),
+}
+
+/// Errors opening the sled database behind `Storage` and `PromptStorage`
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("Database at {} is locked; another build-system instance may be using it", .0.display())]
+    AlreadyOpen(PathBuf),
+
+    #[error("Failed to open database at {}: {source}", .path.display())]
+    Open {
+        path: PathBuf,
+        #[source]
+        source: sled::Error,
+    },
 }
@@ -4,6 +4,9 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use crate::prompt::project_generation::{ProjectGenerationConfig, GenerationProjectType, GenerationBuildConfig, DirectoryEntry};
 use reqwest;
+use async_openai::types::Role;
+use crate::util::stable_hash;
+use crate::prompt::error::PromptError;
 
 pub mod error;
 pub mod generator;
@@ -28,11 +31,59 @@ impl Prompt {
     }
 }
 
+/// Ordered chat history sent as the `messages` array of a completion request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Conversation {
+    pub messages: Vec<(Role, String)>,
+}
+
+impl Conversation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn system(mut self, content: &str) -> Self {
+        self.messages.push((Role::System, content.to_string()));
+        self
+    }
+
+    pub fn user(mut self, content: &str) -> Self {
+        self.messages.push((Role::User, content.to_string()));
+        self
+    }
+
+    pub fn assistant(mut self, content: &str) -> Self {
+        self.messages.push((Role::Assistant, content.to_string()));
+        self
+    }
+
+    /// Render the history in the chat completions `messages` format
+    pub fn to_messages(&self) -> serde_json::Value {
+        serde_json::Value::Array(
+            self.messages
+                .iter()
+                .map(|(role, content)| serde_json::json!({ "role": role, "content": content }))
+                .collect(),
+        )
+    }
+}
+
+impl From<&Prompt> for Conversation {
+    fn from(prompt: &Prompt) -> Self {
+        Conversation::new()
+            .system(&prompt.system_context)
+            .user(&prompt.user_request)
+    }
+}
+
 #[async_trait::async_trait]
 pub trait PromptProcessor: Send + Sync {
     async fn process_response(&self, response: String) -> Result<()>;
 }
 
+/// Project generation prompt written to a template directory that doesn't have one yet
+const PROJECT_GENERATION_TEMPLATE: &str = include_str!("project_generation_prompt.md");
+
 #[derive(Debug)]
 pub struct PromptManager {
     template_dir: PathBuf,
@@ -44,9 +95,20 @@ impl PromptManager {
         let template_path = PathBuf::from(template_dir);
         std::fs::create_dir_all(&template_path)?;
 
-        // Write the project generation prompt template
+        // Write the project generation prompt template unless the user already has one
         let project_prompt_path = template_path.join("project_generation.txt");
-        std::fs::write(&project_prompt_path, include_str!("project_generation_prompt.md"))?;
+        if project_prompt_path.exists() {
+            let existing = std::fs::read_to_string(&project_prompt_path)
+                .with_context(|| format!("Failed to read {}", project_prompt_path.display()))?;
+            if stable_hash(&existing) != stable_hash(PROJECT_GENERATION_TEMPLATE) {
+                tracing::warn!(
+                    "{} differs from the built-in template; keeping the local version",
+                    project_prompt_path.display()
+                );
+            }
+        } else {
+            std::fs::write(&project_prompt_path, PROJECT_GENERATION_TEMPLATE)?;
+        }
 
         Ok(Self {
             template_dir: template_path,
@@ -73,6 +135,39 @@ impl PromptManager {
         Ok(())
     }
 
+    /// Names of the `{{placeholder}}`s in a loaded template, in order of first use;
+    /// empty for templates that aren't loaded
+    pub fn required_vars(&self, template_name: &str) -> Vec<String> {
+        self.templates
+            .get(template_name)
+            .map(|template| template_placeholders(template))
+            .unwrap_or_default()
+    }
+
+    /// Fill a loaded template's placeholders from `vars`, failing with every variable
+    /// left unfilled rather than sending the model a prompt with holes in it
+    pub fn render(&self, template_name: &str, vars: &HashMap<String, String>) -> Result<String, PromptError> {
+        let template = self.templates.get(template_name).ok_or(PromptError::NotFound)?;
+
+        let missing: Vec<String> = template_placeholders(template)
+            .into_iter()
+            .filter(|name| !vars.contains_key(name))
+            .collect();
+        if !missing.is_empty() {
+            return Err(PromptError::MissingVariables(missing));
+        }
+
+        let mut rendered = String::with_capacity(template.len());
+        let mut rest = template.as_str();
+        while let Some((before, name, after)) = next_placeholder(rest) {
+            rendered.push_str(before);
+            rendered.push_str(&vars[name]);
+            rest = after;
+        }
+        rendered.push_str(rest);
+        Ok(rendered)
+    }
+
     pub async fn generate_project_config(&self, user_request: &str) -> Result<ProjectConfig> {
         let template_path = self.template_dir.join("project_generation.txt");
         let template = tokio::fs::read_to_string(template_path)
@@ -131,9 +226,96 @@ impl PromptManager {
     }
 }
 
+/// Distinct placeholder names in `template`, in order of first use
+fn template_placeholders(template: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = template;
+    while let Some((_, name, after)) = next_placeholder(rest) {
+        if !names.iter().any(|known| known == name) {
+            names.push(name.to_string());
+        }
+        rest = after;
+    }
+    names
+}
+
+/// Split `text` around its first `{{name}}` placeholder into the text before it, the
+/// trimmed name, and the text after it. Braces around anything but an identifier are
+/// left alone, so JSON examples in a template aren't mistaken for placeholders.
+fn next_placeholder(text: &str) -> Option<(&str, &str, &str)> {
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("{{").map(|i| offset + i) {
+        let len = text[start + 2..].find("}}")?;
+        let name = text[start + 2..start + 2 + len].trim();
+        if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Some((&text[..start], name, &text[start + 4 + len..]));
+        }
+        offset = start + 2;
+    }
+    None
+}
+
 #[async_trait::async_trait]
 impl PromptProcessor for PromptManager {
     async fn process_response(&self, _response: String) -> Result<()> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_test::traced_test;
+
+    #[test]
+    #[traced_test]
+    fn test_template_written_once_and_user_edits_kept() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let dir = temp_dir.path().to_str().unwrap();
+        let template = temp_dir.path().join("project_generation.txt");
+
+        PromptManager::new(dir)?;
+        assert_eq!(std::fs::read_to_string(&template)?, PROJECT_GENERATION_TEMPLATE);
+        assert!(!logs_contain("differs from the built-in template"));
+
+        std::fs::write(&template, "My own instructions")?;
+        PromptManager::new(dir)?;
+        assert_eq!(std::fs::read_to_string(&template)?, "My own instructions");
+        assert!(logs_contain("differs from the built-in template"));
+        Ok(())
+    }
+
+    async fn manager_with(template: &str) -> Result<PromptManager> {
+        let temp_dir = tempfile::tempdir()?;
+        std::fs::write(temp_dir.path().join("scaffold.txt"), template)?;
+        let mut manager = PromptManager::new(temp_dir.path().to_str().unwrap())?;
+        manager.load_templates().await?;
+        Ok(manager)
+    }
+
+    #[tokio::test]
+    async fn test_required_vars_lists_placeholders() -> Result<()> {
+        let manager = manager_with("Create a {{language}} app with {{ framework }}.\nName: {{language}}-{{name}}\nExample: {{\"a\": 1}}").await?;
+
+        assert_eq!(manager.required_vars("scaffold.txt"), ["language", "framework", "name"]);
+        assert!(manager.required_vars("project_generation.txt").is_empty());
+        assert!(manager.required_vars("missing.txt").is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_rejects_missing_vars() -> Result<()> {
+        let manager = manager_with("Create a {{language}} app with {{framework}}").await?;
+        let mut vars = HashMap::from([("language".to_string(), "Rust".to_string())]);
+
+        match manager.render("scaffold.txt", &vars) {
+            Err(PromptError::MissingVariables(missing)) => assert_eq!(missing, ["framework"]),
+            other => panic!("expected MissingVariables, got {:?}", other),
+        }
+
+        vars.insert("framework".to_string(), "axum".to_string());
+        assert_eq!(manager.render("scaffold.txt", &vars)?, "Create a Rust app with axum");
+        assert!(matches!(manager.render("missing.txt", &vars), Err(PromptError::NotFound)));
+        Ok(())
+    }
+}
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use anyhow::Result;
+use std::fs;
+use std::path::Path;
+use indexmap::IndexMap;
+use anyhow::{anyhow, Context, Result};
 
 /// Represents a comprehensive project generation configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,6 +74,34 @@ pub enum GenerationProjectType {
     Tool,
 }
 
+impl GenerationProjectType {
+    /// Sensible starting dependencies for this kind of project in `language`
+    /// (case-insensitive); empty when there is no recommendation
+    pub fn recommended_dependencies(&self, language: &str) -> HashMap<String, String> {
+        use GenerationProjectType::*;
+
+        let deps: &[(&str, &str)] = match (language.to_lowercase().as_str(), self) {
+            ("rust", WebApplication | MicroService | Service) => &[
+                ("axum", "0.7"),
+                ("tokio", "1"),
+                ("serde", "1.0"),
+                ("serde_json", "1.0"),
+            ],
+            ("rust", CommandLineInterface | Tool) => &[("clap", "4"), ("anyhow", "1.0")],
+            ("rust", Library) => &[("thiserror", "1.0")],
+            ("python", WebApplication | MicroService | Service) => &[("fastapi", "0.110.0"), ("uvicorn", "0.29.0")],
+            ("python", CommandLineInterface | Tool) => &[("click", "8.1.7")],
+            ("javascript" | "typescript", WebApplication | MicroService | Service) => &[("express", "^4.18.0")],
+            ("javascript" | "typescript", CommandLineInterface | Tool) => &[("commander", "^11.0.0")],
+            _ => &[],
+        };
+
+        deps.iter()
+            .map(|(name, version)| (name.to_string(), version.to_string()))
+            .collect()
+    }
+}
+
 impl std::fmt::Display for GenerationProjectType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -115,9 +146,49 @@ impl GenerationDependencyConfig {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct GenerationBuildConfig {
     pub build_tool: String,
-    pub scripts: HashMap<String, String>,
+    /// Named scripts in the order they should be rendered
+    pub scripts: IndexMap<String, String>,
+}
+
+/// Result of `ProjectGenerationConfig::validate_detailed`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationOutcome {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ValidationOutcome {
+    /// True when there are no errors, or no issues at all if `fail_on_warnings` is set
+    pub fn passes(&self, fail_on_warnings: bool) -> bool {
+        self.errors.is_empty() && (!fail_on_warnings || self.warnings.is_empty())
+    }
+}
+
+/// Upper bounds on the directory structure a config may ask to scaffold, so a runaway
+/// generation cannot create thousands of directories or deeply nested paths
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StructureLimits {
+    /// Maximum number of top-level directories
+    pub max_dirs: usize,
+    /// Maximum number of files listed under a single directory
+    pub max_files_per_dir: usize,
+    /// Maximum number of path components in `dir/file`, counting the directory
+    pub max_path_depth: usize,
 }
 
+impl Default for StructureLimits {
+    fn default() -> Self {
+        Self {
+            max_dirs: 100,
+            max_files_per_dir: 200,
+            max_path_depth: 8,
+        }
+    }
+}
+
+/// Directory names recognized as holding the project's tests
+const TEST_DIRECTORIES: &[&str] = &["tests", "test", "__tests__", "spec"];
+
 /// Directory entry that can be either a single file or a list of files
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -164,36 +235,145 @@ impl ProjectGenerationConfig {
         })
     }
 
-    /// Validate the project generation configuration
+    /// JSON schema a generated config must satisfy before it can be deserialized
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "project_name": { "type": "string", "minLength": 1 },
+                "name": { "type": "string", "minLength": 1 },
+                "description": { "type": "string" },
+                "language": { "type": "string", "minLength": 1 },
+                "framework": { "type": "string" },
+                "project_type": {
+                    "enum": [
+                        "WebApplication", "CommandLineInterface", "Library", "MicroService",
+                        "DesktopApplication", "MobileApplication", "Application", "Service", "Tool"
+                    ]
+                },
+                "technologies": { "type": "array", "items": { "type": "string" } },
+                "directory_structure": { "type": "object" },
+                "dependencies": {
+                    "type": "object",
+                    "properties": {
+                        "production": { "type": "object", "additionalProperties": { "type": "string" } },
+                        "development": { "type": "object", "additionalProperties": { "type": "string" } }
+                    }
+                }
+            },
+            "required": ["language", "project_type"],
+            "anyOf": [
+                { "required": ["project_name"] },
+                { "required": ["name"] }
+            ]
+        })
+    }
+
+    /// Validate the project generation configuration, returning the first error.
+    /// Warnings don't fail validation; see `validate_detailed`.
     pub fn validate(&self) -> Result<(), String> {
+        self.validate_with_limits(&StructureLimits::default())
+    }
+
+    /// `validate` with custom directory structure limits
+    pub fn validate_with_limits(&self, limits: &StructureLimits) -> Result<(), String> {
+        match self.validate_detailed_with_limits(limits).errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Collect every validation error and warning instead of stopping at the first
+    pub fn validate_detailed(&self) -> ValidationOutcome {
+        self.validate_detailed_with_limits(&StructureLimits::default())
+    }
+
+    /// `validate_detailed` with custom directory structure limits
+    pub fn validate_detailed_with_limits(&self, limits: &StructureLimits) -> ValidationOutcome {
+        let mut outcome = ValidationOutcome::default();
+
         // Check required fields
         if self.project_name.is_empty() {
-            return Err("Project name is required".to_string());
+            outcome.errors.push("Project name is required".to_string());
+        } else if !is_valid_project_name(&self.project_name) {
+            outcome.errors.push("Invalid project name format".to_string());
         }
-        if !is_valid_project_name(&self.project_name) {
-            return Err("Invalid project name format".to_string());
+        if self.language.is_empty() {
+            outcome.errors.push("Programming language is required".to_string());
         }
         if self.description.is_empty() {
-            return Err("Project description is required".to_string());
-        }
-        if self.language.is_empty() {
-            return Err("Programming language is required".to_string());
+            outcome.warnings.push("Project description is missing".to_string());
         }
         if self.framework.is_empty() {
-            return Err("Framework is required".to_string());
+            outcome.errors.push("Framework is required".to_string());
         }
 
         // Check directory structure
         for (dir, _) in &self.directory_structure {
             if dir.is_empty() {
-                return Err("Directory name cannot be empty".to_string());
-            }
-            if dir.contains('/') || dir.contains('\\') {
-                return Err("Directory name cannot contain path separators".to_string());
+                outcome.errors.push("Directory name cannot be empty".to_string());
+            } else if dir.contains('/') || dir.contains('\\') {
+                outcome.errors.push(format!("Directory name '{}' cannot contain path separators", dir));
             }
         }
+        outcome.errors.extend(self.structure_limit_errors(limits));
+        if !self.directory_structure.keys().any(|dir| TEST_DIRECTORIES.contains(&dir.as_str())) {
+            outcome.warnings.push("No tests directory in the directory structure".to_string());
+        }
 
-        Ok(())
+        // Unpinned versions make generated projects irreproducible
+        let mut unpinned: Vec<&String> = self
+            .dependencies
+            .production
+            .iter()
+            .chain(self.dependencies.development.iter())
+            .filter(|(_, version)| matches!(version.trim(), "latest" | "*" | ""))
+            .map(|(name, _)| name)
+            .collect();
+        unpinned.sort();
+        for name in unpinned {
+            outcome.warnings.push(format!("Dependency '{}' is not pinned to a version", name));
+        }
+
+        outcome
+    }
+
+    /// Errors for every way the directory structure exceeds `limits`, in directory order
+    fn structure_limit_errors(&self, limits: &StructureLimits) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.directory_structure.len() > limits.max_dirs {
+            errors.push(format!(
+                "Directory structure declares {} directories; the limit is {}",
+                self.directory_structure.len(),
+                limits.max_dirs
+            ));
+        }
+
+        let mut dirs: Vec<_> = self.directory_structure.iter().collect();
+        dirs.sort_by_key(|(a, _)| *a);
+        for (dir, entry) in dirs {
+            let files = entry.to_vec();
+            if files.len() > limits.max_files_per_dir {
+                errors.push(format!(
+                    "Directory '{}' lists {} files; the limit is {}",
+                    dir,
+                    files.len(),
+                    limits.max_files_per_dir
+                ));
+            }
+            let deepest = files
+                .iter()
+                .map(|file| 1 + file.split(['/', '\\']).filter(|part| !part.is_empty()).count())
+                .max()
+                .unwrap_or(1);
+            if deepest > limits.max_path_depth {
+                errors.push(format!(
+                    "Directory '{}' nests files {} levels deep; the limit is {}",
+                    dir, deepest, limits.max_path_depth
+                ));
+            }
+        }
+        errors
     }
 
     /// Add a production dependency
@@ -219,6 +399,16 @@ impl ProjectGenerationConfig {
         Ok(())
     }
 
+    /// Append a named build script; re-adding an existing name replaces its command in place
+    pub fn add_script(&mut self, name: &str, command: &str) -> Result<(), String> {
+        if name.is_empty() || command.is_empty() {
+            return Err("Script name and command cannot be empty".to_string());
+        }
+
+        self.build_config.scripts.insert(name.to_string(), command.to_string());
+        Ok(())
+    }
+
     /// Add initialization command
     pub fn add_initialization_command(&mut self, command: &str) {
         self.initialization_commands.push(command.to_string());
@@ -256,6 +446,249 @@ impl ProjectGenerationConfig {
         Ok(())
     }
 
+    /// Human-readable summary for a pull request description: description, tech stack,
+    /// a dependency table, the directory tree and recommendations. Maps are rendered in
+    /// sorted order so the summary is stable.
+    pub fn to_markdown_summary(&self) -> String {
+        let mut md = format!("# {}\n\n", self.project_name);
+        if !self.description.is_empty() {
+            md.push_str(&format!("{}\n\n", self.description));
+        }
+
+        md.push_str("## Tech stack\n\n");
+        md.push_str(&format!("- **Language:** {}\n", self.language));
+        if !self.framework.is_empty() {
+            md.push_str(&format!("- **Framework:** {}\n", self.framework));
+        }
+        md.push_str(&format!("- **Project type:** {}\n", self.project_type));
+        if !self.build_config.build_tool.is_empty() {
+            md.push_str(&format!("- **Build tool:** {}\n", self.build_config.build_tool));
+        }
+        if !self.technologies.is_empty() {
+            md.push_str(&format!("- **Technologies:** {}\n", self.technologies.join(", ")));
+        }
+
+        md.push_str("\n## Dependencies\n\n");
+        let mut dependencies: Vec<(&String, &String, &str)> = self
+            .dependencies
+            .production
+            .iter()
+            .map(|(name, version)| (name, version, "production"))
+            .chain(self.dependencies.development.iter().map(|(name, version)| (name, version, "development")))
+            .collect();
+        if dependencies.is_empty() {
+            md.push_str("None\n");
+        } else {
+            dependencies.sort_by(|a, b| b.2.cmp(a.2).then_with(|| a.0.cmp(b.0)));
+            md.push_str("| Package | Version | Scope |\n|---|---|---|\n");
+            for (name, version, scope) in dependencies {
+                md.push_str(&format!("| {} | {} | {} |\n", name, version, scope));
+            }
+        }
+
+        if !self.directory_structure.is_empty() {
+            md.push_str("\n## Directory structure\n\n```text\n");
+            md.push_str(&format!("{}/\n", self.project_name));
+            let mut dirs: Vec<_> = self.directory_structure.iter().collect();
+            dirs.sort_by_key(|(a, _)| *a);
+            let last_dir = dirs.len() - 1;
+            for (i, (dir, entry)) in dirs.into_iter().enumerate() {
+                let (branch, indent) = if i == last_dir { ("└── ", "    ") } else { ("├── ", "│   ") };
+                md.push_str(&format!("{}{}/\n", branch, dir.trim_end_matches('/')));
+                let files = entry.to_vec();
+                for (j, file) in files.iter().enumerate() {
+                    let leaf = if j + 1 == files.len() { "└── " } else { "├── " };
+                    md.push_str(&format!("{}{}{}\n", indent, leaf, file));
+                }
+            }
+            md.push_str("```\n");
+        }
+
+        if !self.recommendations.is_empty() {
+            md.push_str("\n## Recommendations\n\n");
+            for recommendation in &self.recommendations {
+                md.push_str(&format!("- {}\n", recommendation));
+            }
+        }
+        md
+    }
+
+    /// Layer `overrides` on top of this config. Non-empty scalar fields of `overrides`
+    /// replace ours, maps are unioned with `overrides` winning on conflicting keys, lists
+    /// are appended without duplicates, and directories present in both keep the files
+    /// of each.
+    pub fn merge(&self, overrides: &Self) -> Self {
+        let mut merged = self.clone();
+
+        for (field, value) in [
+            (&mut merged.project_name, &overrides.project_name),
+            (&mut merged.description, &overrides.description),
+            (&mut merged.language, &overrides.language),
+            (&mut merged.framework, &overrides.framework),
+            (&mut merged.build_config.build_tool, &overrides.build_config.build_tool),
+        ] {
+            if !value.is_empty() {
+                field.clone_from(value);
+            }
+        }
+        merged.project_type = overrides.project_type.clone();
+
+        for (name, version) in &overrides.dependencies.production {
+            merged.dependencies.production.insert(name.clone(), version.clone());
+        }
+        for (name, version) in &overrides.dependencies.development {
+            merged.dependencies.development.insert(name.clone(), version.clone());
+        }
+        for (name, responsibility) in &overrides.components {
+            merged.components.insert(name.clone(), responsibility.clone());
+        }
+        for (name, command) in &overrides.build_config.scripts {
+            merged.build_config.scripts.insert(name.clone(), command.clone());
+        }
+
+        for (dir, entry) in &overrides.directory_structure {
+            let files = match merged.directory_structure.get(dir) {
+                Some(existing) => union(existing.to_vec(), &entry.to_vec()),
+                None => entry.to_vec(),
+            };
+            merged.directory_structure.insert(dir.clone(), DirectoryEntry::Files(files));
+        }
+
+        merged.technologies = union(merged.technologies, &overrides.technologies);
+        merged.initialization_commands = union(merged.initialization_commands, &overrides.initialization_commands);
+        merged.recommendations = union(merged.recommendations, &overrides.recommendations);
+        merged
+    }
+
+    /// Infer a configuration from an existing project: the language from its manifest,
+    /// dependencies from `Cargo.toml`, `package.json` or `requirements.txt`, and the
+    /// directory structure from its top-level directories. Direct files are listed by
+    /// name and nested directories as `name/`.
+    pub fn from_existing(dir: &Path) -> Result<Self> {
+        if !dir.is_dir() {
+            return Err(anyhow!("Not a directory: {}", dir.display()));
+        }
+
+        let dir_name = dir
+            .canonicalize()
+            .ok()
+            .and_then(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "existing-project".to_string());
+        let mut config = Self {
+            project_name: to_project_name(&dir_name),
+            description: String::new(),
+            language: String::new(),
+            framework: String::new(),
+            project_type: GenerationProjectType::Application,
+            technologies: Vec::new(),
+            components: HashMap::new(),
+            directory_structure: HashMap::new(),
+            dependencies: GenerationDependencyConfig::new(),
+            build_config: GenerationBuildConfig::default(),
+            initialization_commands: Vec::new(),
+            recommendations: Vec::new(),
+        };
+
+        if dir.join("Cargo.toml").is_file() {
+            config.read_cargo_manifest(&dir.join("Cargo.toml"))?;
+            config.project_type = if dir.join("src/main.rs").is_file() {
+                GenerationProjectType::Application
+            } else if dir.join("src/lib.rs").is_file() {
+                GenerationProjectType::Library
+            } else {
+                GenerationProjectType::Application
+            };
+        } else if dir.join("package.json").is_file() {
+            config.read_package_json(&dir.join("package.json"))?;
+            config.language = if dir.join("tsconfig.json").is_file() { "TypeScript" } else { "JavaScript" }.to_string();
+        } else if dir.join("requirements.txt").is_file() || dir.join("pyproject.toml").is_file() {
+            config.language = "Python".to_string();
+            config.build_config.build_tool = "pip".to_string();
+            for (file, target) in [("requirements.txt", false), ("dev-requirements.txt", true)] {
+                let path = dir.join(file);
+                if path.is_file() {
+                    let deps = read_requirements(&path)?;
+                    let target = if target { &mut config.dependencies.development } else { &mut config.dependencies.production };
+                    target.extend(deps);
+                }
+            }
+        } else if dir.join("go.mod").is_file() {
+            config.language = "Go".to_string();
+            config.build_config.build_tool = "go".to_string();
+        } else if dir.join("pom.xml").is_file() || dir.join("build.gradle").is_file() {
+            config.language = "Java".to_string();
+            config.build_config.build_tool = if dir.join("pom.xml").is_file() { "maven" } else { "gradle" }.to_string();
+        } else {
+            return Err(anyhow!("Could not detect the project language in {}", dir.display()));
+        }
+
+        config.directory_structure = scan_directory_structure(dir)?;
+        Ok(config)
+    }
+
+    fn read_cargo_manifest(&mut self, path: &Path) -> Result<()> {
+        let manifest: toml::Value = toml::from_str(&fs::read_to_string(path)?)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        self.language = "Rust".to_string();
+        self.build_config.build_tool = "cargo".to_string();
+        if let Some(name) = manifest.get("package").and_then(|p| p.get("name")).and_then(|n| n.as_str()) {
+            self.project_name = to_project_name(name);
+        }
+        if let Some(description) = manifest.get("package").and_then(|p| p.get("description")).and_then(|d| d.as_str()) {
+            self.description = description.to_string();
+        }
+
+        for (section, target) in [
+            ("dependencies", &mut self.dependencies.production),
+            ("dev-dependencies", &mut self.dependencies.development),
+        ] {
+            if let Some(deps) = manifest.get(section).and_then(|d| d.as_table()) {
+                for (name, spec) in deps {
+                    // Either `name = "1.0"` or `name = { version = "1.0", ... }`; path and
+                    // git dependencies have no version
+                    let version = spec
+                        .as_str()
+                        .or_else(|| spec.get("version").and_then(|v| v.as_str()))
+                        .unwrap_or("*");
+                    target.insert(name.clone(), version.to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn read_package_json(&mut self, path: &Path) -> Result<()> {
+        let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        self.build_config.build_tool = "npm".to_string();
+        if let Some(name) = manifest["name"].as_str() {
+            self.project_name = to_project_name(name);
+        }
+        if let Some(description) = manifest["description"].as_str() {
+            self.description = description.to_string();
+        }
+        for (section, target) in [
+            ("dependencies", &mut self.dependencies.production),
+            ("devDependencies", &mut self.dependencies.development),
+        ] {
+            if let Some(deps) = manifest[section].as_object() {
+                for (name, version) in deps {
+                    target.insert(name.clone(), version.as_str().unwrap_or("*").to_string());
+                }
+            }
+        }
+        if let Some(scripts) = manifest["scripts"].as_object() {
+            for (name, command) in scripts {
+                if let Some(command) = command.as_str() {
+                    self.build_config.scripts.insert(name.clone(), command.to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Generate a sample project configuration for testing
     pub fn sample_web_project() -> Self {
         let mut config = Self::new(
@@ -292,6 +725,84 @@ impl ProjectGenerationConfig {
     }
 }
 
+/// Best-effort kebab-case form of a package or directory name
+fn to_project_name(name: &str) -> String {
+    // Scoped npm packages (`@scope/name`) keep only the package name
+    let name = name.rsplit('/').next().unwrap_or(name);
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect::<String>()
+        .trim_matches('-')
+        .to_string()
+}
+
+/// Dependencies from a requirements file; unpinned entries get version `*`
+fn read_requirements(path: &Path) -> Result<HashMap<String, String>> {
+    let mut deps = HashMap::new();
+    for line in fs::read_to_string(path)?.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() || line.starts_with('-') {
+            continue;
+        }
+        let split = line.find(|c: char| "=<>!~;[ ".contains(c)).unwrap_or(line.len());
+        let (name, rest) = line.split_at(split);
+        // Keep the whole specifier (e.g. `>=2.28` or `~=1.4,<2`) minus environment
+        // markers; an exact `==` pin is stored as the bare version
+        let specifier: String = rest.split(';').next().unwrap_or("").chars().filter(|c| !c.is_whitespace()).collect();
+        let version = if specifier.is_empty() || rest.starts_with('[') {
+            "*".to_string()
+        } else {
+            match specifier.strip_prefix("==") {
+                Some(exact) if !exact.starts_with('=') && !exact.contains(',') => exact.to_string(),
+                _ => specifier,
+            }
+        };
+        deps.insert(name.to_string(), version);
+    }
+    Ok(deps)
+}
+
+/// Directories skipped when scanning an existing project
+const IGNORED_DIRECTORIES: &[&str] = &["target", "node_modules", "__pycache__", "venv", "dist", "build"];
+
+fn scan_directory_structure(root: &Path) -> Result<HashMap<String, DirectoryEntry>> {
+    let mut structure = HashMap::new();
+    for entry in fs::read_dir(root)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !entry.file_type()?.is_dir() || name.starts_with('.') || IGNORED_DIRECTORIES.contains(&name.as_str()) {
+            continue;
+        }
+
+        let mut files = Vec::new();
+        for child in fs::read_dir(entry.path())? {
+            let child = child?;
+            let child_name = child.file_name().to_string_lossy().into_owned();
+            if child_name.starts_with('.') {
+                continue;
+            }
+            if child.file_type()?.is_dir() {
+                files.push(format!("{}/", child_name));
+            } else {
+                files.push(child_name);
+            }
+        }
+        files.sort();
+        structure.insert(name, DirectoryEntry::Files(files));
+    }
+    Ok(structure)
+}
+
+/// `base` followed by the items of `extra` it does not already contain
+fn union(mut base: Vec<String>, extra: &[String]) -> Vec<String> {
+    for item in extra {
+        if !base.contains(item) {
+            base.push(item.clone());
+        }
+    }
+    base
+}
+
 fn is_valid_project_name(name: &str) -> bool {
     if name.is_empty() {
         return false;
@@ -321,4 +832,207 @@ mod tests {
         assert!(config.components.is_empty());
         assert!(config.directory_structure.is_empty());
     }
+
+    #[test]
+    fn test_markdown_summary() {
+        let mut config = ProjectGenerationConfig::sample_web_project();
+        config.directory_structure.insert(
+            "src".to_string(),
+            DirectoryEntry::Files(vec!["app.py".to_string(), "models.py".to_string()]),
+        );
+        let summary = config.to_markdown_summary();
+
+        assert!(summary.starts_with("# sample-web-app\n"));
+        assert!(summary.contains("| flask | 2.0.1 | production |"));
+        assert!(summary.contains("| pytest | 6.2.5 | development |"));
+        assert!(summary.contains("└── src/\n    ├── app.py\n    └── models.py\n"));
+        for recommendation in &config.recommendations {
+            assert!(summary.contains(&format!("- {}\n", recommendation)));
+        }
+    }
+
+    #[test]
+    fn test_merge_layers_overrides_on_base() {
+        let mut base = ProjectGenerationConfig::sample_web_project();
+        base.framework = "Flask".to_string();
+        base.directory_structure.insert("src".to_string(), DirectoryEntry::Files(vec!["main.py".to_string()]));
+        base.add_production_dependency("requests", "2.31.0");
+
+        let mut overrides = base.clone();
+        overrides.project_name = String::new();
+        overrides.description = String::new();
+        overrides.framework = "FastAPI".to_string();
+        overrides.dependencies = GenerationDependencyConfig::new();
+        overrides.add_production_dependency("fastapi", "0.110.0");
+        overrides.add_production_dependency("requests", "2.32.0");
+        overrides.directory_structure = HashMap::from([
+            ("src".to_string(), DirectoryEntry::File("api.py".to_string())),
+            ("docs".to_string(), DirectoryEntry::Files(vec!["index.md".to_string()])),
+        ]);
+
+        let merged = base.merge(&overrides);
+        assert_eq!(merged.project_name, base.project_name);
+        assert_eq!(merged.description, base.description);
+        assert_eq!(merged.framework, "FastAPI");
+        assert_eq!(merged.dependencies.production["fastapi"], "0.110.0");
+        assert_eq!(merged.dependencies.production["requests"], "2.32.0");
+        for name in base.dependencies.production.keys() {
+            assert!(merged.dependencies.production.contains_key(name));
+        }
+        assert_eq!(merged.directory_structure["src"].to_vec(), vec!["main.py", "api.py"]);
+        assert_eq!(merged.directory_structure["docs"].to_vec(), vec!["index.md"]);
+        assert_eq!(merged.technologies, base.technologies);
+    }
+
+    #[test]
+    fn test_recommended_dependencies_rust_web_app() {
+        let deps = GenerationProjectType::WebApplication.recommended_dependencies("Rust");
+        assert!(deps.contains_key("axum"));
+        assert!(deps.contains_key("tokio"));
+        assert!(deps.contains_key("serde"));
+    }
+
+    #[test]
+    fn test_recommended_dependencies_cli() {
+        let deps = GenerationProjectType::CommandLineInterface.recommended_dependencies("rust");
+        assert!(deps.contains_key("clap"));
+        assert!(GenerationProjectType::MobileApplication.recommended_dependencies("rust").is_empty());
+    }
+
+    #[test]
+    fn test_add_script_preserves_order() {
+        let mut config = ProjectGenerationConfig::new(
+            "web-app".to_string(),
+            "A web app".to_string(),
+            "JavaScript".to_string(),
+            "Express".to_string(),
+            GenerationProjectType::WebApplication,
+        ).unwrap();
+
+        config.set_build_scripts("node src/app.js", "tsc", "jest").unwrap();
+        config.add_script("lint", "eslint .").unwrap();
+        assert!(config.add_script("", "noop").is_err());
+
+        let names: Vec<&String> = config.build_config.scripts.keys().collect();
+        assert_eq!(names, ["dev", "build", "test", "lint"]);
+    }
+
+    #[test]
+    fn test_from_existing_rust_project() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src/bin"))?;
+        fs::create_dir_all(root.join("tests"))?;
+        fs::create_dir_all(root.join("target/debug"))?;
+        fs::write(root.join("Cargo.toml"), r#"[package]
+name = "inventory_tool"
+description = "Tracks inventory"
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+anyhow = "1.0"
+local = { path = "../local" }
+
+[dev-dependencies]
+tempfile = "3"
+"#)?;
+        fs::write(root.join("src/main.rs"), "fn main() {}")?;
+        fs::write(root.join("tests/cli.rs"), "")?;
+
+        let config = ProjectGenerationConfig::from_existing(root)?;
+        assert_eq!(config.language, "Rust");
+        assert_eq!(config.project_name, "inventory-tool");
+        assert_eq!(config.description, "Tracks inventory");
+        assert_eq!(config.dependencies.production["serde"], "1.0");
+        assert_eq!(config.dependencies.production["local"], "*");
+        assert_eq!(config.dependencies.development["tempfile"], "3");
+        assert_eq!(config.directory_structure["src"].to_vec(), ["bin/", "main.rs"]);
+        assert!(config.directory_structure.contains_key("tests"));
+        assert!(!config.directory_structure.contains_key("target"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_existing_python_project() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        fs::write(
+            temp_dir.path().join("requirements.txt"),
+            "flask==2.0.1\nrequests>=2.28 # http\nclick\nnumpy ~= 1.24\nurllib3>=1.26,<3 ; python_version >= '3.8'\n",
+        )?;
+
+        let config = ProjectGenerationConfig::from_existing(temp_dir.path())?;
+        assert_eq!(config.language, "Python");
+        assert_eq!(config.dependencies.production["flask"], "2.0.1");
+        assert_eq!(config.dependencies.production["requests"], ">=2.28");
+        assert_eq!(config.dependencies.production["numpy"], "~=1.24");
+        assert_eq!(config.dependencies.production["urllib3"], ">=1.26,<3");
+        assert_eq!(config.dependencies.production["click"], "*");
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_schema_accepts_sample_project() -> Result<()> {
+        let validator = crate::prompt::storage::SchemaValidator::new(&ProjectGenerationConfig::json_schema())?;
+        let sample = serde_json::to_value(ProjectGenerationConfig::sample_web_project())?;
+        assert!(validator.errors(&sample).is_empty());
+        assert!(!validator.errors(&serde_json::json!({ "project_name": "no-language" })).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_description_is_a_warning() {
+        let mut config = ProjectGenerationConfig::new(
+            "no-description".to_string(),
+            String::new(),
+            "Rust".to_string(),
+            "axum".to_string(),
+            GenerationProjectType::WebApplication,
+        ).unwrap();
+        config.directory_structure.insert("tests".to_string(), DirectoryEntry::File("api.rs".to_string()));
+
+        let outcome = config.validate_detailed();
+        assert!(outcome.errors.is_empty());
+        assert_eq!(outcome.warnings, ["Project description is missing"]);
+        assert!(config.validate().is_ok());
+        assert!(outcome.passes(false));
+        assert!(!outcome.passes(true));
+    }
+
+    #[test]
+    fn test_structure_limits() {
+        let mut config = ProjectGenerationConfig::sample_web_project();
+        config.directory_structure.insert(
+            "src".to_string(),
+            DirectoryEntry::Files((0..5).map(|n| format!("module_{}.py", n)).collect()),
+        );
+        config.directory_structure.insert("tests".to_string(), DirectoryEntry::File("unit/api/test_app.py".to_string()));
+        assert!(config.validate().is_ok());
+
+        let limits = StructureLimits { max_files_per_dir: 4, ..Default::default() };
+        assert_eq!(
+            config.validate_with_limits(&limits),
+            Err("Directory 'src' lists 5 files; the limit is 4".to_string())
+        );
+
+        let limits = StructureLimits { max_dirs: 1, max_path_depth: 3, ..Default::default() };
+        let outcome = config.validate_detailed_with_limits(&limits);
+        assert_eq!(outcome.errors, [
+            "Directory structure declares 2 directories; the limit is 1",
+            "Directory 'tests' nests files 4 levels deep; the limit is 3",
+        ]);
+    }
+
+    #[test]
+    fn test_validate_detailed_collects_all_issues() {
+        let mut config = ProjectGenerationConfig::sample_web_project();
+        config.project_name = "Not Kebab".to_string();
+        config.directory_structure.insert("src/app".to_string(), DirectoryEntry::File("main.py".to_string()));
+        config.add_production_dependency("requests", "latest");
+
+        let outcome = config.validate_detailed();
+        assert_eq!(outcome.errors.len(), 2);
+        assert!(outcome.warnings.contains(&"No tests directory in the directory structure".to_string()));
+        assert!(outcome.warnings.contains(&"Dependency 'requests' is not pinned to a version".to_string()));
+        assert_eq!(config.validate(), Err("Invalid project name format".to_string()));
+    }
 }
@@ -1,10 +1,69 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use sled::Db;
+use sled::transaction::{ConflictableTransactionError, ConflictableTransactionResult, TransactionError, TransactionalTree};
 use uuid::Uuid;
 use jsonschema::JSONSchema;
 use serde_json::Value;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
 use std::path::Path;
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+
+use crate::prompt::error::StorageError;
+
+/// Leading bytes of a file written by `Storage::backup_to`
+const BACKUP_MAGIC: &[u8] = b"BSBACKUP1";
+
+/// Length prefix that ends a backup; it is followed by the `u64` entry count
+const BACKUP_END_MARKER: u32 = u32::MAX;
+
+/// Largest key or value a backup may hold, so a corrupt length can't force a huge allocation
+const MAX_BACKUP_CHUNK_LEN: usize = 64 * 1024 * 1024;
+
+/// Timestamp format encoded as the suffix of time-keyed entries (e.g. `build_validation_<timestamp>`)
+pub const KEY_TIMESTAMP_FORMAT: &str = "%Y%m%d_%H%M%S";
+
+#[cfg(test)]
+thread_local! {
+    /// Schemas compiled by `SchemaValidator::new` on this thread; thread-local so tests
+    /// running in parallel don't see each other's compilations
+    static SCHEMA_COMPILATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// A JSON schema compiled once and reused for any number of validations
+pub struct SchemaValidator {
+    compiled: JSONSchema,
+}
+
+impl SchemaValidator {
+    pub fn new(schema: &Value) -> Result<Self> {
+        // Compile errors borrow the schema, so render them before returning
+        let compiled = JSONSchema::compile(schema)
+            .map_err(|e| anyhow::anyhow!("Failed to compile JSON schema: {}", e))?;
+        #[cfg(test)]
+        SCHEMA_COMPILATIONS.with(|count| count.set(count.get() + 1));
+        Ok(Self { compiled })
+    }
+
+    /// Validate `data` against the compiled schema, collecting every validation error
+    pub fn validate(&self, data: &Value) -> Result<()> {
+        let error_messages = self.errors(data);
+        if !error_messages.is_empty() {
+            anyhow::bail!("JSON validation failed: {}", error_messages.join(", "));
+        }
+
+        Ok(())
+    }
+
+    /// Every validation error for `data`, rendered as messages; empty when it validates
+    pub fn errors(&self, data: &Value) -> Vec<String> {
+        match self.compiled.validate(data) {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.map(|error| error.to_string()).collect(),
+        }
+    }
+}
 
 /// Manages persistent storage and validation for prompts and workflows
 pub struct PromptStorage {
@@ -13,26 +72,15 @@ pub struct PromptStorage {
 
 impl PromptStorage {
     /// Create a new PromptStorage instance
-    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let db = sled::open(path)?;
+    pub fn new<P: AsRef<Path>>(path: P) -> std::result::Result<Self, StorageError> {
+        let db = open_db(path.as_ref())?;
         Ok(Self { db })
     }
 
-    /// Validate JSON against a given schema
+    /// Validate JSON against a given schema. Compiles the schema on every call; use a
+    /// `SchemaValidator` when validating many documents against the same schema.
     pub fn validate_json(schema: &Value, data: &Value) -> Result<()> {
-        // Create a 'static reference by leaking the schema
-        let schema_static = Box::leak(Box::new(schema.clone()));
-        let compiled_schema = JSONSchema::compile(schema_static)?;
-        
-        // Validate the data against the schema and collect any validation errors
-        if let Err(errors) = compiled_schema.validate(data) {
-            let error_messages: Vec<String> = errors
-                .map(|error| error.to_string())
-                .collect();
-            anyhow::bail!("JSON validation failed: {}", error_messages.join(", "));
-        }
-        
-        Ok(())
+        SchemaValidator::new(schema)?.validate(data)
     }
 
     /// Store a serializable item with a UUID
@@ -54,26 +102,33 @@ impl PromptStorage {
             .context("Failed to deserialize item")
     }
 
-    /// List all items of a specific type
+    /// List all items of a specific type, skipping any that fail to load
     pub fn list<T: for<'de> Deserialize<'de>>(&self, key: &str) -> Result<Vec<(Uuid, T)>> {
+        Ok(self.list_iter(key).filter_map(Result::ok).collect())
+    }
+
+    /// Lazily iterate over the items of a specific type; each item is only read and
+    /// deserialized when the iterator reaches it. Keys under the prefix that do not end
+    /// in a UUID are skipped.
+    pub fn list_iter<T: for<'de> Deserialize<'de>>(&self, key: &str) -> impl Iterator<Item = Result<(Uuid, T)>> + '_ {
         let prefix = format!("{}-", key);
-        let items = self.db.scan_prefix(prefix.as_bytes())
-            .filter_map(|res| {
-                res.ok().and_then(|(k, v)| {
-                    // Extract UUID from key
-                    let uuid_str = std::str::from_utf8(&k)
-                        .ok()?
-                        .strip_prefix(&prefix)?;
-                    let uuid = Uuid::parse_str(uuid_str).ok()?;
-                    
-                    // Deserialize value
-                    serde_json::from_slice(&v).ok()
-                        .map(|item| (uuid, item))
-                })
-            })
-            .collect();
-
-        Ok(items)
+        self.db.scan_prefix(prefix.as_bytes()).filter_map(move |res| {
+            let (k, v) = match res {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(e.into())),
+            };
+            // Extract UUID from key
+            let uuid = std::str::from_utf8(&k)
+                .ok()?
+                .strip_prefix(&prefix)
+                .and_then(|uuid_str| Uuid::parse_str(uuid_str).ok())?;
+
+            Some(
+                serde_json::from_slice(&v)
+                    .map(|item| (uuid, item))
+                    .with_context(|| format!("Failed to deserialize item {}{}", prefix, uuid)),
+            )
+        })
     }
 
     /// Delete an item by its UUID
@@ -97,8 +152,8 @@ pub struct Storage {
 }
 
 impl Storage {
-    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let db = sled::open(path)?;
+    pub fn new<P: AsRef<Path>>(path: P) -> std::result::Result<Self, StorageError> {
+        let db = open_db(path.as_ref())?;
         Ok(Self { db })
     }
 
@@ -135,11 +190,225 @@ impl Storage {
         Ok(keys)
     }
 
+    /// List keys starting with `prefix` whose timestamp suffix falls within `[from, to]`.
+    /// Keys whose suffix does not parse as a timestamp are skipped.
+    pub fn list_keys_in_range(
+        &self,
+        prefix: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        for res in self.db.scan_prefix(prefix.as_bytes()) {
+            let (key, _) = res?;
+            let key_str = match String::from_utf8(key.to_vec()) {
+                Ok(key_str) => key_str,
+                Err(_) => continue,
+            };
+
+            if let Some(timestamp) = key_timestamp(&key_str, prefix) {
+                if timestamp >= from && timestamp <= to {
+                    keys.push(key_str);
+                }
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Remove every key matching `predicate`, returning how many were removed
+    pub fn prune(&self, predicate: impl Fn(&str) -> bool) -> Result<usize> {
+        let mut removed = 0;
+        for key in self.list_keys()? {
+            if predicate(&key) && self.db.remove(key.as_bytes())?.is_some() {
+                removed += 1;
+            }
+        }
+        self.db.flush()?;
+        Ok(removed)
+    }
+
+    /// Remove timestamp-suffixed keys under `prefix` that are older than `cutoff`
+    pub fn prune_older_than(&self, prefix: &str, cutoff: DateTime<Utc>) -> Result<usize> {
+        self.prune(|key| matches!(key_timestamp(key, prefix), Some(timestamp) if timestamp < cutoff))
+    }
+
     pub fn clear(&self) -> Result<()> {
         self.db.clear()?;
         self.db.flush()?;
         Ok(())
     }
+
+    /// Write every key/value pair to a single gzip-compressed file at `path`, returning
+    /// how many entries were written. Each entry is stored as a little-endian `u32`
+    /// length followed by the bytes, key first; `BACKUP_END_MARKER` and the entry count
+    /// close the file so a truncated backup can be told apart from a complete one.
+    pub fn backup_to(&self, path: &Path) -> Result<usize> {
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create backup file {}", path.display()))?;
+        let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+        encoder.write_all(BACKUP_MAGIC)?;
+
+        let mut entries = 0;
+        for res in self.db.iter() {
+            let (key, value) = res?;
+            write_chunk(&mut encoder, &key)?;
+            write_chunk(&mut encoder, &value)?;
+            entries += 1;
+        }
+        encoder.write_all(&BACKUP_END_MARKER.to_le_bytes())?;
+        encoder.write_all(&(entries as u64).to_le_bytes())?;
+        encoder.finish()?.flush()?;
+        Ok(entries)
+    }
+
+    /// Load the entries of a backup written by `backup_to`, returning how many were
+    /// restored. With `clear_first` every existing key is removed beforehand; otherwise
+    /// restored entries overwrite keys they share with the current contents. Nothing is
+    /// written unless the whole backup reads cleanly, and the clear and the restore are
+    /// applied as one atomic batch.
+    pub fn restore_from(&self, path: &Path, clear_first: bool) -> Result<usize> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open backup file {}", path.display()))?;
+        let mut decoder = GzDecoder::new(BufReader::new(file));
+
+        let mut magic = [0u8; BACKUP_MAGIC.len()];
+        decoder.read_exact(&mut magic).context("Failed to read backup header")?;
+        if magic != BACKUP_MAGIC {
+            anyhow::bail!("{} is not a storage backup", path.display());
+        }
+
+        let mut restored = Vec::new();
+        while let Some(key) = read_chunk(&mut decoder)? {
+            let value = read_chunk(&mut decoder)?
+                .with_context(|| format!("Backup is truncated after entry {}", restored.len()))?;
+            restored.push((key, value));
+        }
+        let mut count = [0u8; 8];
+        decoder.read_exact(&mut count).context("Backup is truncated in its entry count")?;
+        let expected = u64::from_le_bytes(count);
+        if expected != restored.len() as u64 {
+            anyhow::bail!("Backup holds {} entries but records {}", restored.len(), expected);
+        }
+
+        // Later writes to a key win within a batch, so restored entries replace the removals
+        let mut batch = sled::Batch::default();
+        if clear_first {
+            for key in self.db.iter().keys() {
+                batch.remove(key?);
+            }
+        }
+        let entries = restored.len();
+        for (key, value) in restored {
+            batch.insert(key, value);
+        }
+        self.db.apply_batch(batch)?;
+        self.db.flush()?;
+        Ok(entries)
+    }
+
+    /// Apply several writes atomically: either every write made by `f` is committed and
+    /// flushed, or (when `f` returns an error) none are. sled may re-run `f` on conflict,
+    /// so it should not have side effects outside the transaction.
+    pub fn transaction<R>(&self, f: impl Fn(&StorageTransaction<'_>) -> TxResult<R>) -> Result<R> {
+        let value = self
+            .db
+            .transaction(|tree| f(&StorageTransaction { tree }))
+            .map_err(|e| match e {
+                TransactionError::Abort(e) => e,
+                TransactionError::Storage(e) => anyhow::Error::from(e).context("Storage transaction failed"),
+            })?;
+        self.db.flush()?;
+        Ok(value)
+    }
+}
+
+fn write_chunk(writer: &mut impl Write, bytes: &[u8]) -> Result<()> {
+    if bytes.len() > MAX_BACKUP_CHUNK_LEN {
+        anyhow::bail!("Entry of {} bytes is too large to back up", bytes.len());
+    }
+    writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    writer.write_all(bytes)?;
+    Ok(())
+}
+
+/// Read one length-prefixed chunk, or `None` at `BACKUP_END_MARKER`
+fn read_chunk(reader: &mut impl Read) -> Result<Option<Vec<u8>>> {
+    let mut len = [0u8; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => anyhow::bail!("Backup is truncated before its end marker"),
+        Err(e) => return Err(e.into()),
+    }
+    let len = u32::from_le_bytes(len);
+    if len == BACKUP_END_MARKER {
+        return Ok(None);
+    }
+    if len as usize > MAX_BACKUP_CHUNK_LEN {
+        anyhow::bail!("Backup entry length {} exceeds the {} byte limit", len, MAX_BACKUP_CHUNK_LEN);
+    }
+    let mut bytes = vec![0u8; len as usize];
+    reader.read_exact(&mut bytes).context("Backup is truncated")?;
+    Ok(Some(bytes))
+}
+
+/// Result of a step inside `Storage::transaction`; an error aborts the whole transaction
+pub type TxResult<T> = ConflictableTransactionResult<T, anyhow::Error>;
+
+/// Abort the enclosing `Storage::transaction` with `error`
+pub fn abort<T>(error: impl Into<anyhow::Error>) -> TxResult<T> {
+    Err(ConflictableTransactionError::Abort(error.into()))
+}
+
+/// Typed access to the keys of a `Storage` inside a transaction
+pub struct StorageTransaction<'a> {
+    tree: &'a TransactionalTree,
+}
+
+impl StorageTransaction<'_> {
+    pub fn store<T: Serialize>(&self, key: &str, value: &T) -> TxResult<()> {
+        let serialized = serde_json::to_vec(value).map_err(|e| ConflictableTransactionError::Abort(e.into()))?;
+        self.tree.insert(key.as_bytes(), serialized)?;
+        Ok(())
+    }
+
+    pub fn load<T: DeserializeOwned>(&self, key: &str) -> TxResult<Option<T>> {
+        match self.tree.get(key.as_bytes())? {
+            Some(data) => serde_json::from_slice(&data)
+                .map(Some)
+                .map_err(|e| ConflictableTransactionError::Abort(e.into())),
+            None => Ok(None),
+        }
+    }
+
+    pub fn delete(&self, key: &str) -> TxResult<()> {
+        self.tree.remove(key.as_bytes())?;
+        Ok(())
+    }
+
+    /// The underlying sled transactional tree, for raw byte access
+    pub fn tree(&self) -> &TransactionalTree {
+        self.tree
+    }
+}
+
+/// Open the sled database at `path`, reporting a lock held by another open handle as
+/// `StorageError::AlreadyOpen`
+fn open_db(path: &Path) -> std::result::Result<Db, StorageError> {
+    sled::open(path).map_err(|source| match &source {
+        // sled reports a failed file lock as a plain I/O error
+        sled::Error::Io(e) if e.to_string().contains("could not acquire lock") => {
+            StorageError::AlreadyOpen(path.to_path_buf())
+        }
+        _ => StorageError::Open { path: path.to_path_buf(), source },
+    })
+}
+
+/// Parse the `KEY_TIMESTAMP_FORMAT` suffix of a key that starts with `prefix`
+fn key_timestamp(key: &str, prefix: &str) -> Option<DateTime<Utc>> {
+    let suffix = key.strip_prefix(prefix)?;
+    NaiveDateTime::parse_from_str(suffix, KEY_TIMESTAMP_FORMAT)
+        .ok()
+        .map(|naive| Utc.from_utc_datetime(&naive))
 }
 
 // Example usage and tests
@@ -177,6 +446,32 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_schema_validator_reused_across_documents() -> Result<()> {
+        let schema = json!({
+            "type": "object",
+            "properties": { "id": { "type": "integer", "minimum": 0 } },
+            "required": ["id"]
+        });
+
+        // One compilation serves every document
+        let compilations = || SCHEMA_COMPILATIONS.with(|count| count.get());
+        let before = compilations();
+        let validator = SchemaValidator::new(&schema)?;
+        for id in 0..1000 {
+            validator.validate(&json!({ "id": id }))?;
+            assert!(validator.validate(&json!({ "id": -id - 1 })).is_err());
+        }
+        assert!(validator.validate(&json!({})).is_err());
+        assert_eq!(compilations() - before, 1);
+
+        // validate_json compiles per call
+        PromptStorage::validate_json(&schema, &json!({ "id": 1 }))?;
+        assert_eq!(compilations() - before, 2);
+
+        Ok(())
+    }
+
     #[test]
     fn test_prompt_storage() -> Result<()> {
         let dir = tempdir()?;
@@ -212,6 +507,36 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_list_iter_is_lazy() -> Result<()> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static DESERIALIZED: AtomicUsize = AtomicUsize::new(0);
+
+        struct Counted(u32);
+
+        impl<'de> Deserialize<'de> for Counted {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+                DESERIALIZED.fetch_add(1, Ordering::SeqCst);
+                u32::deserialize(deserializer).map(Counted)
+            }
+        }
+
+        let dir = tempdir()?;
+        let storage = PromptStorage::new(dir.path())?;
+        for n in 0..100u32 {
+            storage.store("counted", &n)?;
+        }
+
+        let first: Vec<(Uuid, Counted)> = storage.list_iter("counted").take(5).collect::<Result<_>>()?;
+        assert_eq!(first.len(), 5);
+        assert!(first.iter().all(|(_, item)| item.0 < 100));
+        assert_eq!(DESERIALIZED.load(Ordering::SeqCst), 5);
+
+        assert_eq!(storage.list::<u32>("counted")?.len(), 100);
+        Ok(())
+    }
+
     #[test]
     fn test_storage_operations() -> Result<()> {
         let temp_dir = tempdir()?;
@@ -245,4 +570,153 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_list_keys_in_range() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let storage = Storage::new(temp_dir.path())?;
+
+        storage.store("record_20240101_120000", &1)?;
+        storage.store("record_20240102_120000", &2)?;
+        storage.store("record_20240103_120000", &3)?;
+        storage.store("record_not_a_timestamp", &4)?;
+        storage.store("other_20240102_120000", &5)?;
+
+        let from = Utc.with_ymd_and_hms(2024, 1, 1, 18, 0, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2024, 1, 3, 12, 0, 0).unwrap();
+        let keys = storage.list_keys_in_range("record_", from, to)?;
+
+        assert_eq!(keys, vec![
+            "record_20240102_120000".to_string(),
+            "record_20240103_120000".to_string(),
+        ]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_transaction_is_all_or_nothing() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let storage = Storage::new(temp_dir.path())?;
+
+        let result: Result<()> = storage.transaction(|tx| {
+            tx.store("validation", &json!({ "passed": true }))?;
+            tx.store("record", &json!({ "id": 1 }))?;
+            abort(anyhow::anyhow!("simulated failure"))
+        });
+        assert_eq!(result.unwrap_err().to_string(), "simulated failure");
+        assert!(storage.load::<Value>("validation")?.is_none());
+        assert!(storage.load::<Value>("record")?.is_none());
+
+        storage.transaction(|tx| {
+            tx.store("validation", &json!({ "passed": true }))?;
+            tx.store("record", &json!({ "id": 1 }))
+        })?;
+        assert_eq!(storage.load::<Value>("validation")?, Some(json!({ "passed": true })));
+        assert_eq!(storage.load::<Value>("record")?, Some(json!({ "id": 1 })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_second_open_reports_already_open() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let _first = Storage::new(temp_dir.path())?;
+
+        match Storage::new(temp_dir.path()) {
+            Err(StorageError::AlreadyOpen(path)) => assert_eq!(path, temp_dir.path()),
+            other => panic!("expected AlreadyOpen, got {:?}", other.map(|_| ())),
+        }
+        let err = PromptStorage::new(temp_dir.path()).err().expect("prompt storage open should fail");
+        assert!(err.to_string().contains("another build-system instance"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let storage = Storage::new(temp_dir.path())?;
+
+        storage.store("cache_a", &1)?;
+        storage.store("cache_b", &2)?;
+        storage.store("record_20240101_120000", &3)?;
+        storage.store("record_20240301_120000", &4)?;
+        storage.store("record_latest", &5)?;
+
+        assert_eq!(storage.prune(|key| key.starts_with("cache_"))?, 2);
+
+        let cutoff = Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+        assert_eq!(storage.prune_older_than("record_", cutoff)?, 1);
+
+        let mut keys = storage.list_keys()?;
+        keys.sort();
+        assert_eq!(keys, vec!["record_20240301_120000".to_string(), "record_latest".to_string()]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_backup_and_restore() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let storage = Storage::new(temp_dir.path().join("db"))?;
+        let backup = temp_dir.path().join("storage.bak");
+
+        storage.store("alpha", &json!({"name": "alpha", "tags": ["a", "b"]}))?;
+        storage.store("beta", &42)?;
+        storage.store("gamma", &"text".to_string())?;
+        assert_eq!(storage.backup_to(&backup)?, 3);
+
+        storage.clear()?;
+        storage.store("stale", &0)?;
+        assert_eq!(storage.restore_from(&backup, true)?, 3);
+
+        let mut keys = storage.list_keys()?;
+        keys.sort();
+        assert_eq!(keys, vec!["alpha", "beta", "gamma"]);
+        assert_eq!(storage.load::<Value>("alpha")?, Some(json!({"name": "alpha", "tags": ["a", "b"]})));
+        assert_eq!(storage.load::<i32>("beta")?, Some(42));
+        assert_eq!(storage.load::<String>("gamma")?, Some("text".to_string()));
+
+        storage.store("extra", &1)?;
+        storage.restore_from(&backup, false)?;
+        assert_eq!(storage.list_keys()?.len(), 4);
+
+        std::fs::write(&backup, b"not a backup")?;
+        assert!(storage.restore_from(&backup, true).is_err());
+        assert_eq!(storage.list_keys()?.len(), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_rejects_truncated_backup() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let storage = Storage::new(temp_dir.path().join("db"))?;
+        let backup = temp_dir.path().join("storage.bak");
+
+        storage.store("alpha", &1)?;
+        storage.store("beta", &2)?;
+        storage.backup_to(&backup)?;
+
+        // Cut the backup off cleanly after its first entry, dropping the end marker
+        let mut raw = Vec::new();
+        GzDecoder::new(std::fs::File::open(&backup)?).read_to_end(&mut raw)?;
+        let first_entry = BACKUP_MAGIC.len() + 4 + "alpha".len() + 4 + serde_json::to_vec(&1)?.len();
+        let mut encoder = GzEncoder::new(std::fs::File::create(&backup)?, Compression::default());
+        encoder.write_all(&raw[..first_entry])?;
+        encoder.finish()?;
+
+        storage.store("gamma", &3)?;
+        let err = storage.restore_from(&backup, true).unwrap_err();
+        assert!(err.to_string().contains("truncated"), "{}", err);
+        assert_eq!(storage.list_keys()?.len(), 3);
+
+        // A length prefix beyond the cap is rejected instead of allocated
+        let mut encoder = GzEncoder::new(std::fs::File::create(&backup)?, Compression::default());
+        encoder.write_all(BACKUP_MAGIC)?;
+        encoder.write_all(&(u32::MAX - 1).to_le_bytes())?;
+        encoder.finish()?;
+        let err = storage.restore_from(&backup, true).unwrap_err();
+        assert!(err.to_string().contains("exceeds"), "{}", err);
+        Ok(())
+    }
 }
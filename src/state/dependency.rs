@@ -36,6 +36,40 @@ impl DependencyGraph {
         Ok(())
     }
 
+    /// Register several tasks' dependencies while holding the graph locks once,
+    /// so no reader observes a partially registered batch
+    pub async fn add_tasks(&self, tasks: Vec<(TaskId, Vec<TaskId>)>) -> Result<(), StateError> {
+        let mut deps = self.dependencies.write().await;
+        let mut depts = self.dependents.write().await;
+
+        for (task_id, dependencies) in tasks {
+            let task_deps = deps.entry(task_id.clone()).or_insert_with(HashSet::new);
+            for dep in dependencies {
+                task_deps.insert(dep.clone());
+                depts.entry(dep).or_insert_with(HashSet::new).insert(task_id.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Drop the edges from `task_id` to each of `dependencies`, keeping the task itself
+    pub async fn remove_dependencies(&self, task_id: &TaskId, dependencies: &[TaskId]) -> Result<(), StateError> {
+        let mut deps = self.dependencies.write().await;
+        let mut depts = self.dependents.write().await;
+
+        if let Some(task_deps) = deps.get_mut(task_id) {
+            for dep in dependencies {
+                task_deps.remove(dep);
+                if let Some(dep_depts) = depts.get_mut(dep) {
+                    dep_depts.remove(task_id);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn remove_task(&self, task_id: &TaskId) -> Result<(), StateError> {
         let mut deps = self.dependencies.write().await;
         let mut depts = self.dependents.write().await;
@@ -73,6 +107,51 @@ impl DependencyGraph {
         Ok(depts.get(task_id).cloned().unwrap_or_default())
     }
 
+    /// Group tasks into layers with Kahn's algorithm: every task's dependencies lie in
+    /// earlier layers, so the tasks within one layer can run in parallel. Each layer is
+    /// sorted by id for a stable order.
+    pub async fn topo_layers(&self) -> Result<Vec<Vec<TaskId>>, StateError> {
+        let deps = self.dependencies.read().await;
+        let depts = self.dependents.read().await;
+
+        // Tasks that only appear as someone's dependency are nodes too
+        let mut remaining: HashMap<TaskId, usize> = deps
+            .iter()
+            .map(|(task_id, task_deps)| (task_id.clone(), task_deps.len()))
+            .collect();
+        for task_id in depts.keys() {
+            remaining.entry(task_id.clone()).or_insert(0);
+        }
+
+        let mut layers = Vec::new();
+        while !remaining.is_empty() {
+            let mut layer: Vec<TaskId> = remaining
+                .iter()
+                .filter(|(_, in_degree)| **in_degree == 0)
+                .map(|(task_id, _)| task_id.clone())
+                .collect();
+
+            if layer.is_empty() {
+                let mut cyclic: Vec<String> = remaining.keys().map(|id| id.to_string()).collect();
+                cyclic.sort();
+                return Err(StateError::CircularDependency(cyclic.join(", ")));
+            }
+            layer.sort_by(|a, b| a.0.cmp(&b.0));
+
+            for task_id in &layer {
+                remaining.remove(task_id);
+                for dependent in depts.get(task_id).into_iter().flatten() {
+                    if let Some(in_degree) = remaining.get_mut(dependent) {
+                        *in_degree -= 1;
+                    }
+                }
+            }
+            layers.push(layer);
+        }
+
+        Ok(layers)
+    }
+
     pub async fn has_cycle(&self) -> bool {
         let deps = self.dependencies.read().await;
         let mut visited = HashSet::new();
@@ -183,4 +262,32 @@ mod tests {
 
         assert!(graph.has_cycle().await);
     }
+
+    #[tokio::test]
+    async fn test_topo_layers_diamond() {
+        let graph = DependencyGraph::new();
+        let root = TaskId::new("root");
+        let left = TaskId::new("left");
+        let right = TaskId::new("right");
+        let join = TaskId::new("join");
+
+        graph.add_task(left.clone(), vec![root.clone()]).await.unwrap();
+        graph.add_task(right.clone(), vec![root.clone()]).await.unwrap();
+        graph.add_task(join.clone(), vec![left.clone(), right.clone()]).await.unwrap();
+
+        let layers = graph.topo_layers().await.unwrap();
+        assert_eq!(layers, vec![vec![root], vec![left, right], vec![join]]);
+    }
+
+    #[tokio::test]
+    async fn test_topo_layers_cycle_errors() {
+        let graph = DependencyGraph::new();
+        let task1 = TaskId::new("test-1");
+        let task2 = TaskId::new("test-2");
+
+        graph.add_task(task1.clone(), vec![task2.clone()]).await.unwrap();
+        graph.add_task(task2.clone(), vec![task1.clone()]).await.unwrap();
+
+        assert!(matches!(graph.topo_layers().await, Err(StateError::CircularDependency(_))));
+    }
 }
@@ -5,6 +5,12 @@ pub enum StateError {
     #[error("Task not found: {0}")]
     TaskNotFound(String),
     
+    #[error("Task was deleted: {0}")]
+    TaskDeleted(String),
+    
+    #[error("Lock poisoned: {0}")]
+    LockPoisoned(String),
+    
     #[error("Task already exists: {0}")]
     TaskAlreadyExists(String),
     
@@ -17,9 +23,20 @@ pub enum StateError {
     #[error("Dependencies not met: {0}")]
     DependenciesNotMet(String),
     
+    #[error("Incompatible snapshot version {found} (expected {expected})")]
+    IncompatibleSnapshot { found: u32, expected: u32 },
+    
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
     
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
+    
+    #[cfg(feature = "postgres")]
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+    
+    #[cfg(feature = "postgres")]
+    #[error("Migration error: {0}")]
+    MigrationError(#[from] sqlx::migrate::MigrateError),
 }
@@ -1,86 +1,342 @@
 use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use chrono::Utc;
+use indexmap::IndexSet;
+use lru::LruCache;
+use tokio::sync::RwLock;
 
 use crate::state::error::StateError;
-use crate::state::types::{TaskId, TaskState, TaskStatus, StateSnapshot};
+use crate::state::types::{StatusTransition, TaskId, TaskMetadata, TaskState, TaskStatus, StateSnapshot};
 use crate::state::dependency::DependencyGraph;
+use crate::state::store::{MemoryStateStore, StateStore};
+
+/// Most deleted ids remembered for `TaskDeleted`; older ones are reported as `TaskNotFound`
+const MAX_TOMBSTONES: usize = 1024;
 
-#[derive(Debug, Clone)]
-pub struct StateManager {
-    states: Arc<RwLock<HashMap<TaskId, TaskState>>>,
+/// Recently read tasks. `generation` is bumped by every invalidation, so a read that
+/// raced with a mutation can tell its result may be stale and leave it uncached.
+struct ReadCache {
+    entries: LruCache<TaskId, TaskState>,
+    generation: u64,
+}
+
+/// Tracks tasks and their dependencies on top of a pluggable `StateStore`.
+///
+/// `StateManager::new()` keeps everything in memory; use `with_store` to share state
+/// between instances through a persistent backend.
+pub struct StateManager<S: StateStore = MemoryStateStore> {
+    store: Arc<S>,
     dependencies: DependencyGraph,
+    read_cache: Option<Arc<Mutex<ReadCache>>>,
+    /// Ids deleted through this manager, oldest first, so lookups can report `TaskDeleted`
+    deleted: Arc<RwLock<IndexSet<TaskId>>>,
+}
+
+impl<S: StateStore> Clone for StateManager<S> {
+    fn clone(&self) -> Self {
+        StateManager {
+            store: Arc::clone(&self.store),
+            dependencies: self.dependencies.clone(),
+            read_cache: self.read_cache.clone(),
+            deleted: Arc::clone(&self.deleted),
+        }
+    }
+}
+
+impl<S: StateStore> fmt::Debug for StateManager<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StateManager")
+            .field("store", &self.store)
+            .field("dependencies", &self.dependencies)
+            .field("read_cache", &self.read_cache.is_some())
+            .finish()
+    }
 }
 
 impl StateManager {
     pub fn new() -> Self {
+        Self::with_store(MemoryStateStore::new())
+    }
+}
+
+impl<S: StateStore> StateManager<S> {
+    pub fn with_store(store: S) -> Self {
         StateManager {
-            states: Arc::new(RwLock::new(HashMap::new())),
+            store: Arc::new(store),
             dependencies: DependencyGraph::new(),
+            read_cache: None,
+            deleted: Arc::new(RwLock::new(IndexSet::new())),
+        }
+    }
+
+    /// Keep up to `capacity` recently read tasks in memory so `get_task` can skip the
+    /// store. Entries are invalidated by mutations made through this manager (and its
+    /// clones), so only enable it when no other instance writes to the same store.
+    /// A capacity of zero disables the cache.
+    pub fn with_read_cache(mut self, capacity: usize) -> Self {
+        self.read_cache = NonZeroUsize::new(capacity).map(|capacity| {
+            Arc::new(Mutex::new(ReadCache { entries: LruCache::new(capacity), generation: 0 }))
+        });
+        self
+    }
+
+    fn cache(&self) -> Result<Option<std::sync::MutexGuard<'_, ReadCache>>, StateError> {
+        self.read_cache
+            .as_ref()
+            .map(|cache| cache.lock().map_err(|_| StateError::LockPoisoned("read cache".to_string())))
+            .transpose()
+    }
+
+    fn invalidate(&self, id: &TaskId) -> Result<(), StateError> {
+        if let Some(mut cache) = self.cache()? {
+            cache.entries.pop(id);
+            cache.generation += 1;
+        }
+        Ok(())
+    }
+
+    fn invalidate_all(&self) -> Result<(), StateError> {
+        if let Some(mut cache) = self.cache()? {
+            cache.entries.clear();
+            cache.generation += 1;
+        }
+        Ok(())
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Remember that `id` was deleted, forgetting the oldest tombstone past `MAX_TOMBSTONES`
+    async fn record_deleted(&self, id: &TaskId) {
+        let mut deleted = self.deleted.write().await;
+        deleted.insert(id.clone());
+        if deleted.len() > MAX_TOMBSTONES {
+            deleted.shift_remove_index(0);
+        }
+    }
+
+    /// Report a missing task as `TaskDeleted` when this manager deleted it
+    async fn missing(&self, err: StateError) -> StateError {
+        match err {
+            StateError::TaskNotFound(id) if self.deleted.read().await.contains(&TaskId::new(&id)) => {
+                StateError::TaskDeleted(id)
+            }
+            err => err,
         }
     }
 
     pub async fn create_task(&self, task: TaskState) -> Result<(), StateError> {
-        let task_id = task.id.clone();
-        let mut states = self.states.write().await;
-        if states.contains_key(&task_id) {
-            return Err(StateError::TaskAlreadyExists(task_id.to_string()));
+        self.invalidate(&task.id)?;
+        let id = task.id.clone();
+        self.store.create(task).await?;
+        self.deleted.write().await.shift_remove(&id);
+        Ok(())
+    }
+
+    /// Create all `tasks` or none of them.
+    ///
+    /// The store inserts the batch atomically, and the tasks' dependency edges are
+    /// registered together with them.
+    pub async fn bulk_create_tasks(&self, tasks: Vec<TaskState>) -> Result<(), StateError> {
+        let edges = tasks
+            .iter()
+            .map(|task| (task.id.clone(), task.metadata.dependencies.clone()))
+            .collect();
+        let ids: Vec<TaskId> = tasks.iter().map(|task| task.id.clone()).collect();
+        for id in &ids {
+            self.invalidate(id)?;
+        }
+
+        self.store.create_many(tasks).await?;
+        if let Err(e) = self.dependencies.add_tasks(edges).await {
+            // Roll the batch back so the store and graph stay consistent
+            for id in &ids {
+                let _ = self.store.delete(id).await;
+                self.invalidate(id)?;
+            }
+            return Err(e);
+        }
+        let mut deleted = self.deleted.write().await;
+        for id in &ids {
+            deleted.shift_remove(id);
         }
-        states.insert(task_id, task);
         Ok(())
     }
 
+    /// Create a copy of `source` under `new_id`: same metadata and dependencies, but
+    /// `Pending`, with no history and fresh timestamps. Fails if `source` is missing or
+    /// `new_id` is taken.
+    pub async fn clone_task(&self, source: &TaskId, new_id: TaskId) -> Result<(), StateError> {
+        let source = self.get_task(source).await?;
+        let mut task = TaskState::new(new_id);
+        task.metadata = source.metadata;
+        self.bulk_create_tasks(vec![task]).await
+    }
+
     pub async fn get_task(&self, id: &TaskId) -> Result<TaskState, StateError> {
-        let states = self.states.read().await;
-        states
-            .get(id)
-            .cloned()
-            .ok_or_else(|| StateError::TaskNotFound(id.to_string()))
+        let generation = match self.cache()? {
+            Some(mut cache) => match cache.entries.get(id) {
+                Some(task) => return Ok(task.clone()),
+                None => Some(cache.generation),
+            },
+            None => None,
+        };
+
+        let task = match self.store.get(id).await {
+            Ok(task) => task,
+            Err(e) => return Err(self.missing(e).await),
+        };
+        if let Some(mut cache) = self.cache()? {
+            // Skip caching if a mutation was invalidated while the store was being read
+            if Some(cache.generation) == generation {
+                cache.entries.put(id.clone(), task.clone());
+            }
+        }
+        Ok(task)
     }
 
     pub async fn update_task_status(&self, id: &TaskId, status: TaskStatus) -> Result<(), StateError> {
-        let mut states = self.states.write().await;
-        if let Some(task) = states.get_mut(id) {
-            task.status = status;
-            task.updated_at = Utc::now();
-            Ok(())
-        } else {
-            Err(StateError::TaskNotFound(id.to_string()))
+        let result = self.store.update_status(id, status).await;
+        self.invalidate(id)?;
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) => Err(self.missing(e).await),
+        }
+    }
+
+    /// Recorded status transitions of a task, oldest first
+    pub async fn task_history(&self, id: &TaskId) -> Result<Vec<StatusTransition>, StateError> {
+        Ok(self.get_task(id).await?.history)
+    }
+
+    /// Edit a task's metadata in place, keeping its `created_at` and bumping `updated_at`.
+    /// Dependencies added or removed by `f` are mirrored in the dependency graph.
+    pub async fn update_task_metadata<F>(&self, id: &TaskId, f: F) -> Result<TaskState, StateError>
+    where
+        F: FnOnce(&mut TaskMetadata) + Send + 'static,
+    {
+        let result = self
+            .store
+            .update(id, Box::new(|task: &mut TaskState| {
+                f(&mut task.metadata);
+                task.updated_at = Utc::now();
+            }))
+            .await;
+        self.invalidate(id)?;
+        let (before, updated) = match result {
+            Ok(result) => result,
+            Err(e) => return Err(self.missing(e).await),
+        };
+
+        let previous = &before.metadata.dependencies;
+        let current = &updated.metadata.dependencies;
+        let removed: Vec<TaskId> = previous.iter().filter(|dep| !current.contains(dep)).cloned().collect();
+        let added: Vec<TaskId> = current.iter().filter(|dep| !previous.contains(dep)).cloned().collect();
+        if !removed.is_empty() {
+            self.dependencies.remove_dependencies(id, &removed).await?;
         }
+        if !added.is_empty() {
+            self.dependencies.add_task(id.clone(), added).await?;
+        }
+        Ok(updated)
     }
 
     pub async fn delete_task(&self, id: &TaskId) -> Result<(), StateError> {
-        let mut states = self.states.write().await;
-        if states.remove(id).is_some() {
-            Ok(())
-        } else {
-            Err(StateError::TaskNotFound(id.to_string()))
+        let result = self.store.delete(id).await;
+        self.invalidate(id)?;
+        match result {
+            Ok(()) => {
+                self.record_deleted(id).await;
+                Ok(())
+            }
+            Err(e) => Err(self.missing(e).await),
         }
     }
 
+    /// Delete tasks in one of the terminal `statuses` (completed, failed or cancelled;
+    /// others are ignored) last updated more than `older_than` ago, returning how many
+    /// were removed. A task is kept while a task that isn't being collected depends on
+    /// it. Collected tasks are also removed from the dependency graph, and leave no
+    /// tombstone: looking one up afterwards reports `TaskNotFound`.
+    pub async fn gc(&self, older_than: Duration, statuses: &[TaskStatus]) -> Result<usize, StateError> {
+        let cutoff = chrono::Duration::from_std(older_than)
+            .ok()
+            .and_then(|age| Utc::now().checked_sub_signed(age))
+            .unwrap_or(chrono::DateTime::<Utc>::MIN_UTC);
+        let terminal = [TaskStatus::Completed, TaskStatus::Failed, TaskStatus::Cancelled];
+
+        let mut collectable: HashSet<TaskId> = self
+            .store
+            .list()
+            .await?
+            .into_iter()
+            .filter(|task| terminal.contains(&task.status) && statuses.contains(&task.status))
+            .filter(|task| task.updated_at < cutoff)
+            .map(|task| task.id)
+            .collect();
+
+        // Dropping one task can leave a task it depends on with a live dependent
+        loop {
+            let mut kept = Vec::new();
+            for id in &collectable {
+                let dependents = self.dependencies.get_dependents(id).await?;
+                if dependents.iter().any(|dependent| !collectable.contains(dependent)) {
+                    kept.push(id.clone());
+                }
+            }
+            if kept.is_empty() {
+                break;
+            }
+            for id in kept {
+                collectable.remove(&id);
+            }
+        }
+
+        for id in &collectable {
+            let result = self.store.delete(id).await;
+            self.invalidate(id)?;
+            result?;
+            self.dependencies.remove_task(id).await?;
+        }
+        Ok(collectable.len())
+    }
+
     pub async fn list_tasks(&self) -> Result<Vec<TaskState>, StateError> {
-        let states = self.states.read().await;
-        Ok(states.values().cloned().collect())
+        self.store.list().await
     }
 
     pub async fn get_tasks_by_status(&self, status: TaskStatus) -> Result<Vec<TaskState>, StateError> {
-        let states = self.states.read().await;
-        Ok(states
-            .values()
+        Ok(self
+            .store
+            .list()
+            .await?
+            .into_iter()
             .filter(|task| task.status == status)
-            .cloned()
             .collect())
     }
 
+    /// Number of tasks in each status; every status is present, with zero when unused
+    pub async fn status_counts(&self) -> Result<HashMap<TaskStatus, usize>, StateError> {
+        let mut counts: HashMap<TaskStatus, usize> = TaskStatus::ALL.iter().map(|status| (status.clone(), 0)).collect();
+        for task in self.store.list().await? {
+            *counts.entry(task.status).or_insert(0) += 1;
+        }
+        Ok(counts)
+    }
+
     pub async fn get_ready_tasks(&self) -> Result<Vec<TaskState>, StateError> {
-        let states = self.states.read().await;
-        Ok(states
-            .values()
+        Ok(self
+            .store
+            .list()
+            .await?
+            .into_iter()
             .filter(|task| {
                 task.status == TaskStatus::Pending && task.metadata.dependencies.is_empty()
             })
-            .cloned()
             .collect())
     }
 
@@ -93,29 +349,106 @@ impl StateManager {
     }
 
     pub async fn take_snapshot(&self) -> Result<StateSnapshot, StateError> {
-        let states = self.states.read().await;
-        Ok(StateSnapshot {
-            tasks: states.clone(),
-            timestamp: Utc::now(),
-        })
+        let tasks = self
+            .store
+            .list()
+            .await?
+            .into_iter()
+            .map(|task| (task.id.clone(), task))
+            .collect();
+        Ok(StateSnapshot::new(tasks))
     }
 
     pub async fn restore_snapshot(&self, snapshot: StateSnapshot) -> Result<(), StateError> {
-        let mut states = self.states.write().await;
-        *states = snapshot.tasks;
-        Ok(())
+        let result = self.store.replace_all(snapshot.tasks).await;
+        self.invalidate_all()?;
+        self.deleted.write().await.clear();
+        result
     }
 
     pub async fn add_dependency(&self, task_id: TaskId, dependencies: Vec<TaskId>) -> Result<(), StateError> {
         self.dependencies.add_task(task_id, dependencies).await
     }
+
+    /// Estimated duration of the whole plan, taken as the longest chain of
+    /// estimated durations through the dependency graph (the critical path)
+    pub async fn estimated_total_duration(&self) -> Result<Duration, StateError> {
+        self.critical_path(&|_| true).await
+    }
+
+    /// Critical-path estimate of the work left, where completed tasks take no time
+    pub async fn estimated_remaining(&self) -> Result<Duration, StateError> {
+        self.critical_path(&|task| task.status != TaskStatus::Completed).await
+    }
+
+    async fn critical_path(&self, counts: &(dyn Fn(&TaskState) -> bool + Sync)) -> Result<Duration, StateError> {
+        let states: HashMap<TaskId, TaskState> = self
+            .store
+            .list()
+            .await?
+            .into_iter()
+            .map(|task| (task.id.clone(), task))
+            .collect();
+
+        // Dependencies may be declared on the task metadata or registered in the graph
+        let mut edges: HashMap<TaskId, HashSet<TaskId>> = HashMap::new();
+        for task in states.values() {
+            let mut deps: HashSet<TaskId> = task.metadata.dependencies.iter().cloned().collect();
+            deps.extend(self.dependencies.get_dependencies(&task.id).await.unwrap_or_default());
+            edges.insert(task.id.clone(), deps);
+        }
+
+        let mut finish_times = HashMap::new();
+        let mut visiting = HashSet::new();
+        let mut longest = Duration::ZERO;
+        for id in states.keys() {
+            let finish = Self::finish_time(id, &states, &edges, counts, &mut finish_times, &mut visiting);
+            longest = longest.max(finish);
+        }
+        Ok(longest)
+    }
+
+    /// Earliest finish time of a task: its own estimate after its slowest dependency
+    fn finish_time(
+        id: &TaskId,
+        states: &HashMap<TaskId, TaskState>,
+        edges: &HashMap<TaskId, HashSet<TaskId>>,
+        counts: &(dyn Fn(&TaskState) -> bool + Sync),
+        finish_times: &mut HashMap<TaskId, Duration>,
+        visiting: &mut HashSet<TaskId>,
+    ) -> Duration {
+        if let Some(finish) = finish_times.get(id) {
+            return *finish;
+        }
+        let task = match states.get(id) {
+            Some(task) => task,
+            None => return Duration::ZERO,
+        };
+        // A task already on the current path means a cycle; don't count it twice
+        if !visiting.insert(id.clone()) {
+            return Duration::ZERO;
+        }
+
+        let mut slowest_dependency = Duration::ZERO;
+        if let Some(deps) = edges.get(id) {
+            for dep in deps {
+                let finish = Self::finish_time(dep, states, edges, counts, finish_times, visiting);
+                slowest_dependency = slowest_dependency.max(finish);
+            }
+        }
+        visiting.remove(id);
+
+        let own = if counts(task) { task.metadata.estimated_duration } else { Duration::ZERO };
+        let finish = slowest_dependency + own;
+        finish_times.insert(id.clone(), finish);
+        finish
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::state::types::TaskMetadata;
-    use std::time::Duration;
+    use crate::state::types::{TaskMetadata, MAX_STATUS_HISTORY};
 
     fn create_test_task(id: &str) -> TaskState {
         let task_id = TaskId::new(id);
@@ -129,6 +462,7 @@ mod tests {
             priority: 1,
             tags: vec!["test".to_string()],
             additional_info: HashMap::new(),
+            working_dir: None,
         };
         task
     }
@@ -146,6 +480,35 @@ mod tests {
         assert_eq!(retrieved, task);
     }
 
+    #[tokio::test]
+    async fn test_clone_task() -> Result<(), StateError> {
+        let manager = StateManager::new();
+        manager.create_task(create_test_task("dep")).await?;
+        let mut source = create_test_task("template");
+        source.metadata.dependencies = vec![TaskId::new("dep")];
+        source.metadata.additional_info.insert("target".to_string(), "release".to_string());
+        manager.bulk_create_tasks(vec![source.clone()]).await?;
+        manager.update_task_status(&source.id, TaskStatus::Completed).await?;
+        let source = manager.get_task(&source.id).await?;
+
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        manager.clone_task(&source.id, TaskId::new("rerun")).await?;
+
+        let copy = manager.get_task(&TaskId::new("rerun")).await?;
+        assert_eq!(copy.metadata, source.metadata);
+        assert_eq!(copy.status, TaskStatus::Pending);
+        assert!(copy.history.is_empty());
+        assert!(copy.created_at > source.created_at);
+        assert!(copy.updated_at > source.updated_at);
+        assert!(manager.get_task_dependencies(&copy.id).await?.contains(&TaskId::new("dep")));
+
+        let taken = manager.clone_task(&source.id, TaskId::new("dep")).await;
+        assert!(matches!(taken, Err(StateError::TaskAlreadyExists(id)) if id == "dep"));
+        let missing = manager.clone_task(&TaskId::new("nope"), TaskId::new("other")).await;
+        assert!(matches!(missing, Err(StateError::TaskNotFound(_))));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_update_task_status() {
         let manager = StateManager::new();
@@ -198,4 +561,309 @@ mod tests {
         assert_eq!(ready.len(), 1);
         assert_eq!(ready[0].id.0, "test-task-1");
     }
+
+    #[tokio::test]
+    async fn test_estimated_duration_uses_critical_path() {
+        let manager = StateManager::new();
+
+        // Diamond: root -> (short, long) -> join
+        let mut root = create_test_task("root");
+        root.metadata.estimated_duration = Duration::from_secs(10);
+        let mut short = create_test_task("short");
+        short.metadata.estimated_duration = Duration::from_secs(20);
+        short.metadata.dependencies = vec![TaskId::new("root")];
+        let mut long = create_test_task("long");
+        long.metadata.estimated_duration = Duration::from_secs(50);
+        long.metadata.dependencies = vec![TaskId::new("root")];
+        let mut join = create_test_task("join");
+        join.metadata.estimated_duration = Duration::from_secs(5);
+        join.metadata.dependencies = vec![TaskId::new("short"), TaskId::new("long")];
+
+        for task in [root, short, long, join] {
+            manager.create_task(task).await.unwrap();
+        }
+
+        assert_eq!(manager.estimated_total_duration().await.unwrap(), Duration::from_secs(65));
+
+        manager.update_task_status(&TaskId::new("root"), TaskStatus::Completed).await.unwrap();
+        assert_eq!(manager.estimated_remaining().await.unwrap(), Duration::from_secs(55));
+        assert_eq!(manager.estimated_total_duration().await.unwrap(), Duration::from_secs(65));
+    }
+
+    #[tokio::test]
+    async fn test_bulk_create_tasks_is_all_or_nothing() {
+        let manager = StateManager::new();
+        manager.create_task(create_test_task("existing")).await.unwrap();
+
+        let mut first = create_test_task("first");
+        first.metadata.dependencies = vec![TaskId::new("existing")];
+        let batch = vec![first, create_test_task("existing"), create_test_task("second")];
+
+        let result = manager.bulk_create_tasks(batch).await;
+        assert!(matches!(result, Err(StateError::TaskAlreadyExists(id)) if id == "existing"));
+        assert_eq!(manager.list_tasks().await.unwrap().len(), 1);
+        assert!(manager.get_task_dependencies(&TaskId::new("first")).await.unwrap().is_empty());
+
+        let mut first = create_test_task("first");
+        first.metadata.dependencies = vec![TaskId::new("existing")];
+        manager.bulk_create_tasks(vec![first, create_test_task("second")]).await.unwrap();
+        assert_eq!(manager.list_tasks().await.unwrap().len(), 3);
+        assert!(manager.get_task_dependents(&TaskId::new("existing")).await.unwrap().contains(&TaskId::new("first")));
+    }
+
+    #[tokio::test]
+    async fn test_update_task_metadata() -> Result<(), StateError> {
+        let manager = StateManager::new();
+        manager.create_task(create_test_task("base")).await?;
+        let mut task = create_test_task("edited");
+        task.metadata.dependencies = vec![TaskId::new("old-dep")];
+        manager.bulk_create_tasks(vec![task]).await?;
+        let id = TaskId::new("edited");
+        let before = manager.get_task(&id).await?;
+
+        let updated = manager.update_task_metadata(&id, |metadata| {
+            metadata.priority = 5;
+            metadata.dependencies = vec![TaskId::new("base")];
+        }).await?;
+
+        assert_eq!(updated.metadata.priority, 5);
+        assert_eq!(updated.created_at, before.created_at);
+        assert!(updated.updated_at >= before.updated_at);
+        assert_eq!(manager.get_task(&id).await?.metadata.priority, 5);
+
+        let deps = manager.get_task_dependencies(&id).await?;
+        assert_eq!(deps, HashSet::from([TaskId::new("base")]));
+        assert!(manager.get_task_dependents(&TaskId::new("base")).await?.contains(&id));
+        assert!(manager.get_task_dependents(&TaskId::new("old-dep")).await?.is_empty());
+
+        assert!(matches!(
+            manager.update_task_metadata(&TaskId::new("missing"), |m| m.priority = 1).await,
+            Err(StateError::TaskNotFound(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_cache_invalidated_on_update() -> Result<(), StateError> {
+        let manager = StateManager::new().with_read_cache(2);
+        let task = create_test_task("cached");
+        manager.create_task(task.clone()).await?;
+
+        let first = manager.get_task(&task.id).await?;
+        let second = manager.get_task(&task.id).await?;
+        assert_eq!(first, second);
+        assert!(manager.read_cache.as_ref().unwrap().lock().unwrap().entries.contains(&task.id));
+
+        manager.update_task_status(&task.id, TaskStatus::Running).await?;
+        assert!(!manager.read_cache.as_ref().unwrap().lock().unwrap().entries.contains(&task.id));
+        assert_eq!(manager.get_task(&task.id).await?.status, TaskStatus::Running);
+
+        manager.update_task_metadata(&task.id, |metadata| metadata.priority = 9).await?;
+        assert_eq!(manager.get_task(&task.id).await?.metadata.priority, 9);
+
+        manager.delete_task(&task.id).await?;
+        assert!(matches!(manager.get_task(&task.id).await, Err(StateError::TaskDeleted(_))));
+        Ok(())
+    }
+
+    /// Memory store whose `get` waits for `release` once `gate` is set, so a test can
+    /// mutate the task while a read is in flight
+    #[derive(Debug, Default)]
+    struct GatedStore {
+        inner: MemoryStateStore,
+        gate: std::sync::atomic::AtomicBool,
+        reading: tokio::sync::Notify,
+        release: tokio::sync::Notify,
+    }
+
+    #[async_trait::async_trait]
+    impl StateStore for GatedStore {
+        async fn create(&self, task: TaskState) -> Result<(), StateError> {
+            self.inner.create(task).await
+        }
+        async fn get(&self, id: &TaskId) -> Result<TaskState, StateError> {
+            let task = self.inner.get(id).await;
+            if self.gate.swap(false, std::sync::atomic::Ordering::SeqCst) {
+                self.reading.notify_one();
+                self.release.notified().await;
+            }
+            task
+        }
+        async fn update_status(&self, id: &TaskId, status: TaskStatus) -> Result<(), StateError> {
+            self.inner.update_status(id, status).await
+        }
+        async fn update(
+            &self,
+            id: &TaskId,
+            f: Box<dyn for<'t> FnOnce(&'t mut TaskState) + Send>,
+        ) -> Result<(TaskState, TaskState), StateError> {
+            self.inner.update(id, f).await
+        }
+        async fn delete(&self, id: &TaskId) -> Result<(), StateError> {
+            self.inner.delete(id).await
+        }
+        async fn list(&self) -> Result<Vec<TaskState>, StateError> {
+            self.inner.list().await
+        }
+        async fn create_many(&self, tasks: Vec<TaskState>) -> Result<(), StateError> {
+            self.inner.create_many(tasks).await
+        }
+        async fn replace_all(&self, tasks: HashMap<TaskId, TaskState>) -> Result<(), StateError> {
+            self.inner.replace_all(tasks).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_racing_update_is_not_cached() -> Result<(), StateError> {
+        let manager = StateManager::with_store(GatedStore::default()).with_read_cache(4);
+        let task = create_test_task("raced");
+        manager.create_task(task.clone()).await?;
+
+        manager.store().gate.store(true, std::sync::atomic::Ordering::SeqCst);
+        let reader = manager.clone();
+        let id = task.id.clone();
+        let read = tokio::spawn(async move { reader.get_task(&id).await });
+
+        // The reader holds the pre-update task while the update lands
+        manager.store().reading.notified().await;
+        manager.update_task_status(&task.id, TaskStatus::Running).await?;
+        manager.store().release.notify_one();
+        assert_eq!(read.await.unwrap()?.status, TaskStatus::Pending);
+
+        assert_eq!(manager.get_task(&task.id).await?.status, TaskStatus::Running);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_task_history_records_transitions() -> Result<(), StateError> {
+        let manager = StateManager::new();
+        let task = create_test_task("audited");
+        manager.create_task(task.clone()).await?;
+        let before = Utc::now();
+
+        manager.update_task_status(&task.id, TaskStatus::Running).await?;
+        manager.update_task_status(&task.id, TaskStatus::Completed).await?;
+
+        let history = manager.task_history(&task.id).await?;
+        assert_eq!(history.len(), 2);
+        assert_eq!((&history[0].from, &history[0].to), (&TaskStatus::Pending, &TaskStatus::Running));
+        assert_eq!((&history[1].from, &history[1].to), (&TaskStatus::Running, &TaskStatus::Completed));
+        assert!(history[0].at >= before);
+        assert!(history[1].at >= history[0].at);
+        Ok(())
+    }
+
+    #[test]
+    fn test_task_history_is_bounded() {
+        let mut task = TaskState::new(TaskId::new("busy"));
+        for _ in 0..MAX_STATUS_HISTORY + 10 {
+            task.set_status(TaskStatus::Running);
+        }
+        assert_eq!(task.history.len(), MAX_STATUS_HISTORY);
+    }
+
+    #[tokio::test]
+    async fn test_deleted_task_distinct_from_missing() -> Result<(), StateError> {
+        let manager = StateManager::new();
+        let task = create_test_task("short-lived");
+        manager.create_task(task.clone()).await?;
+        manager.delete_task(&task.id).await?;
+
+        assert!(matches!(manager.get_task(&task.id).await, Err(StateError::TaskDeleted(id)) if id == "short-lived"));
+        assert!(matches!(manager.delete_task(&task.id).await, Err(StateError::TaskDeleted(_))));
+        assert!(matches!(
+            manager.update_task_status(&task.id, TaskStatus::Running).await,
+            Err(StateError::TaskDeleted(_))
+        ));
+        assert!(matches!(
+            manager.get_task(&TaskId::new("never-created")).await,
+            Err(StateError::TaskNotFound(_))
+        ));
+
+        // Re-creating the id clears its tombstone
+        manager.create_task(task.clone()).await?;
+        assert_eq!(manager.get_task(&task.id).await?, task);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tombstones_are_bounded() -> Result<(), StateError> {
+        let manager = StateManager::new();
+        for index in 0..=MAX_TOMBSTONES {
+            let task = create_test_task(&format!("doomed-{}", index));
+            manager.create_task(task.clone()).await?;
+            manager.delete_task(&task.id).await?;
+        }
+
+        assert_eq!(manager.deleted.read().await.len(), MAX_TOMBSTONES);
+        assert!(matches!(manager.get_task(&TaskId::new("doomed-0")).await, Err(StateError::TaskNotFound(_))));
+        let newest = TaskId::new(&format!("doomed-{}", MAX_TOMBSTONES));
+        assert!(matches!(manager.get_task(&newest).await, Err(StateError::TaskDeleted(_))));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_status_counts() -> Result<(), StateError> {
+        let manager = StateManager::new();
+        let statuses = [
+            ("a", TaskStatus::Pending),
+            ("b", TaskStatus::Pending),
+            ("c", TaskStatus::Running),
+            ("d", TaskStatus::Completed),
+            ("e", TaskStatus::Completed),
+            ("f", TaskStatus::Completed),
+            ("g", TaskStatus::Failed),
+        ];
+        for (id, status) in &statuses {
+            let task = create_test_task(id);
+            manager.create_task(task).await?;
+            manager.update_task_status(&TaskId::new(id), status.clone()).await?;
+        }
+
+        let counts = manager.status_counts().await?;
+        assert_eq!(counts[&TaskStatus::Pending], 2);
+        assert_eq!(counts[&TaskStatus::Running], 1);
+        assert_eq!(counts[&TaskStatus::Completed], 3);
+        assert_eq!(counts[&TaskStatus::Failed], 1);
+        assert_eq!(counts[&TaskStatus::Cancelled], 0);
+        assert_eq!(counts.values().sum::<usize>(), statuses.len());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_gc_collects_old_terminal_tasks() -> Result<(), StateError> {
+        let manager = StateManager::new();
+        let long_ago = Utc::now() - chrono::Duration::hours(2);
+        let tasks = [
+            ("old-done", TaskStatus::Completed, long_ago),
+            ("old-cancelled", TaskStatus::Cancelled, long_ago),
+            ("old-needed", TaskStatus::Completed, long_ago),
+            ("old-failed", TaskStatus::Failed, long_ago),
+            ("recent-done", TaskStatus::Completed, Utc::now()),
+            ("running", TaskStatus::Running, long_ago),
+            ("recent-running", TaskStatus::Running, Utc::now()),
+        ];
+        for (id, status, updated_at) in &tasks {
+            let mut task = create_test_task(id);
+            task.status = status.clone();
+            task.updated_at = *updated_at;
+            manager.create_task(task).await?;
+        }
+        // A running task still depends on this one, so it must survive
+        manager.add_dependency(TaskId::new("recent-running"), vec![TaskId::new("old-needed")]).await?;
+        manager.add_dependency(TaskId::new("old-cancelled"), vec![TaskId::new("old-done")]).await?;
+
+        let removed = manager
+            .gc(Duration::from_secs(3600), &[TaskStatus::Completed, TaskStatus::Cancelled, TaskStatus::Running])
+            .await?;
+        assert_eq!(removed, 2);
+
+        let mut remaining: Vec<String> = manager.list_tasks().await?.into_iter().map(|task| task.id.0).collect();
+        remaining.sort();
+        assert_eq!(remaining, ["old-failed", "old-needed", "recent-done", "recent-running", "running"]);
+        assert!(matches!(manager.get_task(&TaskId::new("old-done")).await, Err(StateError::TaskNotFound(_))));
+        assert!(manager.deleted.read().await.is_empty());
+        assert!(manager.get_task_dependents(&TaskId::new("old-done")).await?.is_empty());
+        Ok(())
+    }
 }
@@ -2,8 +2,16 @@ pub mod error;
 pub mod types;
 pub mod manager;
 pub mod dependency;
+pub mod store;
+
+#[cfg(feature = "postgres")]
+pub mod postgres;
 
 pub use manager::StateManager;
+pub use store::{MemoryStateStore, StateStore};
+
+#[cfg(feature = "postgres")]
+pub use postgres::PgStateStore;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use async_trait::async_trait;
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::types::Json;
+use sqlx::{Postgres, Transaction};
+
+use crate::state::error::StateError;
+use crate::state::store::StateStore;
+use crate::state::types::{TaskId, TaskState, TaskStatus};
+
+/// `StateStore` backed by a PostgreSQL `tasks` table, so several build-system
+/// instances can share task state
+#[derive(Debug, Clone)]
+pub struct PgStateStore {
+    pool: PgPool,
+}
+
+impl PgStateStore {
+    /// Connect to `database_url` and apply any pending migrations
+    pub async fn connect(database_url: &str) -> Result<Self, StateError> {
+        let pool = PgPoolOptions::new().connect(database_url).await?;
+        let store = Self::from_pool(pool);
+        store.migrate().await?;
+        Ok(store)
+    }
+
+    /// Wrap an existing pool; call `migrate` if the schema may not exist yet
+    pub fn from_pool(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn migrate(&self) -> Result<(), StateError> {
+        sqlx::migrate!("./migrations").run(&self.pool).await?;
+        Ok(())
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    async fn insert(tx: &mut Transaction<'_, Postgres>, task: &TaskState) -> Result<(), StateError> {
+        let result = sqlx::query(
+            "INSERT INTO tasks (id, status, owner, state, created_at, updated_at) \
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(&task.id.0)
+        .bind(status_column(&task.status))
+        .bind(&task.metadata.owner)
+        .bind(Json(task))
+        .bind(task.created_at)
+        .bind(task.updated_at)
+        .execute(&mut **tx)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+                Err(StateError::TaskAlreadyExists(task.id.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Value stored in the indexed `status` column; matches the serialized status name
+fn status_column(status: &TaskStatus) -> String {
+    status.as_str().to_string()
+}
+
+#[async_trait]
+impl StateStore for PgStateStore {
+    async fn create(&self, task: TaskState) -> Result<(), StateError> {
+        let mut tx = self.pool.begin().await?;
+        Self::insert(&mut tx, &task).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get(&self, id: &TaskId) -> Result<TaskState, StateError> {
+        let state: Option<Json<TaskState>> = sqlx::query_scalar("SELECT state FROM tasks WHERE id = $1")
+            .bind(&id.0)
+            .fetch_optional(&self.pool)
+            .await?;
+        state
+            .map(|Json(task)| task)
+            .ok_or_else(|| StateError::TaskNotFound(id.to_string()))
+    }
+
+    async fn update_status(&self, id: &TaskId, status: TaskStatus) -> Result<(), StateError> {
+        self.update(id, Box::new(move |task| {
+            task.set_status(status);
+        }))
+        .await?;
+        Ok(())
+    }
+
+    async fn update(
+        &self,
+        id: &TaskId,
+        f: Box<dyn for<'t> FnOnce(&'t mut TaskState) + Send>,
+    ) -> Result<(TaskState, TaskState), StateError> {
+        let mut tx = self.pool.begin().await?;
+        let state: Option<Json<TaskState>> = sqlx::query_scalar("SELECT state FROM tasks WHERE id = $1 FOR UPDATE")
+            .bind(&id.0)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let Json(mut task) = state.ok_or_else(|| StateError::TaskNotFound(id.to_string()))?;
+
+        let previous = task.clone();
+        f(&mut task);
+        sqlx::query("UPDATE tasks SET status = $2, owner = $3, state = $4, updated_at = $5 WHERE id = $1")
+            .bind(&id.0)
+            .bind(status_column(&task.status))
+            .bind(&task.metadata.owner)
+            .bind(Json(&task))
+            .bind(task.updated_at)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok((previous, task))
+    }
+
+    async fn delete(&self, id: &TaskId) -> Result<(), StateError> {
+        let result = sqlx::query("DELETE FROM tasks WHERE id = $1")
+            .bind(&id.0)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(StateError::TaskNotFound(id.to_string()));
+        }
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<TaskState>, StateError> {
+        let states: Vec<Json<TaskState>> = sqlx::query_scalar("SELECT state FROM tasks ORDER BY created_at, id")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(states.into_iter().map(|Json(task)| task).collect())
+    }
+
+    async fn create_many(&self, tasks: Vec<TaskState>) -> Result<(), StateError> {
+        // Dropping the transaction on error rolls back any rows already inserted
+        let mut tx = self.pool.begin().await?;
+        for task in &tasks {
+            Self::insert(&mut tx, task).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn replace_all(&self, tasks: HashMap<TaskId, TaskState>) -> Result<(), StateError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM tasks").execute(&mut *tx).await?;
+        for task in tasks.values() {
+            Self::insert(&mut tx, task).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use crate::state::error::StateError;
+use crate::state::types::{TaskId, TaskState, TaskStatus};
+
+/// Persistence backend for task state used by `StateManager`
+#[async_trait]
+pub trait StateStore: fmt::Debug + Send + Sync {
+    /// Insert a new task, failing with `TaskAlreadyExists` if the id is taken
+    async fn create(&self, task: TaskState) -> Result<(), StateError>;
+
+    async fn get(&self, id: &TaskId) -> Result<TaskState, StateError>;
+
+    /// Set a task's status and bump its `updated_at`
+    async fn update_status(&self, id: &TaskId, status: TaskStatus) -> Result<(), StateError>;
+
+    /// Apply `f` to a stored task atomically, returning the task as it was before and
+    /// after the change. The closure's lifetime is spelled out so `async_trait` keeps it
+    /// higher-ranked instead of tying it to `&self`.
+    async fn update(
+        &self,
+        id: &TaskId,
+        f: Box<dyn for<'t> FnOnce(&'t mut TaskState) + Send>,
+    ) -> Result<(TaskState, TaskState), StateError>;
+
+    async fn delete(&self, id: &TaskId) -> Result<(), StateError>;
+
+    async fn list(&self) -> Result<Vec<TaskState>, StateError>;
+
+    /// Insert all `tasks` or none of them
+    async fn create_many(&self, tasks: Vec<TaskState>) -> Result<(), StateError>;
+
+    /// Replace every stored task with `tasks`
+    async fn replace_all(&self, tasks: HashMap<TaskId, TaskState>) -> Result<(), StateError>;
+}
+
+/// Process-local store; the default backend for `StateManager`
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStateStore {
+    states: Arc<RwLock<HashMap<TaskId, TaskState>>>,
+}
+
+impl MemoryStateStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl StateStore for MemoryStateStore {
+    async fn create(&self, task: TaskState) -> Result<(), StateError> {
+        let mut states = self.states.write().await;
+        if states.contains_key(&task.id) {
+            return Err(StateError::TaskAlreadyExists(task.id.to_string()));
+        }
+        states.insert(task.id.clone(), task);
+        Ok(())
+    }
+
+    async fn get(&self, id: &TaskId) -> Result<TaskState, StateError> {
+        let states = self.states.read().await;
+        states
+            .get(id)
+            .cloned()
+            .ok_or_else(|| StateError::TaskNotFound(id.to_string()))
+    }
+
+    async fn update_status(&self, id: &TaskId, status: TaskStatus) -> Result<(), StateError> {
+        let mut states = self.states.write().await;
+        if let Some(task) = states.get_mut(id) {
+            task.set_status(status);
+            Ok(())
+        } else {
+            Err(StateError::TaskNotFound(id.to_string()))
+        }
+    }
+
+    async fn update(
+        &self,
+        id: &TaskId,
+        f: Box<dyn for<'t> FnOnce(&'t mut TaskState) + Send>,
+    ) -> Result<(TaskState, TaskState), StateError> {
+        let mut states = self.states.write().await;
+        let task = states
+            .get_mut(id)
+            .ok_or_else(|| StateError::TaskNotFound(id.to_string()))?;
+        let previous = task.clone();
+        f(task);
+        Ok((previous, task.clone()))
+    }
+
+    async fn delete(&self, id: &TaskId) -> Result<(), StateError> {
+        let mut states = self.states.write().await;
+        if states.remove(id).is_some() {
+            Ok(())
+        } else {
+            Err(StateError::TaskNotFound(id.to_string()))
+        }
+    }
+
+    async fn list(&self) -> Result<Vec<TaskState>, StateError> {
+        let states = self.states.read().await;
+        Ok(states.values().cloned().collect())
+    }
+
+    async fn create_many(&self, tasks: Vec<TaskState>) -> Result<(), StateError> {
+        let mut states = self.states.write().await;
+
+        let mut batch_ids = HashSet::new();
+        for task in &tasks {
+            if states.contains_key(&task.id) || !batch_ids.insert(task.id.clone()) {
+                return Err(StateError::TaskAlreadyExists(task.id.to_string()));
+            }
+        }
+
+        for task in tasks {
+            states.insert(task.id.clone(), task);
+        }
+        Ok(())
+    }
+
+    async fn replace_all(&self, tasks: HashMap<TaskId, TaskState>) -> Result<(), StateError> {
+        let mut states = self.states.write().await;
+        *states = tasks;
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@ mod tests {
     use std::time::Duration;
 
     use crate::state::error::StateError;
-    use crate::state::types::{TaskId, TaskState, TaskStatus, TaskMetadata};
+    use crate::state::types::{TaskId, TaskState, TaskStatus, TaskMetadata, StateSnapshot, SNAPSHOT_VERSION};
     use crate::state::StateManager;
 
     #[tokio::test]
@@ -18,6 +18,20 @@ mod tests {
         assert_eq!(task.status, TaskStatus::Pending);
     }
 
+    #[test]
+    fn test_task_id_from_name() {
+        let id = TaskId::from_name("Build & Deploy: API v2!");
+        let (slug, suffix) = id.0.rsplit_once('-').unwrap();
+        assert_eq!(slug, "build-deploy-api-v2");
+        assert_eq!(suffix.len(), 8);
+        assert!(suffix.chars().all(|c| c.is_ascii_hexdigit()));
+
+        assert_eq!(TaskId::from_name("Build & Deploy: API v2!"), id);
+        assert_ne!(TaskId::from_name("build-deploy-api-v2"), id);
+        assert!(TaskId::from_name("  ???  ").0.starts_with("task-"));
+        assert!(TaskId::from_name("Über/../täsk").0.starts_with("ber-t-sk-"));
+    }
+
     #[tokio::test]
     async fn test_task_metadata() {
         let id = "test-task-1";
@@ -34,6 +48,7 @@ mod tests {
             description: Some("Test Description".to_string()),
             owner: "test-owner".to_string(),
             additional_info: HashMap::new(),
+            working_dir: None,
         };
 
         assert_eq!(task.metadata.priority, 1);
@@ -197,4 +212,69 @@ mod tests {
         
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_load_current_snapshot() -> Result<(), StateError> {
+        let manager = StateManager::new();
+        manager.create_task(TaskState::new(TaskId::new("snap-task"))).await?;
+
+        let bytes = serde_json::to_vec(&manager.take_snapshot().await?)?;
+        let snapshot = StateSnapshot::load(&bytes)?;
+
+        assert_eq!(snapshot.version, SNAPSHOT_VERSION);
+        assert!(snapshot.tasks.contains_key(&TaskId::new("snap-task")));
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_unversioned_snapshot_migrates() -> Result<(), StateError> {
+        let legacy = serde_json::json!({
+            "tasks": {},
+            "timestamp": "2024-01-01T00:00:00Z"
+        });
+
+        let snapshot = StateSnapshot::load(legacy.to_string().as_bytes())?;
+        assert_eq!(snapshot.version, SNAPSHOT_VERSION);
+        assert!(snapshot.tasks.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_task_status_snake_case_wire_format() -> Result<(), StateError> {
+        assert_eq!(serde_json::to_string(&TaskStatus::Running)?, "\"running\"");
+        for status in TaskStatus::ALL {
+            assert_eq!(serde_json::to_value(&status)?, status.as_str());
+            assert_eq!(status.as_str().parse::<TaskStatus>()?, status);
+        }
+        assert!("Unknown".parse::<TaskStatus>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_task_status_accepts_legacy_names() -> Result<(), StateError> {
+        assert_eq!(serde_json::from_str::<TaskStatus>("\"Cancelled\"")?, TaskStatus::Cancelled);
+        assert_eq!("Completed".parse::<TaskStatus>()?, TaskStatus::Completed);
+
+        // A task written before the rename still loads
+        let mut legacy = serde_json::to_value(TaskState::new(TaskId::new("legacy")))?;
+        legacy["status"] = "Failed".into();
+        let task: TaskState = serde_json::from_value(legacy)?;
+        assert_eq!(task.status, TaskStatus::Failed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_unknown_snapshot_version_fails() {
+        let future = serde_json::json!({
+            "version": SNAPSHOT_VERSION + 1,
+            "tasks": {},
+            "timestamp": "2024-01-01T00:00:00Z"
+        });
+
+        let err = StateSnapshot::load(future.to_string().as_bytes()).unwrap_err();
+        assert!(matches!(
+            err,
+            StateError::IncompatibleSnapshot { found, expected } if found == SNAPSHOT_VERSION + 1 && expected == SNAPSHOT_VERSION
+        ));
+    }
 }
@@ -1,11 +1,15 @@
 use std::collections::HashMap;
 use std::fmt;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::time::Duration;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use anyhow::Error;
 
+use crate::util::stable_hash;
+use crate::state::error::StateError;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TaskId(pub String);
 
@@ -13,6 +17,24 @@ impl TaskId {
     pub fn new(id: &str) -> Self {
         TaskId(id.to_string())
     }
+
+    /// Deterministic id for a task name: the name slugified to lowercase ASCII letters,
+    /// digits and hyphens, followed by a short hash of the original name so names that
+    /// slugify alike (`Build App`, `build_app`) still get distinct ids
+    pub fn from_name(name: &str) -> Self {
+        let mut slug = String::with_capacity(name.len());
+        for c in name.chars() {
+            if c.is_ascii_alphanumeric() {
+                slug.push(c.to_ascii_lowercase());
+            } else if !slug.is_empty() && !slug.ends_with('-') {
+                slug.push('-');
+            }
+        }
+        let slug = slug.trim_end_matches('-');
+        let slug = if slug.is_empty() { "task" } else { slug };
+
+        TaskId(format!("{}-{}", slug, &stable_hash(name)[..8]))
+    }
 }
 
 impl fmt::Display for TaskId {
@@ -29,15 +51,56 @@ impl FromStr for TaskId {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Serialized as snake_case (`pending`, `running`, ...). The variant names written by
+/// older snapshots are still accepted when deserializing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TaskStatus {
+    #[serde(alias = "Pending")]
     Pending,
+    #[serde(alias = "Running")]
     Running,
+    #[serde(alias = "Completed")]
     Completed,
+    #[serde(alias = "Failed")]
     Failed,
+    #[serde(alias = "Cancelled")]
     Cancelled,
 }
 
+impl TaskStatus {
+    pub const ALL: [TaskStatus; 5] = [
+        TaskStatus::Pending,
+        TaskStatus::Running,
+        TaskStatus::Completed,
+        TaskStatus::Failed,
+        TaskStatus::Cancelled,
+    ];
+
+    /// Wire name of the status, as serialized
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Pending => "pending",
+            TaskStatus::Running => "running",
+            TaskStatus::Completed => "completed",
+            TaskStatus::Failed => "failed",
+            TaskStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl FromStr for TaskStatus {
+    type Err = StateError;
+
+    /// Parse a wire name, also accepting the legacy variant names (`Pending`, ...)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TaskStatus::ALL
+            .into_iter()
+            .find(|status| status.as_str() == s || format!("{:?}", status) == s)
+            .ok_or_else(|| StateError::InvalidState(format!("Unknown task status: {}", s)))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TaskMetadata {
     pub name: String,
@@ -48,6 +111,9 @@ pub struct TaskMetadata {
     pub priority: i32,
     pub tags: Vec<String>,
     pub additional_info: HashMap<String, String>,
+    /// Directory to run the task command in; relative paths resolve against the build manager's working dir
+    #[serde(default)]
+    pub working_dir: Option<PathBuf>,
 }
 
 impl Default for TaskMetadata {
@@ -61,10 +127,22 @@ impl Default for TaskMetadata {
             priority: 0,
             tags: Vec::new(),
             additional_info: HashMap::new(),
+            working_dir: None,
         }
     }
 }
 
+/// Number of status transitions kept per task; older ones are dropped first
+pub const MAX_STATUS_HISTORY: usize = 50;
+
+/// A single status change recorded in `TaskState::history`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusTransition {
+    pub from: TaskStatus,
+    pub to: TaskStatus,
+    pub at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TaskState {
     pub id: TaskId,
@@ -72,6 +150,9 @@ pub struct TaskState {
     pub metadata: TaskMetadata,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Recent status transitions, oldest first, capped at `MAX_STATUS_HISTORY`
+    #[serde(default)]
+    pub history: Vec<StatusTransition>,
 }
 
 impl TaskState {
@@ -83,8 +164,25 @@ impl TaskState {
             metadata: TaskMetadata::default(),
             created_at: now,
             updated_at: now,
+            history: Vec::new(),
         }
     }
+
+    /// Change the status, bump `updated_at` and record the transition
+    pub fn set_status(&mut self, status: TaskStatus) {
+        let now = Utc::now();
+        self.history.push(StatusTransition {
+            from: self.status.clone(),
+            to: status.clone(),
+            at: now,
+        });
+        if self.history.len() > MAX_STATUS_HISTORY {
+            let excess = self.history.len() - MAX_STATUS_HISTORY;
+            self.history.drain(..excess);
+        }
+        self.status = status;
+        self.updated_at = now;
+    }
 }
 
 impl Default for TaskState {
@@ -94,8 +192,42 @@ impl Default for TaskState {
     }
 }
 
+/// Current `StateSnapshot` schema version. Snapshots written before versioning was
+/// introduced deserialize as version 0 and share the version 1 layout.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateSnapshot {
+    #[serde(default)]
+    pub version: u32,
     pub tasks: HashMap<TaskId, TaskState>,
     pub timestamp: DateTime<Utc>,
 }
+
+impl StateSnapshot {
+    pub fn new(tasks: HashMap<TaskId, TaskState>) -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            tasks,
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Deserialize a snapshot, migrating known older versions and rejecting unknown ones
+    pub fn load(bytes: &[u8]) -> Result<Self, StateError> {
+        let value: serde_json::Value = serde_json::from_slice(bytes)?;
+        let found = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+
+        if found > SNAPSHOT_VERSION as u64 {
+            return Err(StateError::IncompatibleSnapshot {
+                found: found as u32,
+                expected: SNAPSHOT_VERSION,
+            });
+        }
+
+        let mut snapshot: StateSnapshot = serde_json::from_value(value)?;
+        // Version 0 only lacked the version field, so it upgrades in place
+        snapshot.version = SNAPSHOT_VERSION;
+        Ok(snapshot)
+    }
+}
@@ -0,0 +1,219 @@
+use anyhow::{Context, Result, anyhow};
+use clap::Parser;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use crate::cli::style;
+use crate::inference::InferenceClient;
+use super::DryRun;
+
+#[derive(Parser, Debug)]
+pub struct BatchArgs {
+    /// File with one project prompt per line; blank lines are skipped
+    #[clap(long)]
+    prompts: PathBuf,
+
+    /// Maximum number of projects generated at once
+    #[clap(long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+    parallel: u16,
+
+    /// Directory the generated projects are written under
+    #[clap(long, default_value = "build")]
+    output_dir: PathBuf,
+}
+
+/// Result of generating the project for one prompt
+#[derive(Debug)]
+pub struct BatchOutcome {
+    pub prompt: String,
+    /// Directory of the generated project, or why generation failed
+    pub result: Result<PathBuf, String>,
+}
+
+pub async fn handle_batch(args: BatchArgs, dry_run: DryRun) -> Result<()> {
+    handle_batch_with(args, dry_run, || {
+        println!("Initializing inference client...");
+        InferenceClient::new()
+    })
+    .await
+}
+
+/// `handle_batch` with the inference client built by `connect`, which is only called
+/// once the run is known to need the model
+async fn handle_batch_with(
+    args: BatchArgs,
+    dry_run: DryRun,
+    connect: impl FnOnce() -> Result<InferenceClient>,
+) -> Result<()> {
+    if dry_run.enabled() {
+        let prompts = read_prompts(&args.prompts)?;
+        dry_run.report(&format!(
+            "generate {} project(s) under {}, {} at a time:",
+            prompts.len(), args.output_dir.display(), args.parallel
+        ));
+        for prompt in prompts {
+            println!("  - {}", prompt);
+        }
+        return Ok(());
+    }
+    let client = Arc::new(connect()?);
+    run_batch(args, client).await
+}
+
+async fn run_batch(args: BatchArgs, client: Arc<InferenceClient>) -> Result<()> {
+    let prompts = read_prompts(&args.prompts)?;
+    println!("Generating {} project(s), {} at a time", prompts.len(), args.parallel);
+
+    let outcomes = generate_batch(client, prompts, args.parallel as usize, &args.output_dir).await;
+    print!("{}", summary_table(&outcomes));
+
+    let failed = outcomes.iter().filter(|outcome| outcome.result.is_err()).count();
+    if failed > 0 {
+        return Err(anyhow!("{} of {} generations failed", failed, outcomes.len()));
+    }
+    Ok(())
+}
+
+fn read_prompts(path: &Path) -> Result<Vec<String>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read prompts file: {}", path.display()))?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Generate a project per prompt with at most `parallel` generations in flight,
+/// returning the outcomes in prompt order
+pub async fn generate_batch(
+    client: Arc<InferenceClient>,
+    prompts: Vec<String>,
+    parallel: usize,
+    output_dir: &Path,
+) -> Vec<BatchOutcome> {
+    let semaphore = Arc::new(Semaphore::new(parallel.max(1)));
+    let mut tasks = JoinSet::new();
+    for (index, prompt) in prompts.iter().cloned().enumerate() {
+        let client = Arc::clone(&client);
+        let semaphore = Arc::clone(&semaphore);
+        let output_dir = output_dir.to_path_buf();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.expect("batch semaphore is never closed");
+            let result = client
+                .generate_project_in(&prompt, &output_dir)
+                .await
+                .map_err(|e| format!("{:#}", e));
+            (index, result)
+        });
+    }
+
+    let mut results: Vec<Option<Result<PathBuf, String>>> = prompts.iter().map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, result)) => results[index] = Some(result),
+            Err(e) => tracing::error!("Batch generation task panicked: {}", e),
+        }
+    }
+
+    prompts
+        .into_iter()
+        .zip(results)
+        .map(|(prompt, result)| BatchOutcome {
+            prompt,
+            result: result.unwrap_or_else(|| Err("generation task panicked".to_string())),
+        })
+        .collect()
+}
+
+/// One row per prompt with its status and output directory or error
+fn summary_table(outcomes: &[BatchOutcome]) -> String {
+    let palette = style::palette();
+    let mut table = format!("\n{}\n", palette.heading(&format!("{:<4} {:<8} {}", "#", "Status", "Output")));
+    for (index, outcome) in outcomes.iter().enumerate() {
+        let (status, detail) = match &outcome.result {
+            Ok(dir) => (palette.success(&format!("{:<8}", "ok")), dir.display().to_string()),
+            Err(e) => (palette.error(&format!("{:<8}", "failed")), e.clone()),
+        };
+        table.push_str(&format!("{:<4} {} {}\n", index + 1, status, detail));
+    }
+    let succeeded = outcomes.iter().filter(|outcome| outcome.result.is_ok()).count();
+    table.push_str(&format!("\n{} succeeded, {} failed\n", succeeded, outcomes.len() - succeeded));
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::test_server::{MockResponse, MockServer};
+
+    #[tokio::test]
+    async fn test_batch_generates_every_prompt() -> Result<()> {
+        let responses = (1..=4)
+            .map(|n| {
+                let config = serde_json::json!({
+                    "project_name": format!("batch-project-{}", n),
+                    "description": "Generated in a batch",
+                    "language": "Rust",
+                    "project_type": "Tool",
+                    "directory_structure": { "src": ["main.rs"] }
+                });
+                MockResponse::completion(&config.to_string())
+            })
+            .collect();
+        let server = MockServer::start(responses).await;
+        let client = Arc::new(InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?);
+
+        let temp_dir = tempfile::tempdir()?;
+        let prompts = temp_dir.path().join("prompts.txt");
+        std::fs::write(&prompts, "First tool\nSecond tool\n\nThird tool\nFourth tool\n")?;
+        let output_dir = temp_dir.path().join("out");
+        let args = BatchArgs::try_parse_from([
+            "batch",
+            "--prompts", prompts.to_str().unwrap(),
+            "--parallel", "2",
+            "--output-dir", output_dir.to_str().unwrap(),
+        ])?;
+
+        run_batch(args, client).await?;
+
+        assert_eq!(server.requests().len(), 4);
+        let mut generated: Vec<String> = std::fs::read_dir(&output_dir)?
+            .map(|entry| entry.map(|entry| entry.file_name().to_string_lossy().into_owned()))
+            .collect::<std::io::Result<_>>()?;
+        generated.sort();
+        assert_eq!(generated.len(), 4);
+        for (n, name) in (1..=4).zip(&generated) {
+            assert!(name.starts_with(&format!("batch-project-{}_", n)), "unexpected project dir {}", name);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_batch_dry_run_sends_no_requests() -> Result<()> {
+        let server = MockServer::start(vec![MockResponse::completion("{}")]).await;
+        let temp_dir = tempfile::tempdir()?;
+        let prompts = temp_dir.path().join("prompts.txt");
+        std::fs::write(&prompts, "First tool\nSecond tool\n")?;
+        let output_dir = temp_dir.path().join("out");
+        let args = BatchArgs::try_parse_from([
+            "batch", "--prompts", prompts.to_str().unwrap(), "--output-dir", output_dir.to_str().unwrap(),
+        ])?;
+
+        handle_batch_with(args, DryRun(true), || {
+            InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")
+        })
+        .await?;
+        assert!(server.requests().is_empty());
+        assert!(!output_dir.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parallel_must_be_positive() {
+        assert!(BatchArgs::try_parse_from(["batch", "--prompts", "p.txt", "--parallel", "0"]).is_err());
+    }
+}
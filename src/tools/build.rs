@@ -2,6 +2,7 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use crate::tools::{Tool, ExecutableTool};
 use async_trait::async_trait;
+use std::path::Path;
 use tokio::process::Command;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -70,6 +71,83 @@ impl Default for BuildTool {
     }
 }
 
+/// Project ecosystems the build tool knows how to drive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectKind {
+    Cargo,
+    Npm,
+    Python,
+}
+
+impl ProjectKind {
+    /// Detect the project kind from marker files in `dir`.
+    /// Directories without a recognized marker are treated as Python projects.
+    pub fn detect(dir: &Path) -> Self {
+        if dir.join("Cargo.toml").exists() {
+            ProjectKind::Cargo
+        } else if dir.join("package.json").exists() {
+            ProjectKind::Npm
+        } else {
+            ProjectKind::Python
+        }
+    }
+}
+
+/// What running a build command amounts to for a given project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildAction {
+    /// Run a program with arguments in the working directory
+    Run { program: String, args: Vec<String> },
+    /// Remove the listed directories relative to the working directory
+    Remove(Vec<String>),
+}
+
+impl BuildAction {
+    fn run(program: &str, args: &[&str]) -> Self {
+        BuildAction::Run {
+            program: program.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    fn remove(dirs: &[&str]) -> Self {
+        BuildAction::Remove(dirs.iter().map(|d| d.to_string()).collect())
+    }
+}
+
+impl BuildTool {
+    /// Resolve a build command into the action to perform in `working_dir`
+    pub fn plan(command: &str, working_dir: &Path) -> Result<BuildAction, String> {
+        let action = match (ProjectKind::detect(working_dir), command) {
+            (ProjectKind::Cargo, "build") => BuildAction::run("cargo", &["build"]),
+            (ProjectKind::Cargo, "test") => BuildAction::run("cargo", &["test"]),
+            (ProjectKind::Cargo, "dev") => BuildAction::run("cargo", &["run"]),
+            (ProjectKind::Cargo, "clean") => BuildAction::run("cargo", &["clean"]),
+
+            (ProjectKind::Npm, "build") => BuildAction::run("npm", &["run", "build"]),
+            (ProjectKind::Npm, "test") => BuildAction::run("npm", &["test"]),
+            (ProjectKind::Npm, "dev") => BuildAction::run("npm", &["run", "dev"]),
+            (ProjectKind::Npm, "clean") => BuildAction::remove(&["node_modules", "dist"]),
+
+            (ProjectKind::Python, "build") => {
+                // Check for setup.py or requirements.txt
+                if working_dir.join("setup.py").exists() {
+                    BuildAction::run("python", &["setup.py", "build"])
+                } else {
+                    BuildAction::run("pip", &["install", "-r", "requirements.txt"])
+                }
+            }
+            (ProjectKind::Python, "test") => BuildAction::run("python", &["-m", "pytest"]),
+            (ProjectKind::Python, "dev") => BuildAction::run("python", &["-m", "flask", "run", "--debug"]),
+            (ProjectKind::Python, "clean") => BuildAction::remove(&["build", "__pycache__", ".pytest_cache"]),
+
+            _ => return Err(format!("Unknown command: {}", command)),
+        };
+
+        Ok(action)
+    }
+}
+
 #[async_trait]
 impl ExecutableTool for BuildTool {
     async fn execute(&self, arguments: &str) -> Result<String, String> {
@@ -82,72 +160,49 @@ impl ExecutableTool for BuildTool {
             .ok_or("Missing working_directory parameter")?;
 
         // Execute the appropriate build command based on the project type
-        match command {
-            "build" => {
-                // Check for setup.py or requirements.txt
-                if std::path::Path::new(&format!("{}/setup.py", working_dir)).exists() {
-                    let output = Command::new("python")
-                        .args(&["setup.py", "build"])
-                        .current_dir(working_dir)
-                        .output()
-                        .await
-                        .map_err(|e| format!("Failed to execute build command: {}", e))?;
-                    if output.status.success() {
-                        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-                    } else {
-                        Err(String::from_utf8_lossy(&output.stderr).to_string())
-                    }
-                } else {
-                    let output = Command::new("pip")
-                        .args(&["install", "-r", "requirements.txt"])
-                        .current_dir(working_dir)
-                        .output()
-                        .await
-                        .map_err(|e| format!("Failed to execute build command: {}", e))?;
-                    if output.status.success() {
-                        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-                    } else {
-                        Err(String::from_utf8_lossy(&output.stderr).to_string())
-                    }
-                }
-            }
-            "test" => {
-                let output = Command::new("python")
-                    .args(&["-m", "pytest"])
-                    .current_dir(working_dir)
-                    .output()
-                    .await
-                    .map_err(|e| format!("Failed to execute test command: {}", e))?;
-                if output.status.success() {
-                    Ok(String::from_utf8_lossy(&output.stdout).to_string())
-                } else {
-                    Err(String::from_utf8_lossy(&output.stderr).to_string())
-                }
-            }
-            "dev" => {
-                let output = Command::new("python")
-                    .args(&["-m", "flask", "run", "--debug"])
+        match Self::plan(command, Path::new(working_dir))? {
+            BuildAction::Run { program, args } => {
+                let output = Command::new(&program)
+                    .args(&args)
                     .current_dir(working_dir)
                     .output()
                     .await
-                    .map_err(|e| format!("Failed to execute dev command: {}", e))?;
+                    .map_err(|e| format!("Failed to execute {} command: {}", command, e))?;
                 if output.status.success() {
                     Ok(String::from_utf8_lossy(&output.stdout).to_string())
                 } else {
                     Err(String::from_utf8_lossy(&output.stderr).to_string())
                 }
             }
-            "clean" => {
+            BuildAction::Remove(dirs) => {
                 // Remove build artifacts
-                let _ = std::fs::remove_dir_all(format!("{}/build", working_dir));
-                let _ = std::fs::remove_dir_all(format!("{}/__pycache__", working_dir));
-                let _ = std::fs::remove_dir_all(format!("{}/.pytest_cache", working_dir));
+                for dir in dirs {
+                    let _ = std::fs::remove_dir_all(Path::new(working_dir).join(dir));
+                }
                 Ok("Clean completed successfully".to_string())
             }
-            _ => Err(format!("Unknown command: {}", command)),
         }
     }
 
+    fn validate_arguments(&self, arguments: &str) -> Result<(), String> {
+        let args: serde_json::Value = serde_json::from_str(arguments)
+            .map_err(|e| format!("Failed to parse arguments: {}", e))?;
+
+        let command = args["command"].as_str()
+            .ok_or("Missing command parameter")?;
+        let allowed = &self.parameters.properties.command.allowed_values;
+        if !allowed.iter().any(|value| value == command) {
+            return Err(format!("Unknown command '{}' (expected one of: {})", command, allowed.join(", ")));
+        }
+
+        let working_dir = args["working_directory"].as_str()
+            .ok_or("Missing working_directory parameter")?;
+        if !Path::new(working_dir).is_dir() {
+            return Err(format!("Working directory does not exist: {}", working_dir));
+        }
+        Ok(())
+    }
+
     fn get_tool_definition(&self) -> Tool {
         Tool {
             name: self.name.clone(),
@@ -157,19 +212,81 @@ impl ExecutableTool for BuildTool {
     }
 
     fn get_short_description(&self) -> String {
-        "Execute build commands (build, test, dev, clean) for Python projects".to_string()
+        "Execute build commands (build, test, dev, clean) for Rust, npm, and Python projects".to_string()
     }
 
     fn get_long_description(&self) -> String {
-        r#"This tool executes build-related commands for Python projects. Available commands:
+        r#"This tool executes build-related commands for Rust, npm, and Python projects. Available commands:
         - build: Install dependencies and build the project
-        - test: Run the test suite using pytest
-        - dev: Start the development server in debug mode
+        - test: Run the project's test suite
+        - dev: Start the project in development mode
         - clean: Remove build artifacts and cache directories
         
-        The tool automatically detects the project structure and uses appropriate build commands.
+        The tool detects the project type from marker files and uses appropriate build commands.
+        For Cargo projects (Cargo.toml), it uses cargo build/test/run/clean.
+        For npm projects (package.json), it uses npm scripts.
         For pip-based projects, it uses requirements.txt.
         For setuptools projects, it uses setup.py.
         "#.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn run(program: &str, args: &[&str]) -> BuildAction {
+        BuildAction::run(program, args)
+    }
+
+    #[test]
+    fn test_plan_cargo_project() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"demo\"\n").unwrap();
+
+        assert_eq!(ProjectKind::detect(dir.path()), ProjectKind::Cargo);
+        assert_eq!(BuildTool::plan("build", dir.path()).unwrap(), run("cargo", &["build"]));
+        assert_eq!(BuildTool::plan("test", dir.path()).unwrap(), run("cargo", &["test"]));
+        assert_eq!(BuildTool::plan("dev", dir.path()).unwrap(), run("cargo", &["run"]));
+        assert_eq!(BuildTool::plan("clean", dir.path()).unwrap(), run("cargo", &["clean"]));
+    }
+
+    #[test]
+    fn test_plan_npm_and_python_projects() {
+        let npm_dir = tempdir().unwrap();
+        std::fs::write(npm_dir.path().join("package.json"), "{}").unwrap();
+        assert_eq!(BuildTool::plan("build", npm_dir.path()).unwrap(), run("npm", &["run", "build"]));
+
+        let python_dir = tempdir().unwrap();
+        std::fs::write(python_dir.path().join("requirements.txt"), "flask\n").unwrap();
+        assert_eq!(
+            BuildTool::plan("build", python_dir.path()).unwrap(),
+            run("pip", &["install", "-r", "requirements.txt"])
+        );
+        assert_eq!(BuildTool::plan("test", python_dir.path()).unwrap(), run("python", &["-m", "pytest"]));
+    }
+
+    #[test]
+    fn test_validate_arguments() {
+        let tool = BuildTool::default();
+        let dir = tempdir().unwrap();
+        let args = |command: &str, dir: &Path| {
+            serde_json::json!({ "command": command, "working_directory": dir }).to_string()
+        };
+
+        assert!(tool.validate_arguments(&args("test", dir.path())).is_ok());
+        assert!(tool.validate_arguments(&args("deploy", dir.path())).unwrap_err().contains("Unknown command 'deploy'"));
+        assert!(tool
+            .validate_arguments(&args("build", &dir.path().join("missing")))
+            .unwrap_err()
+            .contains("does not exist"));
+        assert!(tool.validate_arguments(r#"{"command": "build"}"#).is_err());
+    }
+
+    #[test]
+    fn test_plan_unknown_command() {
+        let dir = tempdir().unwrap();
+        assert!(BuildTool::plan("deploy", dir.path()).is_err());
+    }
+}
@@ -0,0 +1,180 @@
+use anyhow::{Context, Result, anyhow, bail};
+use clap::Parser;
+use std::path::{Path, PathBuf};
+use toml_edit::DocumentMut;
+
+use super::DryRun;
+
+#[derive(Parser, Debug)]
+pub struct AddDependencyArgs {
+    /// Directory of the generated project
+    #[clap(long)]
+    dir: PathBuf,
+
+    /// Name of the crate or package to add
+    #[clap(long)]
+    name: String,
+
+    /// Version requirement for the dependency
+    #[clap(long)]
+    version: String,
+
+    /// Add as a development dependency
+    #[clap(long)]
+    dev: bool,
+}
+
+pub async fn handle_add_dependency(args: AddDependencyArgs, dry_run: DryRun) -> Result<()> {
+    if dry_run.enabled() {
+        let section = if args.dev { "dev-dependencies" } else { "dependencies" };
+        dry_run.report(&format!(
+            "add {} = \"{}\" to the [{}] of the manifest in {}",
+            args.name, args.version, section, args.dir.display()
+        ));
+        return Ok(());
+    }
+    let manifest = add_dependency(&args.dir, &args.name, &args.version, args.dev)?;
+    println!("Added {} = \"{}\" to {}", args.name, args.version, manifest.display());
+    Ok(())
+}
+
+/// Insert a dependency into the project's `Cargo.toml` or `package.json`, returning the
+/// path of the edited manifest. Fails if the dependency is already declared.
+pub fn add_dependency(project_dir: &Path, name: &str, version: &str, dev: bool) -> Result<PathBuf> {
+    let cargo_toml = project_dir.join("Cargo.toml");
+    let package_json = project_dir.join("package.json");
+
+    if cargo_toml.exists() {
+        add_cargo_dependency(&cargo_toml, name, version, dev)?;
+        Ok(cargo_toml)
+    } else if package_json.exists() {
+        add_npm_dependency(&package_json, name, version, dev)?;
+        Ok(package_json)
+    } else {
+        Err(anyhow!("No Cargo.toml or package.json found in {}", project_dir.display()))
+    }
+}
+
+fn add_cargo_dependency(path: &Path, name: &str, version: &str, dev: bool) -> Result<()> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut manifest = content.parse::<DocumentMut>()
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    let section = if dev { "dev-dependencies" } else { "dependencies" };
+    let table = manifest
+        .entry(section)
+        .or_insert(toml_edit::table())
+        .as_table_like_mut()
+        .ok_or_else(|| anyhow!("[{}] in {} is not a table", section, path.display()))?;
+
+    if table.contains_key(name) {
+        bail!("Dependency '{}' already exists in [{}]", name, section);
+    }
+    table.insert(name, toml_edit::value(version));
+
+    std::fs::write(path, manifest.to_string())
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn add_npm_dependency(path: &Path, name: &str, version: &str, dev: bool) -> Result<()> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut manifest: serde_json::Value = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+    let section = if dev { "devDependencies" } else { "dependencies" };
+    let table = manifest
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("{} is not a JSON object", path.display()))?
+        .entry(section)
+        .or_insert_with(|| serde_json::json!({}))
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("\"{}\" in {} is not an object", section, path.display()))?;
+
+    if table.contains_key(name) {
+        bail!("Dependency '{}' already exists in \"{}\"", name, section);
+    }
+    table.insert(name.to_string(), serde_json::Value::String(version.to_string()));
+
+    std::fs::write(path, serde_json::to_string_pretty(&manifest)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::build::BuildManager;
+    use crate::state::StateManager;
+
+    fn generate_rust_project(root: &Path) -> Result<PathBuf> {
+        let build_manager = BuildManager::new(StateManager::new(), root.to_path_buf());
+        let config = serde_json::json!({
+            "project_name": "dep-test",
+            "language": "Rust",
+            "dependencies": {
+                "production": { "serde": "1.0" },
+                "development": {}
+            }
+        });
+        Ok(build_manager.scaffold_project(&config.to_string())?.root)
+    }
+
+    #[test]
+    fn test_add_prod_and_dev_dependency_to_cargo_toml() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let project_dir = generate_rust_project(temp_dir.path())?;
+
+        add_dependency(&project_dir, "tokio", "1.0", false)?;
+        add_dependency(&project_dir, "tempfile", "3.8", true)?;
+
+        let manifest: toml::Value = std::fs::read_to_string(project_dir.join("Cargo.toml"))?.parse()?;
+        assert_eq!(manifest["dependencies"]["serde"].as_str(), Some("1.0"));
+        assert_eq!(manifest["dependencies"]["tokio"].as_str(), Some("1.0"));
+        assert_eq!(manifest["dev-dependencies"]["tempfile"].as_str(), Some("3.8"));
+        assert!(manifest["dependencies"].get("tempfile").is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_leaves_manifest_untouched() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let manifest = temp_dir.path().join("Cargo.toml");
+        std::fs::write(&manifest, "[package]\nname = \"demo\"\n")?;
+        let args = AddDependencyArgs::try_parse_from([
+            "add-dependency", "--dir", temp_dir.path().to_str().unwrap(), "--name", "serde", "--version", "1.0",
+        ])?;
+
+        handle_add_dependency(args, DryRun(true)).await?;
+        assert_eq!(std::fs::read_to_string(&manifest)?, "[package]\nname = \"demo\"\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_existing_dependency_fails() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let project_dir = generate_rust_project(temp_dir.path())?;
+
+        let err = add_dependency(&project_dir, "serde", "2.0", false).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_dependency_to_package_json() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        std::fs::write(temp_dir.path().join("package.json"), r#"{ "name": "web-app" }"#)?;
+
+        add_dependency(temp_dir.path(), "express", "^4.18.0", false)?;
+        add_dependency(temp_dir.path(), "jest", "^29.0.0", true)?;
+
+        let manifest: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(temp_dir.path().join("package.json"))?)?;
+        assert_eq!(manifest["dependencies"]["express"], "^4.18.0");
+        assert_eq!(manifest["devDependencies"]["jest"], "^29.0.0");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+use anyhow::{Context, Result};
+use clap::{Args, Parser, Subcommand};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::doc::types::{DocType, Documentation};
+use crate::doc::{DocumentationEngine, FileDocumentationEngine};
+use super::DryRun;
+
+#[derive(Parser, Debug, Clone)]
+pub struct DocArgs {
+    #[command(subcommand)]
+    command: DocCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum DocCommand {
+    /// Render a new document to a file
+    Create(DocFields),
+
+    /// Print a document's contents
+    Read {
+        /// Path of the document
+        #[arg(long)]
+        path: PathBuf,
+    },
+
+    /// Re-render an existing document
+    Update(DocFields),
+
+    /// Delete a document
+    Delete {
+        /// Path of the document
+        #[arg(long)]
+        path: PathBuf,
+    },
+
+    /// List the documents in a directory
+    List {
+        /// Directory to list
+        #[arg(long, default_value = ".")]
+        dir: PathBuf,
+    },
+}
+
+#[derive(Args, Debug, Clone)]
+struct DocFields {
+    /// Path of the document
+    #[arg(long)]
+    path: PathBuf,
+
+    /// Document title
+    #[arg(long)]
+    title: String,
+
+    /// Document type, which decides how it is rendered
+    #[arg(long = "type", value_enum, default_value = "markdown")]
+    doc_type: DocType,
+
+    /// Document body
+    #[arg(long, default_value = "")]
+    content: String,
+}
+
+impl DocFields {
+    fn into_doc(self) -> Documentation {
+        Documentation::new(self.title, self.content, self.doc_type, self.path, String::new())
+    }
+}
+
+pub async fn handle_doc(args: DocArgs, dry_run: DryRun) -> Result<()> {
+    run_doc(args, dry_run, &mut std::io::stdout()).await
+}
+
+/// Run a doc subcommand against a `FileDocumentationEngine` rooted at the current
+/// directory, writing its results to `out`. With `dry_run`, create, update and delete
+/// only report the file they would change.
+pub async fn run_doc(args: DocArgs, dry_run: DryRun, out: &mut dyn Write) -> Result<()> {
+    let engine = FileDocumentationEngine::new(PathBuf::from("."));
+    if dry_run.enabled() {
+        let action = match &args.command {
+            DocCommand::Create(fields) => format!("create {}", fields.path.display()),
+            DocCommand::Update(fields) => format!("update {}", fields.path.display()),
+            DocCommand::Delete { path } => format!("delete {}", path.display()),
+            DocCommand::Read { .. } | DocCommand::List { .. } => String::new(),
+        };
+        if !action.is_empty() {
+            writeln!(out, "[dry-run] Would {}", action)?;
+            return Ok(());
+        }
+    }
+    match args.command {
+        DocCommand::Create(fields) => {
+            let doc = fields.into_doc();
+            engine.create_doc(&doc).await
+                .with_context(|| format!("Failed to create {}", doc.path.display()))?;
+            writeln!(out, "Created {} ({})", doc.path.display(), doc.doc_type.as_str())?;
+        }
+        DocCommand::Read { path } => {
+            let doc = engine.read_doc(&path).await
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            write!(out, "{}", doc.content)?;
+        }
+        DocCommand::Update(fields) => {
+            let doc = fields.into_doc();
+            let path = doc.path.clone();
+            engine.update_doc(doc).await
+                .with_context(|| format!("Failed to update {}", path.display()))?;
+            writeln!(out, "Updated {}", path.display())?;
+        }
+        DocCommand::Delete { path } => {
+            engine.delete_doc(&path).await
+                .with_context(|| format!("Failed to delete {}", path.display()))?;
+            writeln!(out, "Deleted {}", path.display())?;
+        }
+        DocCommand::List { dir } => {
+            for path in list_docs(&engine.resolve_path(&dir))? {
+                writeln!(out, "{}", path.display())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Files directly inside `dir`, sorted by path
+fn list_docs(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut docs = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("Failed to list {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_file() {
+            docs.push(path);
+        }
+    }
+    docs.sort();
+    Ok(docs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn run(args: &[&str]) -> Result<String> {
+        let mut out = Vec::new();
+        run_doc(DocArgs::try_parse_from(std::iter::once("doc").chain(args.iter().copied()))?, DryRun::default(), &mut out).await?;
+        Ok(String::from_utf8(out)?)
+    }
+
+    #[tokio::test]
+    async fn test_doc_lifecycle() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let dir = temp_dir.path().to_str().unwrap();
+        let path = temp_dir.path().join("ARCHITECTURE.md");
+        let path = path.to_str().unwrap();
+
+        run(&["create", "--path", path, "--title", "Pipeline", "--type", "architecture", "--content", "Stages"]).await?;
+        let read = run(&["read", "--path", path]).await?;
+        assert!(read.starts_with("# Architecture: Pipeline\n"), "{}", read);
+
+        run(&["update", "--path", path, "--title", "Pipeline", "--content", "Plain body"]).await?;
+        assert_eq!(run(&["read", "--path", path]).await?, "Plain body");
+        assert_eq!(run(&["list", "--dir", dir]).await?, format!("{}\n", path));
+
+        run(&["delete", "--path", path]).await?;
+        assert!(run(&["list", "--dir", dir]).await?.is_empty());
+        assert!(run(&["delete", "--path", path]).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_doc_dry_run_changes_nothing() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("NOTES.md");
+        let args = DocArgs::try_parse_from(["doc", "create", "--path", path.to_str().unwrap(), "--title", "Notes"])?;
+
+        let mut out = Vec::new();
+        run_doc(args, DryRun(true), &mut out).await?;
+        assert_eq!(String::from_utf8(out)?, format!("[dry-run] Would create {}\n", path.display()));
+        assert!(!path.exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use tokio::process::Command;
+use crate::config::ExternalToolConfig;
+use crate::tools::{Tool, ExecutableTool};
+
+/// A tool declared in configuration that runs a shell command template.
+/// `{{key}}` placeholders are filled from the call's JSON arguments, shell-quoted, so
+/// templates must not quote placeholders themselves.
+#[derive(Debug, Clone)]
+pub struct ExternalCommandTool {
+    name: String,
+    description: String,
+    command: String,
+}
+
+impl ExternalCommandTool {
+    pub fn new(name: &str, description: &str, command: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            command: command.to_string(),
+        }
+    }
+
+    /// Fill the template's `{{key}}` placeholders from `arguments`. Values are
+    /// shell-quoted, so a placeholder wrapped in quotes in the template is rejected, as is
+    /// a placeholder with no matching argument.
+    fn render_command(&self, arguments: &serde_json::Value) -> Result<String, String> {
+        let mut rendered = String::new();
+        let mut missing: Vec<&str> = Vec::new();
+        let mut rest = self.command.as_str();
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start + 2..].find("}}") else {
+                break;
+            };
+            let key = &rest[start + 2..start + 2 + len];
+            let (before, after) = (&rest[..start], &rest[start + 4 + len..]);
+
+            let quote = before.chars().last().filter(|c| *c == '\'' || *c == '"');
+            if quote.is_some() && quote == after.chars().next() {
+                return Err(format!(
+                    "Placeholder {{{{{}}}}} in the {} command must not be quoted; values are shell-quoted when filled in",
+                    key, self.name
+                ));
+            }
+
+            rendered.push_str(before);
+            match arguments.get(key) {
+                Some(serde_json::Value::String(s)) => rendered.push_str(&shell_quote(s)),
+                Some(other) => rendered.push_str(&shell_quote(&other.to_string())),
+                None if !missing.contains(&key) => missing.push(key),
+                None => {}
+            }
+            rest = after;
+        }
+        rendered.push_str(rest);
+
+        if !missing.is_empty() {
+            return Err(format!("Missing arguments for the {} command: {}", self.name, missing.join(", ")));
+        }
+        Ok(rendered)
+    }
+}
+
+impl From<&ExternalToolConfig> for ExternalCommandTool {
+    fn from(config: &ExternalToolConfig) -> Self {
+        Self::new(&config.name, &config.description, &config.command)
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[async_trait]
+impl ExecutableTool for ExternalCommandTool {
+    async fn execute(&self, arguments: &str) -> Result<String, String> {
+        let args: serde_json::Value = if arguments.trim().is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_str(arguments)
+                .map_err(|e| format!("Failed to parse arguments: {}", e))?
+        };
+
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(self.render_command(&args)?)
+            .output()
+            .await
+            .map_err(|e| format!("Failed to execute {} command: {}", self.name, e))?;
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).to_string())
+        }
+    }
+
+    fn get_tool_definition(&self) -> Tool {
+        Tool {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            parameters: serde_json::json!({ "type": "object" }),
+        }
+    }
+
+    fn get_short_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_long_description(&self) -> String {
+        format!("{}\n\nRuns: {}", self.description, self.command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_command_quotes_values() {
+        let tool = ExternalCommandTool::new("grep", "Search files", "grep -rn {{pattern}} {{path}}");
+        let rendered = tool.render_command(&json!({ "pattern": "it's", "path": "src" })).unwrap();
+        assert_eq!(rendered, r"grep -rn 'it'\''s' 'src'");
+    }
+
+    #[test]
+    fn test_render_command_lists_missing_arguments() {
+        let tool = ExternalCommandTool::new("copy", "Copy a file", "cp {{from}} {{to}} && ls {{to}}");
+        let err = tool.render_command(&json!({ "verbose": true })).unwrap_err();
+        assert_eq!(err, "Missing arguments for the copy command: from, to");
+
+        let err = tool.render_command(&serde_json::Value::Null).unwrap_err();
+        assert!(err.ends_with("from, to"), "{}", err);
+    }
+
+    #[test]
+    fn test_render_command_rejects_quoted_placeholders() {
+        for template in ["echo '{{message}}'", "echo \"{{message}}\""] {
+            let tool = ExternalCommandTool::new("echo", "Print a message", template);
+            let err = tool.render_command(&json!({ "message": "hi" })).unwrap_err();
+            assert!(err.contains("{{message}}") && err.contains("must not be quoted"), "{}", err);
+        }
+    }
+}
@@ -7,9 +7,35 @@ use std::fmt::Debug;
 use clap::Parser;
 
 mod build;
+mod external;
+pub mod batch;
+pub mod doc;
 pub mod project;
+pub mod dependency;
+pub use batch::{BatchArgs, handle_batch};
+pub use doc::{DocArgs, handle_doc};
 pub use project::{ProjectArgs, handle_project};
+pub use dependency::{AddDependencyArgs, handle_add_dependency};
 pub use build::BuildTool;
+pub use external::ExternalCommandTool;
+
+use crate::config::SystemConfig;
+
+/// Set by the global `--dry-run` flag. Tools that write files, call the model or touch
+/// the sled stores print what they would do instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DryRun(pub bool);
+
+impl DryRun {
+    pub fn enabled(self) -> bool {
+        self.0
+    }
+
+    /// Print an action that was skipped, phrased to follow "Would"
+    pub fn report(self, action: &str) {
+        println!("[dry-run] Would {}", action);
+    }
+}
 
 /// Represents a tool in the system
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -23,6 +49,13 @@ pub struct Tool {
 #[async_trait::async_trait]
 pub trait ExecutableTool: Send + Sync {
     async fn execute(&self, arguments: &str) -> Result<String, String>;
+
+    /// Check `arguments` before `execute` runs; the registry never executes a tool whose
+    /// arguments fail validation. Accepts everything by default.
+    fn validate_arguments(&self, _arguments: &str) -> Result<(), String> {
+        Ok(())
+    }
+
     fn get_tool_definition(&self) -> Tool;
     fn get_short_description(&self) -> String;
     fn get_long_description(&self) -> String;
@@ -45,14 +78,31 @@ impl ToolRegistry {
         registry
     }
 
+    /// Create a registry with the built-in tools plus any external tools declared in `config`
+    pub fn from_config(config: &SystemConfig) -> Self {
+        let mut registry = Self::new();
+        for tool_config in &config.tools {
+            let tool = ExternalCommandTool::from(tool_config);
+            registry.register_from_definition(tool.get_tool_definition(), Box::new(tool));
+        }
+        registry
+    }
+
     pub fn register_tool<T: ExecutableTool + 'static>(&mut self, name: String, tool: Box<T>) {
         self.tools.insert(name, tool);
     }
 
+    /// Register a tool at runtime under `tool.name`, advertising `tool` as its definition
+    pub fn register_from_definition(&mut self, tool: Tool, exec: Box<dyn ExecutableTool>) {
+        self.tools.insert(tool.name.clone(), Box::new(DefinedTool { definition: tool, exec }));
+    }
+
     pub async fn execute_tool(&self, tool_call: &ToolCall) -> Result<ToolResult, String> {
         let tool = self.tools.get(&tool_call.name)
             .ok_or_else(|| format!("Tool '{}' not found", tool_call.name))?;
         
+        tool.validate_arguments(&tool_call.arguments)
+            .map_err(|e| format!("Invalid arguments for tool '{}': {}", tool_call.name, e))?;
         let output = tool.execute(&tool_call.arguments).await?;
         Ok(ToolResult {
             tool_name: tool_call.name.clone(),
@@ -81,6 +131,35 @@ impl ToolRegistry {
     }
 }
 
+/// Pairs a runtime-registered tool with the definition it was registered under
+struct DefinedTool {
+    definition: Tool,
+    exec: Box<dyn ExecutableTool>,
+}
+
+#[async_trait]
+impl ExecutableTool for DefinedTool {
+    async fn execute(&self, arguments: &str) -> Result<String, String> {
+        self.exec.execute(arguments).await
+    }
+
+    fn validate_arguments(&self, arguments: &str) -> Result<(), String> {
+        self.exec.validate_arguments(arguments)
+    }
+
+    fn get_tool_definition(&self) -> Tool {
+        self.definition.clone()
+    }
+
+    fn get_short_description(&self) -> String {
+        self.exec.get_short_description()
+    }
+
+    fn get_long_description(&self) -> String {
+        self.exec.get_long_description()
+    }
+}
+
 impl Debug for ToolRegistry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ToolRegistry")
@@ -128,12 +207,129 @@ pub struct ToolFunction {
     pub arguments: String, // JSON string of arguments
 }
 
-pub async fn run_tool(tool_name: &str, args: Vec<String>) -> Result<()> {
+pub async fn run_tool(tool_name: &str, args: Vec<String>, dry_run: DryRun) -> Result<()> {
     match tool_name {
         "project" => {
             let args = ProjectArgs::try_parse_from(args)?;
-            handle_project(args).await
+            handle_project(args, dry_run).await
+        }
+        "batch" => {
+            let args = BatchArgs::try_parse_from(args)?;
+            handle_batch(args, dry_run).await
+        }
+        "doc" => {
+            let args = DocArgs::try_parse_from(args)?;
+            handle_doc(args, dry_run).await
+        }
+        "add-dependency" => {
+            let args = AddDependencyArgs::try_parse_from(args)?;
+            handle_add_dependency(args, dry_run).await
         }
         _ => Err(anyhow::anyhow!("Unknown tool: {}", tool_name))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ExternalToolConfig;
+
+    #[tokio::test]
+    async fn test_register_external_tool_from_config() {
+        let config = SystemConfig {
+            tools: vec![ExternalToolConfig {
+                name: "greet".to_string(),
+                description: "Echo a greeting".to_string(),
+                command: "echo hello {{name}}".to_string(),
+            }],
+            ..Default::default()
+        };
+        let registry = ToolRegistry::from_config(&config);
+
+        let result = registry.execute_tool(&ToolCall {
+            name: "greet".to_string(),
+            arguments: r#"{"name": "world; rm -rf /"}"#.to_string(),
+        }).await.unwrap();
+
+        assert_eq!(result.tool_name, "greet");
+        assert_eq!(result.output.trim(), "hello world; rm -rf /");
+        assert!(registry.get_tool_definitions().iter().any(|tool| tool.name == "greet"));
+    }
+
+    #[tokio::test]
+    async fn test_register_from_definition() {
+        let mut registry = ToolRegistry::new();
+        let definition = Tool {
+            name: "shout".to_string(),
+            description: "Echo in capitals".to_string(),
+            parameters: serde_json::json!({ "type": "object" }),
+        };
+        registry.register_from_definition(
+            definition,
+            Box::new(ExternalCommandTool::new("unused", "", "echo {{word}} | tr a-z A-Z")),
+        );
+
+        let result = registry.execute_tool(&ToolCall {
+            name: "shout".to_string(),
+            arguments: r#"{"word": "build"}"#.to_string(),
+        }).await.unwrap();
+
+        assert_eq!(result.output.trim(), "BUILD");
+        assert!(registry.get_tool_definitions().iter().any(|tool| tool.description == "Echo in capitals"));
+    }
+
+    struct RejectingTool {
+        executed: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    #[async_trait]
+    impl ExecutableTool for RejectingTool {
+        async fn execute(&self, _arguments: &str) -> Result<String, String> {
+            self.executed.store(true, std::sync::atomic::Ordering::SeqCst);
+            Ok("ran".to_string())
+        }
+
+        fn validate_arguments(&self, arguments: &str) -> Result<(), String> {
+            if arguments.contains("bad") {
+                return Err("bad arguments".to_string());
+            }
+            Ok(())
+        }
+
+        fn get_tool_definition(&self) -> Tool {
+            Tool {
+                name: "guarded".to_string(),
+                description: "Runs only with valid arguments".to_string(),
+                parameters: serde_json::json!({ "type": "object" }),
+            }
+        }
+
+        fn get_short_description(&self) -> String {
+            String::new()
+        }
+
+        fn get_long_description(&self) -> String {
+            String::new()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_arguments_never_execute() {
+        let executed = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let mut registry = ToolRegistry::new();
+        registry.register_tool("guarded".to_string(), Box::new(RejectingTool { executed: executed.clone() }));
+
+        let err = registry.execute_tool(&ToolCall {
+            name: "guarded".to_string(),
+            arguments: r#"{"mode": "bad"}"#.to_string(),
+        }).await.unwrap_err();
+        assert_eq!(err, "Invalid arguments for tool 'guarded': bad arguments");
+        assert!(!executed.load(std::sync::atomic::Ordering::SeqCst));
+
+        // The built-in build tool rejects unknown commands before running anything
+        let dir = tempfile::tempdir().unwrap();
+        let arguments = serde_json::json!({ "command": "deploy", "working_directory": dir.path() }).to_string();
+        let err = registry.execute_tool(&ToolCall { name: "build".to_string(), arguments }).await.unwrap_err();
+        assert!(err.contains("deploy"), "{}", err);
+    }
+}
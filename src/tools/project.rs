@@ -1,39 +1,265 @@
 use anyhow::{Result, anyhow};
-use clap::Parser;
-use crate::inference::InferenceClient;
-use crate::project_generator::{ProjectGenerator, parse_project_design};
+use clap::{ArgGroup, Parser, ValueEnum};
+use crate::build::{BuildError, BuildManager, CrateVersionResolver};
+use crate::cli::style;
+use crate::config::SystemConfig;
+use crate::inference::{GenerationProfile, InferenceClient};
+use crate::project_generator::{ProjectGenerator, language_value_parser, parse_project_design};
+use crate::prompt::ProjectConfig;
+use crate::prompt::project_generation::ValidationOutcome;
+use crate::prompt::storage::Storage;
+use crate::state::StateManager;
 use serde_json;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::DryRun;
 
 #[derive(Parser, Debug)]
+#[clap(group(ArgGroup::new("config_output").args(["config_only", "from_existing"]).multiple(true)))]
 pub struct ProjectArgs {
     /// Name of the project
-    #[clap(long)]
-    name: String,
+    #[clap(long, required_unless_present = "from_existing")]
+    name: Option<String>,
 
     /// Programming language to use
+    #[clap(long, required_unless_present = "from_existing", ignore_case = true, value_parser = language_value_parser())]
+    language: Option<String>,
+
+    /// Infer the configuration from an existing project directory instead of generating one
+    #[clap(long, conflicts_with = "strict")]
+    from_existing: Option<PathBuf>,
+
+    /// Fail instead of defaulting fields missing from the generated config
+    #[clap(long)]
+    strict: bool,
+
+    /// Only generate the project configuration; skip scaffolding
+    #[clap(long)]
+    config_only: bool,
+
+    /// Where to write the generated configuration (stdout if omitted)
+    #[clap(long, requires = "config_output")]
+    output: Option<PathBuf>,
+
+    /// Lowest severity of validation issue that aborts generation
+    #[clap(long, value_enum, default_value_t = FailOn::Errors)]
+    fail_on: FailOn,
+
+    /// Generation profile from the system config's `[profiles.<name>]` tables
+    #[clap(long, conflicts_with = "from_existing")]
+    profile: Option<String>,
+
+    /// Replace "latest" or missing Rust dependency versions with the newest crates.io release
     #[clap(long)]
-    language: String,
+    resolve_versions: bool,
+
+    /// System config file to read profiles and response file settings from
+    #[clap(long, default_value = "config.toml")]
+    config: PathBuf,
+
+    /// Run the language's build command in the generated project and fail if it does not build
+    #[clap(long, conflicts_with = "config_output")]
+    verify: bool,
+
+    /// Generate reproducibly: temperature 0 and a fixed sampling seed
+    #[clap(long, conflicts_with = "from_existing")]
+    deterministic: bool,
+
+    /// Sampling seed sent to providers that support seeded generation
+    #[clap(long, conflicts_with = "from_existing")]
+    seed: Option<u64>,
+}
+
+/// Severity threshold for `--fail-on`
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FailOn {
+    #[default]
+    Errors,
+    Warnings,
+}
+
+pub async fn handle_project(args: ProjectArgs, dry_run: DryRun) -> Result<()> {
+    handle_project_with(args, dry_run, |args| {
+        eprintln!("Initializing inference client...");
+        let mut client = InferenceClient::new()?
+            .with_deterministic(args.deterministic)
+            .with_seed(args.seed);
+        if args.config.exists() {
+            client = client.with_config(&SystemConfig::load(&args.config)?)?;
+        }
+        Ok(client)
+    })
+    .await
+}
+
+/// `handle_project` with the inference client built by `connect`, which is only called
+/// once the run is known to need the model
+async fn handle_project_with(
+    args: ProjectArgs,
+    dry_run: DryRun,
+    connect: impl FnOnce(&ProjectArgs) -> Result<InferenceClient>,
+) -> Result<()> {
+    if dry_run.enabled() {
+        for action in planned_actions(&args) {
+            dry_run.report(&action);
+        }
+        return Ok(());
+    }
+
+    if let Some(dir) = &args.from_existing {
+        return capture_existing(dir, &args);
+    }
+
+    let client = connect(&args)?;
+    run_project(args, &client).await
 }
 
-pub async fn handle_project(args: ProjectArgs) -> Result<()> {
-    println!("Initializing inference client...");
-    let client = InferenceClient::new()?;
+/// Write the configuration inferred from an existing project, with `--name` and
+/// `--language` overriding what was detected
+fn capture_existing(dir: &Path, args: &ProjectArgs) -> Result<()> {
+    let mut config = ProjectConfig::from_existing(dir)?;
+    if let Some(name) = &args.name {
+        config.project_name = name.clone();
+    }
+    if let Some(language) = &args.language {
+        config.language = language.clone();
+    }
+
+    let pretty = serde_json::to_string_pretty(&config)?;
+    write_config(&pretty, args.output.as_deref())
+}
+
+/// What `handle_project` would do with `args`, for `--dry-run`
+fn planned_actions(args: &ProjectArgs) -> Vec<String> {
+    let destination = match &args.output {
+        Some(path) => path.display().to_string(),
+        None => "stdout".to_string(),
+    };
+    if let Some(dir) = &args.from_existing {
+        return vec![format!("infer a configuration from {} and write it to {}", dir.display(), destination)];
+    }
+
+    let name = args.name.as_deref().unwrap_or_default();
+    let mut actions = vec![format!(
+        "ask the model to \"Create a {} project named '{}'\"",
+        args.language.as_deref().unwrap_or_default(),
+        name
+    )];
+    if args.resolve_versions {
+        actions.push("resolve \"latest\" dependency versions from crates.io".to_string());
+    }
+    if args.config_only {
+        actions.push(format!("write the generated configuration to {}", destination));
+    } else {
+        actions.push("scaffold the generated project under build/".to_string());
+        if args.verify {
+            actions.push("build the generated project to verify it compiles".to_string());
+        }
+    }
+    actions
+}
+
+/// crates.io resolver whose answers are cached in the user cache directory, so a crate
+/// keeps the version it was first resolved to
+fn version_resolver() -> CrateVersionResolver {
+    let resolver = CrateVersionResolver::new();
+    let Some(cache_dir) = dirs::cache_dir() else {
+        return resolver;
+    };
+    match Storage::new(cache_dir.join("build-system").join("crate_versions")) {
+        Ok(storage) => resolver.with_cache(Arc::new(storage)),
+        Err(e) => {
+            tracing::warn!("Crate version cache unavailable: {:#}", e);
+            resolver
+        }
+    }
+}
+
+fn write_config(pretty: &str, output: Option<&Path>) -> Result<()> {
+    match output {
+        Some(path) => {
+            std::fs::write(path, pretty)
+                .map_err(|e| anyhow!("Failed to write config to {}: {}", path.display(), e))?;
+            println!("{}", style::palette().success(&format!("Wrote project configuration to {}", path.display())));
+        }
+        None => println!("{}", pretty),
+    }
+    Ok(())
+}
+
+async fn run_project(args: ProjectArgs, client: &InferenceClient) -> Result<()> {
+    run_project_in(args, client, Path::new("build")).await
+}
+
+/// Generate the project under `output_dir`
+async fn run_project_in(args: ProjectArgs, client: &InferenceClient, output_dir: &Path) -> Result<()> {
+    let (Some(name), Some(language)) = (&args.name, &args.language) else {
+        return Err(anyhow!("--name and --language are required to generate a project"));
+    };
 
     // Create the user request
     let request = format!(
         "Create a {} project named '{}'",
-        args.language, args.name
+        language, name
     );
-    println!("Sending request: {}", request);
+    // Diagnostics go to stderr so `--config-only` output can be piped as JSON
+    eprintln!("Sending request: {}", request);
+
+    let profile = match &args.profile {
+        Some(name) => SystemConfig::load(&args.config)?.profile(name)?,
+        None => GenerationProfile::default(),
+    };
 
     // Generate project configuration using AI
-    let config_json = client.generate_project_config(&request).await?;
-    println!("Generated config (raw):\n{}", config_json);
+    let config_json = client.generate_project_config_with(&request, &profile).await?;
+    eprintln!("Generated config (raw):\n{}", config_json);
 
     // Try to parse it as a Value first to check structure
-    let parsed = serde_json::from_str::<serde_json::Value>(&config_json)
+    let mut parsed = serde_json::from_str::<serde_json::Value>(&config_json)
         .map_err(|e| anyhow!("Invalid JSON: {}", e))?;
-    println!("Parsed JSON structure:\n{}", serde_json::to_string_pretty(&parsed)?);
+    if args.resolve_versions {
+        let resolved = version_resolver().resolve_config(&mut parsed).await;
+        eprintln!("Resolved {} dependency version(s) from crates.io", resolved);
+    }
+    eprintln!("Parsed JSON structure:\n{}", serde_json::to_string_pretty(&parsed)?);
+
+    // Scaffolding defaults fields the schema requires, so a config that doesn't
+    // deserialize is only fatal in strict mode
+    let outcome = match serde_json::from_value::<ProjectConfig>(parsed.clone()) {
+        Ok(config) => config.validate_detailed(),
+        Err(e) if args.strict => {
+            return Err(anyhow!("Generated config does not match the project schema: {}", e));
+        }
+        Err(e) => ValidationOutcome {
+            warnings: vec![format!("Generated config does not match the project schema, using defaults: {}", e)],
+            ..Default::default()
+        },
+    };
+    for warning in &outcome.warnings {
+        eprintln!("{}", style::palette().warning(&format!("Warning: {}", warning)));
+    }
+    if !outcome.passes(args.fail_on == FailOn::Warnings) {
+        let mut issues = outcome.errors.clone();
+        if args.fail_on == FailOn::Warnings {
+            issues.extend(outcome.warnings.iter().cloned());
+        }
+        return Err(anyhow!("Project validation failed: {}", issues.join("; ")));
+    }
+
+    if args.config_only {
+        return write_config(&serde_json::to_string_pretty(&parsed)?, args.output.as_deref());
+    }
+
+    if args.strict {
+        let defaulted = BuildManager::defaulted_fields(&parsed);
+        if !defaulted.is_empty() {
+            return Err(anyhow!(
+                "Strict mode: generated configuration is missing fields: {}",
+                defaulted.join(", ")
+            ));
+        }
+    }
 
     // Print specific fields we care about
     if let Some(dir_struct) = parsed.get("directory_structure") {
@@ -47,13 +273,257 @@ pub async fn handle_project(args: ProjectArgs) -> Result<()> {
 
     // Parse the config into a ProjectDesign
     println!("\nParsing config into ProjectDesign...");
-    let design = parse_project_design(&config_json)?;
+    let design = parse_project_design(&parsed.to_string())?;
+    let language = design.language.clone();
 
     // Use the project generator to create the project
     println!("Generating project structure...");
+    let project_root = output_dir.join(&design.name);
     let generator = ProjectGenerator::new(design);
-    generator.generate().await?;
+    generator.generate_async_in(&project_root).await?;
 
-    println!("Project generation complete!");
+    println!("{}", style::palette().success("Project generation complete!"));
+    if args.verify {
+        return verify_build(&project_root, &language).await;
+    }
+    Ok(())
+}
+
+/// Build the generated project with its language's toolchain, failing with the output
+/// of the first command that did not succeed
+async fn verify_build(project_root: &Path, language: &str) -> Result<()> {
+    println!("Verifying that {} builds...", project_root.display());
+    let build_manager = BuildManager::new(StateManager::new(), project_root.to_path_buf());
+    let report = build_manager.verify_project(project_root, language).await?;
+
+    if let Some((_, error)) = report.failures.first() {
+        let detail = match error {
+            BuildError::CommandFailed(output) => format!("{}{}", output.stdout, output.stderr),
+            other => other.to_string(),
+        };
+        return Err(anyhow!("Build verification failed for {}:\n{}", project_root.display(), detail.trim_end()));
+    }
+    println!("{}", style::palette().success(&format!("Build verified: {} command(s) succeeded", report.succeeded.len())));
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inference::test_server::{MockResponse, MockServer};
+
+    #[tokio::test]
+    async fn test_config_only_writes_output() -> Result<()> {
+        let config = serde_json::json!({
+            "project_name": "config-only",
+            "description": "Inspect before scaffolding",
+            "language": "Rust",
+            "framework": "clap",
+            "project_type": "CommandLineInterface",
+            "directory_structure": { "src": ["main.rs"] }
+        });
+        let server = MockServer::start(vec![MockResponse::completion(&config.to_string())]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+
+        let temp_dir = tempfile::tempdir()?;
+        let output = temp_dir.path().join("config.json");
+        let args = ProjectArgs::try_parse_from([
+            "project", "--name", "config-only", "--language", "rust",
+            "--config-only", "--output", output.to_str().unwrap(),
+        ])?;
+        run_project(args, &client).await?;
+
+        let written: ProjectConfig = serde_json::from_str(&std::fs::read_to_string(&output)?)?;
+        assert_eq!(written.project_name, "config-only");
+        assert_eq!(written.language, "Rust");
+        assert!(!temp_dir.path().join("build").exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fail_on_warnings_rejects_missing_description() -> Result<()> {
+        let config = serde_json::json!({
+            "project_name": "undocumented",
+            "language": "Rust",
+            "framework": "clap",
+            "project_type": "CommandLineInterface",
+            "directory_structure": { "src": ["main.rs"], "tests": ["cli.rs"] }
+        });
+        let temp_dir = tempfile::tempdir()?;
+        let output = temp_dir.path().join("config.json");
+
+        let server = MockServer::start(vec![MockResponse::completion(&config.to_string())]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+        let args = ProjectArgs::try_parse_from([
+            "project", "--name", "undocumented", "--language", "rust",
+            "--config-only", "--output", output.to_str().unwrap(),
+        ])?;
+        run_project(args, &client).await?;
+        assert!(output.exists());
+        std::fs::remove_file(&output)?;
+
+        let server = MockServer::start(vec![MockResponse::completion(&config.to_string())]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+        let args = ProjectArgs::try_parse_from([
+            "project", "--name", "undocumented", "--language", "rust",
+            "--config-only", "--output", output.to_str().unwrap(), "--fail-on", "warnings",
+        ])?;
+        let err = run_project(args, &client).await.unwrap_err();
+        assert!(err.to_string().contains("Project description is missing"));
+        assert!(!output.exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_schema_mismatch_only_fails_in_strict_mode() -> Result<()> {
+        // No project_type: scaffolding defaults it, but ProjectConfig requires it
+        let config = serde_json::json!({
+            "project_name": "untyped",
+            "description": "Missing its project type",
+            "language": "Rust",
+            "framework": "clap",
+            "directory_structure": { "src": ["main.rs"], "tests": ["cli.rs"] }
+        });
+        let temp_dir = tempfile::tempdir()?;
+        let output = temp_dir.path().join("config.json");
+
+        let server = MockServer::start(vec![MockResponse::completion(&config.to_string())]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+        let args = ProjectArgs::try_parse_from([
+            "project", "--name", "untyped", "--language", "rust",
+            "--config-only", "--output", output.to_str().unwrap(),
+        ])?;
+        run_project(args, &client).await?;
+        assert!(output.exists());
+
+        let server = MockServer::start(vec![MockResponse::completion(&config.to_string())]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+        let args = ProjectArgs::try_parse_from(["project", "--name", "untyped", "--language", "rust", "--strict"])?;
+        let err = run_project_in(args, &client, temp_dir.path()).await.unwrap_err();
+        assert!(err.to_string().contains("does not match the project schema"), "{}", err);
+
+        Ok(())
+    }
+
+    fn cargo_available() -> bool {
+        std::process::Command::new("cargo").arg("--version").output().is_ok_and(|out| out.status.success())
+    }
+
+    #[tokio::test]
+    async fn test_verify_builds_generated_project() -> Result<()> {
+        if !cargo_available() {
+            eprintln!("cargo not found; skipping build verification test");
+            return Ok(());
+        }
+
+        let config = serde_json::json!({
+            "project_name": "verified",
+            "description": "Checked with cargo after generation",
+            "technologies": ["Rust"],
+            "language": "Rust",
+            "framework": "clap",
+            "project_type": "CommandLineInterface",
+            "dependencies": { "production": {}, "development": {} },
+            "build_config": { "build_tool": "cargo", "scripts": { "build": "cargo build" } },
+            "directory_structure": { "src": ["main.rs", "cli.rs"] }
+        });
+        let server = MockServer::start(vec![MockResponse::completion(&config.to_string())]).await;
+        let client = InferenceClient::with_endpoint("test-key", &server.base_url, "test-model")?;
+        let temp_dir = tempfile::tempdir()?;
+
+        let args = ProjectArgs::try_parse_from(["project", "--name", "verified", "--language", "rust", "--verify"])?;
+        run_project_in(args, &client, temp_dir.path()).await?;
+
+        // The build that passed is the one the generator wrote
+        let project_dir = temp_dir.path().join("verified");
+        assert!(project_dir.join("Cargo.toml").exists());
+        std::fs::write(project_dir.join("src/main.rs"), "fn main() { let x: u32 = \"no\"; }")?;
+        let err = verify_build(&project_dir, "rust").await.unwrap_err();
+        assert!(err.to_string().contains("Build verification failed"), "{}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_existing_writes_inferred_config() -> Result<()> {
+        let project = tempfile::tempdir()?;
+        std::fs::write(project.path().join("package.json"), r#"{"name": "web-ui", "dependencies": {"react": "^18.2.0"}}"#)?;
+        std::fs::create_dir_all(project.path().join("src"))?;
+        std::fs::write(project.path().join("src/index.js"), "")?;
+
+        let out_dir = tempfile::tempdir()?;
+        let output = out_dir.path().join("config.json");
+        let args = ProjectArgs::try_parse_from([
+            "project", "--from-existing", project.path().to_str().unwrap(),
+            "--output", output.to_str().unwrap(),
+        ])?;
+        capture_existing(args.from_existing.as_deref().unwrap(), &args)?;
+
+        let written: ProjectConfig = serde_json::from_str(&std::fs::read_to_string(&output)?)?;
+        assert_eq!(written.project_name, "web-ui");
+        assert_eq!(written.language, "JavaScript");
+        assert_eq!(written.dependencies.production["react"], "^18.2.0");
+        Ok(())
+    }
+
+    #[test]
+    fn test_language_validated_while_parsing() -> Result<()> {
+        let err = ProjectArgs::try_parse_from(["project", "--name", "typo", "--language", "rst"]).unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::InvalidValue);
+        assert!(err.to_string().contains("rust, python, javascript, typescript, go, java"), "{}", err);
+
+        let args = ProjectArgs::try_parse_from(["project", "--name", "typo", "--language", "Rust"])?;
+        assert_eq!(args.language.as_deref(), Some("rust"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_has_no_side_effects() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let output = temp_dir.path().join("config.json");
+        let args = ProjectArgs::try_parse_from([
+            "project", "--name", "dry-run-demo", "--language", "rust", "--resolve-versions",
+            "--config-only", "--output", output.to_str().unwrap(),
+        ])?;
+        assert_eq!(planned_actions(&args), [
+            "ask the model to \"Create a rust project named 'dry-run-demo'\"".to_string(),
+            "resolve \"latest\" dependency versions from crates.io".to_string(),
+            format!("write the generated configuration to {}", output.display()),
+        ]);
+
+        let config = serde_json::json!({
+            "project_name": "dry-run-demo",
+            "description": "Never generated",
+            "language": "Rust",
+            "framework": "clap",
+            "project_type": "CommandLineInterface"
+        });
+        let server = MockServer::start(vec![MockResponse::completion(&config.to_string())]).await;
+        let connect = |_: &ProjectArgs| InferenceClient::with_endpoint("test-key", &server.base_url, "test-model");
+        handle_project_with(args, DryRun(true), connect).await?;
+        assert!(server.requests().is_empty());
+        assert!(!output.exists());
+
+        let args = ProjectArgs::try_parse_from(["project", "--name", "dry-run-demo", "--language", "rust", "--verify"])?;
+        handle_project_with(args, DryRun(true), connect).await?;
+        assert!(server.requests().is_empty());
+        assert!(!Path::new("build/dry-run-demo").exists());
+
+        // The same client does reach the server once dry-run is off
+        let args = ProjectArgs::try_parse_from([
+            "project", "--name", "dry-run-demo", "--language", "rust",
+            "--config-only", "--output", output.to_str().unwrap(),
+        ])?;
+        handle_project_with(args, DryRun(false), connect).await?;
+        assert_eq!(server.requests().len(), 1);
+        assert!(output.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_name_required_without_from_existing() {
+        assert!(ProjectArgs::try_parse_from(["project", "--language", "rust"]).is_err());
+        assert!(ProjectArgs::try_parse_from(["project", "--name", "x", "--language", "rust", "--output", "out.json"]).is_err());
+    }
+}
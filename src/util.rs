@@ -0,0 +1,20 @@
+/// Hex FNV-1a hash of `text`. Unlike `DefaultHasher` it is stable across builds and
+/// platforms, so it can be persisted, printed and compared by a later run.
+pub fn stable_hash(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stable_hash_is_stable() {
+        assert_eq!(stable_hash(""), "cbf29ce484222325");
+        assert_eq!(stable_hash("a"), "af63dc4c8601ec8c");
+        assert_ne!(stable_hash("a web app"), stable_hash("a web app "));
+    }
+}
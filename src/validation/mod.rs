@@ -1,7 +1,17 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use similar::TextDiff;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::prompt::storage::{Storage, KEY_TIMESTAMP_FORMAT};
+
+/// Key prefix under which build validations are stored
+pub const VALIDATION_KEY_PREFIX: &str = "build_validation_";
+
+/// Key prefix under which validation reports are stored
+pub const REPORT_KEY_PREFIX: &str = "validation_report_";
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FileContent {
@@ -15,6 +25,16 @@ pub struct BuildValidation {
     pub model_response: String,
     pub build_path: PathBuf,
     pub files: HashMap<String, FileContent>,
+    /// Expected contents keyed by relative path, compared against `files` by `validate_build`
+    #[serde(default)]
+    pub expected_files: HashMap<String, String>,
+    /// Patterns each file must contain, keyed by relative path. A pattern passes when it
+    /// occurs literally or matches as a regex.
+    #[serde(default)]
+    pub assertions: HashMap<String, Vec<String>>,
+    /// Paths that could not be captured, with the error encountered
+    #[serde(default)]
+    pub errors: Vec<(String, String)>,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
@@ -24,6 +44,9 @@ impl BuildValidation {
             model_response,
             build_path,
             files: HashMap::new(),
+            expected_files: HashMap::new(),
+            assertions: HashMap::new(),
+            errors: Vec::new(),
             timestamp: chrono::Utc::now(),
         }
     }
@@ -39,89 +62,313 @@ impl BuildValidation {
         );
     }
 
-    pub fn save(&self, storage: &crate::prompt::storage::Storage) -> Result<()> {
+    pub fn expect_file(&mut self, path: String, content: String) {
+        self.expected_files.insert(path, content);
+    }
+
+    pub fn assert_contains(&mut self, path: String, pattern: String) {
+        self.assertions.entry(path).or_default().push(pattern);
+    }
+
+    pub fn record_error(&mut self, path: String, error: String) {
+        tracing::warn!("Could not capture {}: {}", path, error);
+        self.errors.push((path, error));
+    }
+
+    pub fn save(&self, storage: &Storage) -> Result<()> {
         let key = format!(
-            "build_validation_{}",
-            self.timestamp.format("%Y%m%d_%H%M%S")
+            "{}{}",
+            VALIDATION_KEY_PREFIX,
+            self.timestamp.format(KEY_TIMESTAMP_FORMAT)
         );
         storage.store(&key, self)?;
         Ok(())
     }
 
     pub fn load(
-        storage: &crate::prompt::storage::Storage,
+        storage: &Storage,
         key: &str,
     ) -> Result<Option<BuildValidation>> {
         storage.load(key)
     }
 }
 
+/// Load all validations saved at or after `from`, oldest first
+pub fn load_validations_since(storage: &Storage, from: DateTime<Utc>) -> Result<Vec<BuildValidation>> {
+    let keys = storage.list_keys_in_range(VALIDATION_KEY_PREFIX, from, DateTime::<Utc>::MAX_UTC)?;
+
+    let mut validations = Vec::new();
+    for key in keys {
+        if let Some(validation) = BuildValidation::load(storage, &key)? {
+            validations.push(validation);
+        }
+    }
+    Ok(validations)
+}
+
+/// Directories skipped by `capture_build_output` unless `CaptureOptions::include_all` is set
+pub const DEFAULT_SKIPPED_DIRS: &[&str] = &["target", "node_modules", "dist", "build", "__pycache__", "venv"];
+
+/// Name of the gitignore-style file listing paths `capture_build_output` leaves out
+pub const BUILD_IGNORE_FILE: &str = ".buildignore";
+
+/// Options controlling which files `capture_build_output_with` captures
+#[derive(Debug, Clone, Default)]
+pub struct CaptureOptions {
+    /// Capture hidden files, `DEFAULT_SKIPPED_DIRS` and paths listed in `.buildignore`
+    pub include_all: bool,
+}
+
 pub fn capture_build_output(
     build_path: PathBuf,
     model_response: String,
+) -> Result<BuildValidation> {
+    capture_build_output_with(build_path, model_response, &CaptureOptions::default())
+}
+
+/// Capture the files under `build_path`. Hidden files, `DEFAULT_SKIPPED_DIRS` and
+/// anything matched by a `.buildignore` are skipped unless `options.include_all` is set.
+/// Unreadable entries are recorded in `validation.errors` and skipped so the rest of the
+/// tree is still captured.
+pub fn capture_build_output_with(
+    build_path: PathBuf,
+    model_response: String,
+    options: &CaptureOptions,
 ) -> Result<BuildValidation> {
     use std::fs;
 
     let mut validation = BuildValidation::new(model_response, build_path.clone());
 
-    fn visit_dirs(dir: &PathBuf, validation: &mut BuildValidation, base_path: &PathBuf) -> Result<()> {
-        if dir.is_dir() {
-            for entry in fs::read_dir(dir)? {
-                let entry = entry?;
-                let path = entry.path();
-                let relative_path = path.strip_prefix(base_path)?.to_string_lossy().into_owned();
-
-                if path.is_dir() {
-                    validation.add_file(
-                        relative_path,
-                        String::new(),
-                        0,
-                        true,
-                    );
-                    visit_dirs(&path, validation, base_path)?;
-                } else {
-                    let content = fs::read_to_string(&path)?;
-                    let metadata = fs::metadata(&path)?;
-                    validation.add_file(
-                        relative_path,
-                        content,
-                        metadata.len(),
-                        false,
-                    );
-                }
+    let mut walker = ignore::WalkBuilder::new(&build_path);
+    walker
+        .standard_filters(false)
+        .hidden(!options.include_all)
+        .sort_by_file_name(|a, b| a.cmp(b));
+    if !options.include_all {
+        walker
+            .add_custom_ignore_filename(BUILD_IGNORE_FILE)
+            .filter_entry(|entry| {
+                let is_dir = entry.file_type().is_some_and(|file_type| file_type.is_dir());
+                !(is_dir && entry.depth() > 0 && DEFAULT_SKIPPED_DIRS.iter().any(|dir| entry.file_name() == *dir))
+            });
+    }
+
+    for entry in walker.build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                let path = error_path(&e).map(|path| relative_to(path, &build_path)).unwrap_or_default();
+                let message = e.io_error().map_or_else(|| e.to_string(), |io| io.to_string());
+                validation.record_error(path, message);
+                continue;
             }
+        };
+        if entry.depth() == 0 {
+            continue;
         }
-        Ok(())
-    }
 
-    visit_dirs(&build_path, &mut validation, &build_path)?;
+        let path = entry.path();
+        let relative_path = relative_to(path, &build_path);
+        if path.is_dir() {
+            validation.add_file(relative_path, String::new(), 0, true);
+        } else {
+            match fs::read_to_string(path).and_then(|content| Ok((content, fs::metadata(path)?))) {
+                Ok((content, metadata)) => validation.add_file(relative_path, content, metadata.len(), false),
+                Err(e) => validation.record_error(relative_path, e.to_string()),
+            }
+        }
+    }
     Ok(validation)
 }
 
+fn relative_to(path: &Path, base_path: &Path) -> String {
+    path.strip_prefix(base_path)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// The path a walk error happened at, if it records one
+fn error_path(error: &ignore::Error) -> Option<&Path> {
+    match error {
+        ignore::Error::WithPath { path, .. } => Some(path),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => error_path(err),
+        _ => None,
+    }
+}
+
+/// Options controlling how `validate_build` classifies file contents
+#[derive(Debug, Clone)]
+pub struct ValidationOptions {
+    /// Minimum line similarity (0.0-1.0) for a differing file to count as `Partial`
+    pub partial_threshold: f32,
+    /// Ignore whitespace at the end of lines and blank lines at the end of the file
+    pub ignore_whitespace: bool,
+    /// Treat CRLF and lone CR line endings as LF
+    pub ignore_line_endings: bool,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            partial_threshold: 0.8,
+            ignore_whitespace: false,
+            ignore_line_endings: false,
+        }
+    }
+}
+
+impl ValidationOptions {
+    /// `content` with the differences these options ignore removed
+    fn normalize<'a>(&self, content: &'a str) -> std::borrow::Cow<'a, str> {
+        let mut content = std::borrow::Cow::Borrowed(content);
+        if self.ignore_line_endings && content.contains('\r') {
+            content = content.replace("\r\n", "\n").replace('\r', "\n").into();
+        }
+        if self.ignore_whitespace {
+            let mut trimmed: String = content.lines().map(|line| line.trim_end()).collect::<Vec<_>>().join("\n");
+            trimmed.truncate(trimmed.trim_end().len());
+            content = trimmed.into();
+        }
+        content
+    }
+}
+
 pub fn validate_build(validation: &BuildValidation) -> Result<ValidationReport> {
-    // TODO: Implement validation logic to compare model response with actual files
+    validate_build_with(validation, &ValidationOptions::default())
+}
+
+/// Compare expected file contents with the captured build output.
+///
+/// Files are compared after `options` normalizes them. Identical files are `Exact`, files
+/// at or above `partial_threshold` line similarity are `Partial`, absent files are
+/// `Missing`, and files below the threshold (or present without being expected) are
+/// `Unexpected`. Every pattern in `assertions` that the file does not
+/// contain is reported as `AssertionFailed`.
+pub fn validate_build_with(validation: &BuildValidation, options: &ValidationOptions) -> Result<ValidationReport> {
+    let mut matches = Vec::new();
+    let mut mismatches = Vec::new();
+
+    let mut expected_paths: Vec<&String> = validation.expected_files.keys().collect();
+    expected_paths.sort();
+
+    for path in expected_paths {
+        let expected = &validation.expected_files[path];
+        let actual = match validation.files.get(path) {
+            Some(file) if !file.is_directory => file.content.clone(),
+            _ => {
+                mismatches.push(ValidationMatch {
+                    file_path: path.clone(),
+                    expected: expected.clone(),
+                    actual: String::new(),
+                    match_type: MatchType::Missing,
+                    similarity: 0.0,
+                });
+                continue;
+            }
+        };
+
+        let (normalized_expected, normalized_actual) = (options.normalize(expected), options.normalize(&actual));
+        let similarity = similarity_ratio(&normalized_expected, &normalized_actual);
+        let match_type = if normalized_expected == normalized_actual {
+            MatchType::Exact
+        } else if similarity >= options.partial_threshold {
+            MatchType::Partial
+        } else {
+            MatchType::Unexpected
+        };
+
+        let entry = ValidationMatch {
+            file_path: path.clone(),
+            expected: expected.clone(),
+            actual,
+            similarity: if matches!(match_type, MatchType::Exact) { 1.0 } else { similarity },
+            match_type,
+        };
+        match entry.match_type {
+            MatchType::Exact | MatchType::Partial => matches.push(entry),
+            _ => mismatches.push(entry),
+        }
+    }
+
+    // Extra files are only meaningful once expectations have been recorded
+    if !validation.expected_files.is_empty() {
+        let mut extra_paths: Vec<&String> = validation.files.iter()
+            .filter(|(path, file)| !file.is_directory && !validation.expected_files.contains_key(*path))
+            .map(|(path, _)| path)
+            .collect();
+        extra_paths.sort();
+
+        for path in extra_paths {
+            mismatches.push(ValidationMatch {
+                file_path: path.clone(),
+                expected: String::new(),
+                actual: validation.files[path].content.clone(),
+                match_type: MatchType::Unexpected,
+                similarity: 0.0,
+            });
+        }
+    }
+
+    let mut asserted_paths: Vec<&String> = validation.assertions.keys().collect();
+    asserted_paths.sort();
+
+    for path in asserted_paths {
+        let actual = match validation.files.get(path) {
+            Some(file) if !file.is_directory => file.content.as_str(),
+            _ => "",
+        };
+        for pattern in &validation.assertions[path] {
+            if !contains_pattern(actual, pattern) {
+                mismatches.push(ValidationMatch {
+                    file_path: path.clone(),
+                    expected: pattern.clone(),
+                    actual: actual.to_string(),
+                    match_type: MatchType::AssertionFailed,
+                    similarity: 0.0,
+                });
+            }
+        }
+    }
+
     Ok(ValidationReport {
         timestamp: validation.timestamp,
         build_path: validation.build_path.clone(),
-        matches: vec![],
-        mismatches: vec![],
+        matches,
+        mismatches,
     })
 }
 
+/// Whether `content` contains `pattern` literally or, when it is a valid regex, a match for it
+fn contains_pattern(content: &str, pattern: &str) -> bool {
+    content.contains(pattern)
+        || regex::Regex::new(pattern).is_ok_and(|re| re.is_match(content))
+}
+
+/// Line-level similarity between two texts, from 0.0 (nothing shared) to 1.0 (identical)
+fn similarity_ratio(expected: &str, actual: &str) -> f32 {
+    TextDiff::from_lines(expected, actual).ratio()
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ValidationMatch {
     pub file_path: String,
     pub expected: String,
     pub actual: String,
     pub match_type: MatchType,
+    /// Line similarity between expected and actual content (1.0 for an exact match)
+    #[serde(default)]
+    pub similarity: f32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum MatchType {
     Exact,
     Partial,
     Missing,
     Unexpected,
+    /// The file does not contain a pattern from `BuildValidation::assertions`
+    AssertionFailed,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -131,3 +378,242 @@ pub struct ValidationReport {
     pub matches: Vec<ValidationMatch>,
     pub mismatches: Vec<ValidationMatch>,
 }
+
+impl ValidationReport {
+    /// Key of the report for the validation stored under `validation_key`: the
+    /// validation's timestamp suffix under `REPORT_KEY_PREFIX`
+    pub fn key_for(validation_key: &str) -> String {
+        let suffix = validation_key.strip_prefix(VALIDATION_KEY_PREFIX).unwrap_or(validation_key);
+        format!("{}{}", REPORT_KEY_PREFIX, suffix)
+    }
+
+    /// Store the report for the validation saved under `validation_key`, returning the
+    /// key used
+    pub fn save(&self, storage: &Storage, validation_key: &str) -> Result<String> {
+        let key = Self::key_for(validation_key);
+        storage.store(&key, self)?;
+        Ok(key)
+    }
+
+    /// Load the report saved for the validation under `validation_key`
+    pub fn load(
+        storage: &Storage,
+        validation_key: &str,
+    ) -> Result<Option<ValidationReport>> {
+        storage.load(&Self::key_for(validation_key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::tempdir;
+
+    fn validation_at(timestamp: DateTime<Utc>) -> BuildValidation {
+        let mut validation = BuildValidation::new("response".to_string(), PathBuf::from("build"));
+        validation.timestamp = timestamp;
+        validation
+    }
+
+    #[test]
+    fn test_load_validations_since() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let storage = Storage::new(temp_dir.path())?;
+
+        let first = Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap();
+        let second = Utc.with_ymd_and_hms(2024, 1, 2, 9, 0, 0).unwrap();
+        let third = Utc.with_ymd_and_hms(2024, 1, 3, 9, 0, 0).unwrap();
+        for timestamp in [first, second, third] {
+            validation_at(timestamp).save(&storage)?;
+        }
+
+        let since_second = load_validations_since(&storage, second)?;
+        let timestamps: Vec<_> = since_second.iter().map(|v| v.timestamp).collect();
+        assert_eq!(timestamps, vec![second, third]);
+
+        let keys = storage.list_keys_in_range(VALIDATION_KEY_PREFIX, first, second)?;
+        assert_eq!(keys.len(), 2);
+
+        Ok(())
+    }
+
+    fn validation_with(expected: &str, actual: &str) -> BuildValidation {
+        let mut validation = BuildValidation::new("response".to_string(), PathBuf::from("build"));
+        validation.expect_file("src/main.rs".to_string(), expected.to_string());
+        validation.add_file("src/main.rs".to_string(), actual.to_string(), actual.len() as u64, false);
+        validation
+    }
+
+    fn numbered_lines(changed: Option<usize>) -> String {
+        (0..10)
+            .map(|i| if Some(i) == changed { "changed\n".to_string() } else { format!("line {}\n", i) })
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_build_identical_file_is_exact() -> Result<()> {
+        let content = numbered_lines(None);
+        let report = validate_build(&validation_with(&content, &content))?;
+
+        assert_eq!(report.matches.len(), 1);
+        assert_eq!(report.matches[0].match_type, MatchType::Exact);
+        assert_eq!(report.matches[0].similarity, 1.0);
+        assert!(report.mismatches.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_build_slightly_changed_file_is_partial() -> Result<()> {
+        let report = validate_build(&validation_with(&numbered_lines(None), &numbered_lines(Some(3))))?;
+
+        assert_eq!(report.matches.len(), 1);
+        assert_eq!(report.matches[0].match_type, MatchType::Partial);
+        assert!((report.matches[0].similarity - 0.9).abs() < 0.01);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_build_different_and_missing_files() -> Result<()> {
+        let mut validation = validation_with(&numbered_lines(None), "something else entirely\n");
+        validation.expect_file("Cargo.toml".to_string(), "[package]\n".to_string());
+        let report = validate_build(&validation)?;
+
+        assert!(report.matches.is_empty());
+        let types: Vec<_> = report.mismatches.iter().map(|m| (m.file_path.as_str(), m.match_type.clone())).collect();
+        assert_eq!(types, vec![("Cargo.toml", MatchType::Missing), ("src/main.rs", MatchType::Unexpected)]);
+
+        let lenient = validate_build_with(&validation, &ValidationOptions { partial_threshold: 0.0, ..Default::default() })?;
+        assert_eq!(lenient.matches[0].match_type, MatchType::Partial);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_build_line_ending_normalization() -> Result<()> {
+        let unix = "fn main() {\n    run();\n}\n";
+        let validation = validation_with(unix, &unix.replace('\n', "\r\n"));
+
+        let strict = validate_build(&validation)?;
+        assert!(strict.matches.is_empty());
+        assert_ne!(strict.mismatches[0].match_type, MatchType::Exact);
+
+        let options = ValidationOptions { ignore_line_endings: true, ..Default::default() };
+        let report = validate_build_with(&validation, &options)?;
+        assert_eq!(report.matches.len(), 1);
+        assert_eq!(report.matches[0].match_type, MatchType::Exact);
+        assert_eq!(report.matches[0].similarity, 1.0);
+
+        // Trailing whitespace needs its own option
+        let lines = numbered_lines(None);
+        let padded = validation_with(&lines, &format!("{}\n", lines.replacen("line 0", "line 0  ", 1)));
+        assert_eq!(validate_build_with(&padded, &options)?.matches[0].match_type, MatchType::Partial);
+        let options = ValidationOptions { ignore_whitespace: true, ..options };
+        assert_eq!(validate_build_with(&padded, &options)?.matches[0].match_type, MatchType::Exact);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_build_assertions() -> Result<()> {
+        let mut validation = BuildValidation::new("response".to_string(), PathBuf::from("build"));
+        let cargo_toml = "[package]\nname = \"app\"\nedition = \"2021\"\n";
+        validation.add_file("Cargo.toml".to_string(), cargo_toml.to_string(), cargo_toml.len() as u64, false);
+        validation.assert_contains("Cargo.toml".to_string(), "edition = \"2021\"".to_string());
+        validation.assert_contains("Cargo.toml".to_string(), r#"name = "\w+""#.to_string());
+
+        let report = validate_build(&validation)?;
+        assert!(report.mismatches.is_empty(), "{:?}", report.mismatches);
+
+        validation.assert_contains("Cargo.toml".to_string(), "version = ".to_string());
+        validation.assert_contains("src/main.rs".to_string(), "fn main".to_string());
+        let report = validate_build(&validation)?;
+        let failed: Vec<_> = report.mismatches.iter()
+            .map(|m| (m.file_path.as_str(), m.expected.as_str(), m.match_type.clone()))
+            .collect();
+        assert_eq!(failed, vec![
+            ("Cargo.toml", "version = ", MatchType::AssertionFailed),
+            ("src/main.rs", "fn main", MatchType::AssertionFailed),
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validation_report_round_trip() -> Result<()> {
+        let dir = tempdir()?;
+        let storage = Storage::new(dir.path())?;
+
+        let mut validation = BuildValidation::new("response".to_string(), PathBuf::from("build/app"));
+        validation.add_file("src/main.rs".to_string(), "fn main() {}".to_string(), 12, false);
+        validation.expect_file("src/main.rs".to_string(), "fn main() {}".to_string());
+        validation.expect_file("Cargo.toml".to_string(), "[package]".to_string());
+        let report = validate_build(&validation)?;
+
+        let validation_key = "build_validation_20240101_090000";
+        let key = report.save(&storage, validation_key)?;
+        assert_eq!(key, "validation_report_20240101_090000");
+
+        let loaded = ValidationReport::load(&storage, validation_key)?.expect("report was stored");
+        assert_eq!(loaded.build_path, report.build_path);
+        assert_eq!(loaded.timestamp, report.timestamp);
+        assert_eq!(loaded.matches.len(), 1);
+        assert_eq!(loaded.mismatches.len(), 1);
+        assert_eq!(loaded.mismatches[0].file_path, "Cargo.toml");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_capture_build_output_skips_ignored_paths() -> Result<()> {
+        let dir = tempdir()?;
+        std::fs::create_dir_all(dir.path().join("src"))?;
+        std::fs::write(dir.path().join("src/main.rs"), "fn main() {}")?;
+        std::fs::create_dir_all(dir.path().join("target/debug"))?;
+        std::fs::write(dir.path().join("target/debug/app"), "binary")?;
+        std::fs::create_dir_all(dir.path().join(".git"))?;
+        std::fs::write(dir.path().join(".git/HEAD"), "ref: refs/heads/main")?;
+        std::fs::write(dir.path().join("notes.log"), "scratch")?;
+        std::fs::write(dir.path().join(BUILD_IGNORE_FILE), "*.log\n")?;
+
+        let validation = capture_build_output(dir.path().to_path_buf(), "response".to_string())?;
+        let mut captured: Vec<&str> = validation.files.keys().map(String::as_str).collect();
+        captured.sort();
+        assert_eq!(captured, ["src", "src/main.rs"]);
+
+        let options = CaptureOptions { include_all: true };
+        let validation = capture_build_output_with(dir.path().to_path_buf(), "response".to_string(), &options)?;
+        for path in ["target/debug/app", ".git/HEAD", "notes.log", BUILD_IGNORE_FILE, "src/main.rs"] {
+            assert!(validation.files.contains_key(path), "{} not captured", path);
+        }
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_capture_build_output_records_unreadable_dir() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir()?;
+        std::fs::write(dir.path().join("readable.txt"), "ok")?;
+        let locked = dir.path().join("locked");
+        std::fs::create_dir(&locked)?;
+        std::fs::write(locked.join("secret.txt"), "hidden")?;
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o000))?;
+
+        // Privileged users ignore directory permissions, so there is nothing to test
+        if std::fs::read_dir(&locked).is_ok() {
+            std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755))?;
+            return Ok(());
+        }
+
+        let result = capture_build_output(dir.path().to_path_buf(), "response".to_string());
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o755))?;
+        let validation = result?;
+
+        assert_eq!(validation.files["readable.txt"].content, "ok");
+        assert!(validation.files.contains_key("locked"));
+        assert!(!validation.files.contains_key("locked/secret.txt"));
+        assert_eq!(validation.errors.len(), 1);
+        assert_eq!(validation.errors[0].0, "locked");
+
+        Ok(())
+    }
+}
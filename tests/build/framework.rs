@@ -35,9 +35,11 @@ impl TestContext {
                 priority: 1,
                 tags: vec!["test".to_string()],
                 additional_info: HashMap::new(),
+                working_dir: None,
             },
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            history: Vec::new(),
         };
 
         self.state_manager.create_task(task).await.map_err(BuildError::StateError)
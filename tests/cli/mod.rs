@@ -81,3 +81,32 @@ mod llm_tests {
         Ok(())
     }
 }
+
+/// Tests driving the `tools doc` subcommands through the CLI entry point
+#[cfg(test)]
+mod doc_cli_tests {
+    use super::*;
+    use build_system::cli::{Cli, handle_cli_command};
+    use clap::Parser;
+
+    async fn run(args: &[&str]) -> Result<()> {
+        let cli = Cli::try_parse_from(["build-system", "--no-color", "tools", "doc"].iter().chain(args))?;
+        handle_cli_command(cli).await
+    }
+
+    #[tokio::test]
+    async fn test_doc_create_and_read() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("docs/README.md");
+        let path_arg = path.to_str().unwrap();
+
+        run(&["create", "--path", path_arg, "--title", "Widget", "--type", "project-overview", "--content", "Builds widgets"]).await?;
+        let written = std::fs::read_to_string(&path)?;
+        assert!(written.starts_with("# Widget\n"));
+        assert!(written.contains("Builds widgets"));
+
+        run(&["read", "--path", path_arg]).await?;
+        assert!(run(&["read", "--path", temp_dir.path().join("missing.md").to_str().unwrap()]).await.is_err());
+        Ok(())
+    }
+}
@@ -43,9 +43,11 @@ mod tests {
                 priority: 1,
                 tags: vec!["test".to_string()],
                 additional_info: HashMap::new(),
+                working_dir: None,
             },
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            history: Vec::new(),
         };
 
         state_manager.create_task(task).await.map_err(BuildError::StateError)?;
@@ -76,9 +78,11 @@ mod tests {
                 priority: 1,
                 tags: vec!["test".to_string()],
                 additional_info: HashMap::new(),
+                working_dir: None,
             },
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            history: Vec::new(),
         };
 
         state_manager.create_task(task.clone()).await.map_err(BuildError::StateError)?;
@@ -0,0 +1,99 @@
+//! PostgreSQL state backend tests.
+//!
+//! Run with `cargo test --features postgres` and `TEST_DATABASE_URL` pointing at a
+//! disposable database; the tests skip themselves when the variable is unset.
+#![cfg(feature = "postgres")]
+
+use build_system::state::error::StateError;
+use build_system::state::types::{TaskId, TaskState, TaskStatus};
+use build_system::state::{PgStateStore, StateManager, StateStore};
+
+async fn test_store() -> Option<PgStateStore> {
+    let url = match std::env::var("TEST_DATABASE_URL") {
+        Ok(url) => url,
+        Err(_) => {
+            eprintln!("TEST_DATABASE_URL not set; skipping PostgreSQL test");
+            return None;
+        }
+    };
+    Some(PgStateStore::connect(&url).await.expect("connect to test database"))
+}
+
+/// Task ids are namespaced per test so tests can share one database
+fn task(prefix: &str, id: &str) -> TaskState {
+    let mut task = TaskState::new(TaskId::new(&format!("{}-{}", prefix, id)));
+    task.metadata.owner = "pg-test".to_string();
+    task
+}
+
+async fn cleanup(store: &PgStateStore, prefix: &str) {
+    sqlx::query("DELETE FROM tasks WHERE id LIKE $1")
+        .bind(format!("{}-%", prefix))
+        .execute(store.pool())
+        .await
+        .expect("clean up test rows");
+}
+
+#[tokio::test]
+async fn test_pg_store_crud() -> Result<(), StateError> {
+    let Some(store) = test_store().await else { return Ok(()) };
+    let prefix = format!("crud-{}", uuid::Uuid::new_v4());
+
+    let created = task(&prefix, "a");
+    store.create(created.clone()).await?;
+    assert!(matches!(
+        store.create(created.clone()).await,
+        Err(StateError::TaskAlreadyExists(_))
+    ));
+
+    let fetched = store.get(&created.id).await?;
+    assert_eq!(fetched.id, created.id);
+    assert_eq!(fetched.metadata.owner, "pg-test");
+
+    store.update_status(&created.id, TaskStatus::Running).await?;
+    assert_eq!(store.get(&created.id).await?.status, TaskStatus::Running);
+
+    let listed = store.list().await?;
+    assert!(listed.iter().any(|t| t.id == created.id));
+
+    store.delete(&created.id).await?;
+    assert!(matches!(store.get(&created.id).await, Err(StateError::TaskNotFound(_))));
+    assert!(matches!(store.delete(&created.id).await, Err(StateError::TaskNotFound(_))));
+
+    cleanup(&store, &prefix).await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pg_store_create_many_is_atomic() -> Result<(), StateError> {
+    let Some(store) = test_store().await else { return Ok(()) };
+    let prefix = format!("batch-{}", uuid::Uuid::new_v4());
+
+    store.create(task(&prefix, "existing")).await?;
+    let batch = vec![task(&prefix, "new"), task(&prefix, "existing")];
+    assert!(store.create_many(batch).await.is_err());
+    assert!(matches!(
+        store.get(&TaskId::new(&format!("{}-new", prefix))).await,
+        Err(StateError::TaskNotFound(_))
+    ));
+
+    cleanup(&store, &prefix).await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_state_manager_over_pg_store() -> Result<(), StateError> {
+    let Some(store) = test_store().await else { return Ok(()) };
+    let prefix = format!("manager-{}", uuid::Uuid::new_v4());
+    let manager = StateManager::with_store(store);
+
+    let created = task(&prefix, "managed");
+    manager.create_task(created.clone()).await?;
+    manager.update_task_status(&created.id, TaskStatus::Completed).await?;
+
+    let completed = manager.get_tasks_by_status(TaskStatus::Completed).await?;
+    assert!(completed.iter().any(|t| t.id == created.id));
+
+    cleanup(manager.store(), &prefix).await;
+    Ok(())
+}
@@ -35,6 +35,7 @@ mod tests {
             priority: 1,
             tags: vec!["test".to_string()],
             additional_info: HashMap::new(),
+            working_dir: None,
         };
 
         assert_eq!(task.id, task_id);
@@ -61,6 +62,7 @@ mod tests {
             priority: 1,
             tags: vec!["test".to_string()],
             additional_info: HashMap::new(),
+            working_dir: None,
         };
 
         assert_eq!(task.metadata.name, "Test Task test-1");
@@ -86,7 +88,9 @@ mod tests {
                 estimated_duration: Duration::from_micros(1),
                 dependencies: vec![],
                 additional_info: HashMap::new(),
+                working_dir: None,
             },
+            history: Vec::new(),
         };
 
         state_manager.create_task(task.clone()).await?;
@@ -116,7 +120,9 @@ mod tests {
                 estimated_duration: Duration::from_micros(1),
                 dependencies: vec![],
                 additional_info: HashMap::new(),
+                working_dir: None,
             },
+            history: Vec::new(),
         };
 
         // Create initial task
@@ -149,7 +155,9 @@ mod tests {
                 estimated_duration: Duration::from_micros(1),
                 dependencies: vec![],
                 additional_info: HashMap::new(),
+                working_dir: None,
             },
+            history: Vec::new(),
         };
         let mut task2 = TaskState {
             id: TaskId::new("test-4"),
@@ -165,7 +173,9 @@ mod tests {
                 estimated_duration: Duration::from_micros(1),
                 dependencies: vec![],
                 additional_info: HashMap::new(),
+                working_dir: None,
             },
+            history: Vec::new(),
         };
 
         // Add task1 as dependency for task2
@@ -198,7 +208,9 @@ mod tests {
                 estimated_duration: Duration::from_micros(1),
                 dependencies: vec![],
                 additional_info: HashMap::new(),
+                working_dir: None,
             },
+            history: Vec::new(),
         };
 
         // Create and then delete task
@@ -229,7 +241,9 @@ mod tests {
                 estimated_duration: Duration::from_micros(1),
                 dependencies: vec![],
                 additional_info: HashMap::new(),
+                working_dir: None,
             },
+            history: Vec::new(),
         };
 
         manager.create_task(task.clone()).await?;
@@ -258,14 +272,16 @@ mod tests {
                 estimated_duration: Duration::from_micros(1),
                 dependencies: vec![],
                 additional_info: HashMap::new(),
+                working_dir: None,
             },
+            history: Vec::new(),
         };
 
         manager.create_task(task.clone()).await?;
         manager.delete_task(&task.id).await?;
 
         let result = manager.get_task(&task.id).await;
-        assert!(matches!(result, Err(StateError::TaskNotFound(_))));
+        assert!(matches!(result, Err(StateError::TaskDeleted(_))));
 
         Ok(())
     }
@@ -287,7 +303,9 @@ mod tests {
                 estimated_duration: Duration::from_micros(1),
                 dependencies: vec![],
                 additional_info: HashMap::new(),
+                working_dir: None,
             },
+            history: Vec::new(),
         };
         let task2 = TaskState {
             id: TaskId::new("test-5"),
@@ -303,7 +321,9 @@ mod tests {
                 estimated_duration: Duration::from_micros(1),
                 dependencies: vec![],
                 additional_info: HashMap::new(),
+                working_dir: None,
             },
+            history: Vec::new(),
         };
 
         manager.create_task(task1.clone()).await?;
@@ -332,7 +352,9 @@ mod tests {
                 estimated_duration: Duration::from_micros(1),
                 dependencies: vec![],
                 additional_info: HashMap::new(),
+                working_dir: None,
             },
+            history: Vec::new(),
         };
 
         // Test create and get
@@ -353,7 +375,7 @@ mod tests {
         manager.delete_task(&task.id).await?;
         assert!(matches!(
             manager.get_task(&task.id).await,
-            Err(StateError::TaskNotFound(_))
+            Err(StateError::TaskDeleted(_))
         ));
 
         Ok(())